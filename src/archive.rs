@@ -0,0 +1,192 @@
+use std::path::{Path, PathBuf};
+
+use crate::{atomic::DirLock, pipeline::CircuitPaths};
+
+/// Archive formats a circuit can be distributed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar,
+    TarZstd,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Recognizes `.tar`, `.tar.zst` (or `.tzst`) and `.zip` files by name
+    pub fn detect(path: impl AsRef<Path>) -> Option<Self> {
+        let name = path.as_ref().file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".tar.zst") || name.ends_with(".tzst") {
+            Some(ArchiveFormat::TarZstd)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// A circuit unpacked from an archive, in the `CircuitPaths` layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedCircuit {
+    /// Directory holding the `.r1cs`, which is what `--circuits-dir` would point at
+    pub dir: PathBuf,
+    /// Circuit name, taken from the `.r1cs` file stem
+    pub name: String,
+}
+
+impl ExtractedCircuit {
+    pub fn paths(&self) -> CircuitPaths {
+        CircuitPaths::new(&self.dir, &self.name)
+    }
+}
+
+/// Where archives are unpacked unless the caller picks a directory
+pub fn default_cache_dir() -> PathBuf {
+    std::env::temp_dir().join("circom-bp-archives")
+}
+
+/// Unpacks a circuit archive under `cache_dir` and locates the circuit inside it
+///
+/// The archive holds one `.r1cs` plus the files next to it that `CircuitPaths`
+/// expects (`<name>_js/<name>.wasm`, optionally `<name>.sym` and
+/// `<name>_inputs.json`), possibly below a top-level directory. Archives are
+/// unpacked into a directory named after their content hash, so repeated runs
+/// reuse the first extraction.
+pub fn extract(
+    archive: impl AsRef<Path>,
+    cache_dir: impl AsRef<Path>,
+) -> Result<ExtractedCircuit, ArchiveError> {
+    let archive = archive.as_ref();
+    let format = ArchiveFormat::detect(archive)
+        .ok_or_else(|| ArchiveError::Unsupported(archive.to_path_buf()))?;
+    let bytes = std::fs::read(archive)?;
+    let key = hex::encode(&blake3::hash(&bytes).as_bytes()[..16]);
+    let cache_dir = cache_dir.as_ref();
+    let dest = cache_dir.join(&key);
+
+    // Concurrent provers wait for the first one to finish unpacking
+    let _lock = DirLock::acquire(cache_dir)?;
+    if !dest.exists() {
+        // Unpack beside the destination and rename, so an interrupted run never
+        // leaves a half-extracted circuit behind to be reused
+        let partial = cache_dir.join(format!("{key}.partial-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&partial);
+        std::fs::create_dir_all(&partial)?;
+        unpack(format, &bytes, &partial)?;
+        std::fs::rename(&partial, &dest)?;
+    }
+    locate(&dest)
+}
+
+fn unpack(format: ArchiveFormat, bytes: &[u8], dest: &Path) -> Result<(), ArchiveError> {
+    match format {
+        // `unpack` refuses entries escaping `dest`
+        ArchiveFormat::Tar => tar::Archive::new(bytes).unpack(dest)?,
+        ArchiveFormat::TarZstd => tar::Archive::new(zstd::Decoder::new(bytes)?).unpack(dest)?,
+        ArchiveFormat::Zip => zip::ZipArchive::new(std::io::Cursor::new(bytes))
+            .and_then(|mut zip| zip.extract(dest))
+            .map_err(|e| ArchiveError::Zip(e.to_string()))?,
+    }
+    Ok(())
+}
+
+/// Finds the single `.r1cs` below `dir`
+fn locate(dir: &Path) -> Result<ExtractedCircuit, ArchiveError> {
+    let mut found = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "r1cs") {
+                found.push(path);
+            }
+        }
+    }
+
+    let [r1cs] = found.as_slice() else {
+        return Err(ArchiveError::R1csCount(found.len()));
+    };
+    let extracted = ExtractedCircuit {
+        dir: r1cs.parent().unwrap_or(dir).to_path_buf(),
+        name: r1cs
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    };
+    let wasm = extracted.paths().wasm;
+    if !wasm.exists() {
+        return Err(ArchiveError::MissingFile(
+            wasm.strip_prefix(dir).unwrap_or(&wasm).to_path_buf(),
+        ));
+    }
+    Ok(extracted)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("Not a circuit archive (.tar, .tar.zst or .zip): {}", .0.display())]
+    Unsupported(PathBuf),
+    #[error("Failed to read circuit archive: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to read zip archive: {0}")]
+    Zip(String),
+    #[error("Circuit archive must contain exactly one .r1cs file, found {0}")]
+    R1csCount(usize),
+    #[error("Circuit archive is missing {}", .0.display())]
+    MissingFile(PathBuf),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tar_zst(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *contents).unwrap();
+        }
+        zstd::encode_all(builder.into_inner().unwrap().as_slice(), 0).unwrap()
+    }
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(
+            ArchiveFormat::detect("c.bundle.tar.zst"),
+            Some(ArchiveFormat::TarZstd)
+        );
+        assert_eq!(ArchiveFormat::detect("c.zip"), Some(ArchiveFormat::Zip));
+        assert_eq!(ArchiveFormat::detect("multiplier2"), None);
+    }
+
+    #[test]
+    fn extracts_nested_circuit() {
+        let root =
+            std::env::temp_dir().join(format!("circom-bp-archive-test-{}", std::process::id()));
+        let archive = root.join("mult.bundle.tar.zst");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(
+            &archive,
+            tar_zst(&[
+                ("mult/mult.r1cs", b"r1cs"),
+                ("mult/mult_js/mult.wasm", b"wasm"),
+                ("mult/mult_inputs.json", b"{}"),
+            ]),
+        )
+        .unwrap();
+
+        let extracted = extract(&archive, root.join("cache")).unwrap();
+        assert_eq!(extracted.name, "mult");
+        assert!(extracted.paths().inputs.exists());
+        // A second run reuses the extraction
+        assert_eq!(extract(&archive, root.join("cache")).unwrap(), extracted);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Name of the lock file taken in directories that artifacts are written to
+pub const LOCK_FILE: &str = ".circom-bp.lock";
+
+/// An exclusive advisory lock on a directory, released when dropped
+///
+/// Every process writing artifacts through this module takes the lock of the target
+/// directory first, so provers sharing an output or cache directory take turns.
+#[derive(Debug)]
+pub struct DirLock {
+    _file: File,
+}
+
+impl DirLock {
+    /// Blocks until the lock of `dir` is held, creating the directory if needed
+    pub fn acquire(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(dir.join(LOCK_FILE))?;
+        file.lock()?;
+        Ok(Self { _file: file })
+    }
+}
+
+/// Replaces the file at `path` with `contents` in one step
+///
+/// The contents go to a temporary file beside `path`, which is synced and renamed
+/// over it under the directory's `DirLock`. Readers see either the old or the new
+/// file, never a partial one, and an interrupted write leaves `path` untouched.
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    write_inner(path.as_ref(), contents.as_ref(), None)
+}
+
+/// Like `write`, creating the file with the Unix permissions `mode`, e.g. `0o600`
+/// for secrets; other platforms ignore the mode
+///
+/// The temporary file already has the mode, so the contents are never readable
+/// under looser permissions.
+pub fn write_with_mode(
+    path: impl AsRef<Path>,
+    contents: impl AsRef<[u8]>,
+    mode: u32,
+) -> io::Result<()> {
+    write_inner(path.as_ref(), contents.as_ref(), Some(mode))
+}
+
+fn write_inner(path: &Path, contents: &[u8], mode: Option<u32>) -> io::Result<()> {
+    let _lock = DirLock::acquire(parent_dir(path))?;
+    let temp = temp_path(path);
+    let result = write_synced(&temp, contents, mode).and_then(|()| std::fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+fn write_synced(path: &Path, contents: &[u8], mode: Option<u32>) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, mode);
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// The directory holding `path`, which is `.` for bare file names
+pub fn parent_dir(path: &Path) -> &Path {
+    path.parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+}
+
+/// A hidden sibling of `path`, unique across processes and threads
+fn temp_path(path: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    parent_dir(path).join(format!(
+        ".{name}.tmp-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_writers_leave_one_complete_file() {
+        let dir =
+            std::env::temp_dir().join(format!("circom-bp-atomic-test-{}", std::process::id()));
+        let path = dir.join("proof.bin");
+        let contents: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i; 1 << 16]).collect();
+
+        let path = &path;
+        std::thread::scope(|scope| {
+            for bytes in &contents {
+                scope.spawn(move || write(path, bytes).unwrap());
+            }
+        });
+
+        let written = std::fs::read(path).unwrap();
+        assert!(contents.contains(&written));
+        // Only the artifact and the lock file remain
+        let mut names: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, [LOCK_FILE, "proof.bin"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_with_the_requested_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("circom-bp-mode-test-{}", std::process::id()));
+        let path = dir.join("secret.json");
+        write_with_mode(&path, b"{}", 0o600).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use bulletproofs::circuit::types::Witness;
+use serde::Serialize;
+
+use crate::{r1cs::ONE_WIRE, template::InputLayout};
+
+/// Values of at most this many bits are recovered from an unblinded commitment
+/// `v·G` with a baby-step giant-step search
+pub const INVERTIBLE_BITS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// Private variables are committed without blinding
+    ZeroBlinding,
+    /// A private input's commitment is `v·G`, so guesses of its value can be confirmed
+    PrivateSignalExposed,
+    /// A commitment is `v·G` for a small `v`, which can be found by search
+    InvertibleCommitment,
+}
+
+/// A way in which a proof statement leaks the witness it was built from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub kind: FindingKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variable: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal: Option<String>,
+    pub detail: String,
+}
+
+/// Checks whether the statement commitments `V_i = v_i·G + γ_i·H` of a witness
+/// hide its private values
+///
+/// `variables` is the R1CS variable of each statement position, as given by
+/// `conversion::statement_variables`. The public outputs and inputs are skipped
+/// since the verifier learns them anyway; private variables
+/// are only in the statement when committed on request. `names` labels findings
+/// with signal names where known.
+pub fn audit(
+    witness: &Witness<Fr>,
+    variables: &[usize],
+    layout: &InputLayout,
+    names: &HashMap<usize, &str>,
+) -> Vec<Finding> {
+    let public = ONE_WIRE + 1 + layout.outputs + layout.public_inputs;
+    let private_inputs = public..public + layout.private_inputs;
+    let committed: Vec<(usize, usize)> = variables
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(position, variable)| variable >= public && position < witness.v.len())
+        .collect();
+    let unblinded: Vec<(usize, usize)> = committed
+        .iter()
+        .copied()
+        .filter(|&(position, _)| witness.gamma.get(position).is_none_or(Zero::is_zero))
+        .collect();
+
+    let mut findings = Vec::new();
+    if !unblinded.is_empty() {
+        findings.push(Finding {
+            kind: FindingKind::ZeroBlinding,
+            variable: None,
+            signal: None,
+            detail: format!(
+                "{} of {} private variables are committed with zero blinding, so their commitments are not hiding",
+                unblinded.len(),
+                committed.len()
+            ),
+        });
+    }
+    for (position, variable) in unblinded {
+        let small = witness.v[position].into_bigint().num_bits() as usize <= INVERTIBLE_BITS;
+        let (kind, detail) = if private_inputs.contains(&variable) {
+            let consequence = if small {
+                "its value is recoverable by search"
+            } else {
+                "any guess of its value can be confirmed"
+            };
+            (
+                FindingKind::PrivateSignalExposed,
+                format!("private input is committed as v·G; {consequence}"),
+            )
+        } else if small {
+            (
+                FindingKind::InvertibleCommitment,
+                format!(
+                    "commitment is v·G with v below 2^{INVERTIBLE_BITS}; its value is recoverable by search"
+                ),
+            )
+        } else {
+            continue;
+        };
+        findings.push(Finding {
+            kind,
+            variable: Some(variable),
+            signal: names.get(&variable).map(|name| name.to_string()),
+            detail,
+        });
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `c <== a * b` over variables [1, c, a, b], with the private `a` and `b`
+    /// committed on request
+    fn witness(gamma: [u64; 3]) -> Witness<Fr> {
+        Witness {
+            a_l: vec![Fr::zero(); 4],
+            a_r: vec![Fr::zero(); 4],
+            a_o: vec![Fr::zero(); 4],
+            v: [33u64, 3, 11].map(Fr::from).to_vec(),
+            gamma: gamma.map(Fr::from).to_vec(),
+        }
+    }
+
+    const LAYOUT: InputLayout = InputLayout {
+        outputs: 1,
+        public_inputs: 0,
+        private_inputs: 2,
+    };
+
+    #[test]
+    fn flags_unblinded_private_inputs() {
+        let names = HashMap::from([(2, "main.a")]);
+        let findings = audit(&witness([0; 3]), &[1, 2, 3], &LAYOUT, &names);
+
+        let kinds: Vec<_> = findings.iter().map(|finding| finding.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                FindingKind::ZeroBlinding,
+                FindingKind::PrivateSignalExposed,
+                FindingKind::PrivateSignalExposed
+            ]
+        );
+        assert_eq!(findings[1].signal.as_deref(), Some("main.a"));
+        assert_eq!(findings[2].signal, None);
+    }
+
+    #[test]
+    fn blinded_witness_is_clean() {
+        assert!(audit(&witness([0, 5, 7]), &[1, 2, 3], &LAYOUT, &HashMap::new()).is_empty());
+        // Without a request only `c` is committed
+        let mut witness = witness([0; 3]);
+        witness.v.truncate(1);
+        witness.gamma.truncate(1);
+        assert!(audit(&witness, &[1], &LAYOUT, &HashMap::new()).is_empty());
+    }
+}
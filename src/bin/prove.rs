@@ -0,0 +1,137 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use circom_bp::{
+    bundle::{BundleError, ProofBundle},
+    conversion::{blind, circom_to_bulletproofs, public_signal_count},
+    crs::{self, CrsError, CrsParams},
+    curve::{Curve, CurveError},
+    encryption::InputsKey,
+    exit_codes,
+    fingerprint::HashAlgorithm,
+    inputs::{self, InputError, UnknownInputs},
+    pipeline::{self, CircuitPaths, PipelineError},
+};
+use clap::Parser;
+use rand::rngs::OsRng;
+use tracing::info;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser)]
+#[command(
+    name = "circom-bp-prove",
+    version,
+    about = "Prove a compiled Circom circuit with Bulletproofs"
+)]
+struct Cli {
+    /// Circuit name, e.g. `multiplier2`
+    circuit: String,
+    /// Directory holding the compiled `.r1cs`, `_js/` wasm and `_inputs.json` files
+    #[arg(long, default_value = "./circuits")]
+    circuits_dir: PathBuf,
+    /// Inputs file, instead of `<circuits-dir>/<circuit>_inputs.json`; `-` reads stdin.
+    /// Encrypted inputs are decrypted with the key in `CIRCOM_BP_INPUTS_KEY`
+    #[arg(long)]
+    inputs: Option<PathBuf>,
+    /// Reject input values at or above the field modulus instead of reducing them
+    #[arg(long)]
+    strict_inputs: bool,
+    /// Hex-encoded 32-byte seed the CRS is derived from
+    #[arg(long)]
+    crs_seed: Option<String>,
+    /// File the proof bundle is written to
+    #[arg(long, short, default_value = "proof.json")]
+    out: PathBuf,
+}
+
+fn main() -> ExitCode {
+    init_logging();
+    match prove(&Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            tracing::error!("{e}");
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+fn prove(cli: &Cli) -> Result<(), ProveError> {
+    let mut paths = CircuitPaths::new(&cli.circuits_dir, &cli.circuit);
+    if let Some(inputs) = &cli.inputs {
+        paths.inputs = inputs.clone();
+    }
+    Curve::Bn254.check_r1cs(&paths.r1cs)?;
+    let seed = match &cli.crs_seed {
+        Some(seed) => crs::parse_seed(seed)?,
+        None => crs::DEFAULT_SEED,
+    };
+
+    // Like `circom-bp prove`, a missing default inputs file means no inputs
+    let inputs_json = if cli.inputs.is_none() && !paths.inputs.exists() {
+        "{}".to_string()
+    } else {
+        let key = InputsKey::from_env().map_err(InputError::from)?;
+        inputs::read_inputs_decrypted(&paths.inputs, key.as_ref())?
+    };
+    let json = inputs::merge_inputs(&inputs_json, None, &[])?;
+    let mut inputs = inputs::flatten_inputs(&json, cli.strict_inputs)?;
+    pipeline::check_inputs(&paths, &json, &mut inputs, UnknownInputs::Error)?;
+    let circom = pipeline::generate_witness(pipeline::load_config(&paths)?, &inputs)?;
+    let (circuit, mut witness, _) = circom_to_bulletproofs(&circom).map_err(PipelineError::from)?;
+    let mut rng = OsRng;
+    blind(&mut witness, public_signal_count(&circom.r1cs), &mut rng);
+
+    let crs_params = CrsParams::with_seed(circuit.dim(), seed);
+    info!("Generating CRS with size: {}", crs_params.size);
+    let crs = crs_params.derive();
+    info!("Generating proof...");
+    let (statement, proof) = pipeline::prove(&crs, &circuit, &witness, &mut rng)?;
+    pipeline::verify(&crs, &circuit, &statement, &proof, &mut rng)?;
+
+    ProofBundle::new(&cli.circuit, crs_params, &statement, &proof)?
+        .with_circuit_fingerprint(HashAlgorithm::Blake3.digest_file(&paths.r1cs)?)
+        .with_public_signals(pipeline::public_signals(&circom))
+        .write(&cli.out)?;
+    info!("✅ Wrote proof bundle to {}", cli.out.display());
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+enum ProveError {
+    #[error(transparent)]
+    Pipeline(#[from] PipelineError),
+    #[error(transparent)]
+    Crs(#[from] CrsError),
+    #[error(transparent)]
+    Curve(#[from] CurveError),
+    #[error(transparent)]
+    Bundle(#[from] BundleError),
+    #[error("Failed to fingerprint circuit: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<InputError> for ProveError {
+    fn from(e: InputError) -> Self {
+        ProveError::Pipeline(e.into())
+    }
+}
+
+impl ProveError {
+    fn exit_code(&self) -> u8 {
+        match self {
+            ProveError::Pipeline(e) => exit_codes::for_category(e.category()),
+            ProveError::Crs(_) | ProveError::Curve(_) | ProveError::Io(_) => exit_codes::INPUT,
+            ProveError::Bundle(_) => exit_codes::FAILURE,
+        }
+    }
+}
+
+fn init_logging() {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .without_time()
+        .init();
+}
@@ -0,0 +1,113 @@
+use std::{collections::HashMap, path::PathBuf, process::ExitCode};
+
+use circom_bp::{
+    bundle::ProofBundle,
+    conversion::{public_signal_count, r1cs_to_bulletproofs},
+    exit_codes,
+    pipeline::{self, CircuitPaths},
+};
+use clap::Parser;
+use rand::rngs::OsRng;
+use tracing::{error, info};
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser)]
+#[command(
+    name = "circom-bp-verify",
+    version,
+    about = "Verify Bulletproofs proof bundles of a compiled Circom circuit"
+)]
+struct Cli {
+    /// Circuit name, e.g. `multiplier2`
+    circuit: String,
+    /// Proof bundles to verify, or directories written by `prove --output-dir`
+    #[arg(required = true)]
+    bundles: Vec<PathBuf>,
+    /// Directory holding the compiled `.r1cs` file
+    #[arg(long, default_value = "./circuits")]
+    circuits_dir: PathBuf,
+}
+
+fn main() -> ExitCode {
+    init_logging();
+    let cli = Cli::parse();
+    let r1cs_path = CircuitPaths::new(&cli.circuits_dir, &cli.circuit).r1cs;
+    let loaded = std::fs::read(&r1cs_path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| {
+            let r1cs = pipeline::load_r1cs(&r1cs_path).map_err(|e| e.to_string())?;
+            let circuit = r1cs_to_bulletproofs(&r1cs).map_err(|e| e.to_string())?;
+            Ok((bytes, circuit, public_signal_count(&r1cs)))
+        });
+    let (r1cs_bytes, circuit, public) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            error!("Failed to load {}: {e}", r1cs_path.display());
+            return ExitCode::from(exit_codes::INPUT);
+        }
+    };
+
+    let mut crs_cache = HashMap::new();
+    let mut rng = OsRng;
+    let mut failed = 0;
+    for path in &cli.bundles {
+        let result = (|| -> Result<(), Box<dyn std::error::Error>> {
+            let bundle = ProofBundle::load(path)?;
+            if bundle.circuit != cli.circuit {
+                return Err(format!("bundle is for circuit '{}'", bundle.circuit).into());
+            }
+            if let Some(expected) = &bundle.circuit_fingerprint {
+                let actual = expected.algorithm.digest(&r1cs_bytes);
+                if actual != *expected {
+                    return Err(
+                        format!("circuit fingerprint {expected} does not match {actual}").into(),
+                    );
+                }
+            }
+            if bundle.crs.size != circuit.dim() {
+                return Err(format!(
+                    "CRS size {} does not match circuit dimension {}",
+                    bundle.crs.size,
+                    circuit.dim()
+                )
+                .into());
+            }
+            let crs = crs_cache
+                .entry(bundle.crs)
+                .or_insert_with(|| bundle.crs.derive());
+            let statement = bundle.statement()?;
+            bundle.check_public_signals(crs, &statement, public)?;
+            pipeline::verify(crs, &circuit, &statement, &bundle.proof_bytes()?, &mut rng)?;
+            Ok(())
+        })();
+        match result {
+            Ok(()) => info!("✅ {}", path.display()),
+            Err(e) => {
+                failed += 1;
+                error!("❌ {}: {e}", path.display());
+            }
+        }
+    }
+
+    info!(
+        "{} of {} proofs verified",
+        cli.bundles.len() - failed,
+        cli.bundles.len()
+    );
+    if failed > 0 {
+        ExitCode::from(exit_codes::VERIFICATION)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn init_logging() {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .without_time()
+        .init();
+}
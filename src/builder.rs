@@ -0,0 +1,287 @@
+use ark_ff::{BigInteger, PrimeField};
+use bulletproofs::circuit::types::{Circuit, Witness};
+
+/// A wire of a circuit under construction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wire {
+    /// Left input of the i-th multiplication gate
+    Left(usize),
+    /// Right input of the i-th multiplication gate
+    Right(usize),
+    /// Output of the i-th multiplication gate
+    Output(usize),
+    /// The i-th committed value, published as a point of the statement
+    Committed(usize),
+}
+
+/// Wires of a single multiplication gate `left * right = output`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gate {
+    pub left: Wire,
+    pub right: Wire,
+    pub output: Wire,
+}
+
+/// Builds Bulletproofs circuits and their witnesses directly, without circom
+///
+/// Multiplication gates and committed values carry their assignment, so the witness
+/// is produced alongside the circuit. Linear constraints `Σ coeff·wire = constant`
+/// tie gates and commitments together.
+#[derive(Debug, Clone, Default)]
+pub struct CircuitBuilder<F> {
+    a_l: Vec<F>,
+    a_r: Vec<F>,
+    a_o: Vec<F>,
+    v: Vec<F>,
+    gamma: Vec<F>,
+    constraints: Vec<(Vec<(Wire, F)>, F)>,
+}
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a gate assigned `left * right`
+    pub fn add_mul_gate(&mut self, left: F, right: F) -> Gate {
+        let i = self.a_l.len();
+        self.a_l.push(left);
+        self.a_r.push(right);
+        self.a_o.push(left * right);
+        Gate {
+            left: Wire::Left(i),
+            right: Wire::Right(i),
+            output: Wire::Output(i),
+        }
+    }
+
+    /// Commits to `value` with the given blinding factor
+    pub fn commit_value(&mut self, value: F, blinding: F) -> Wire {
+        self.v.push(value);
+        self.gamma.push(blinding);
+        Wire::Committed(self.v.len() - 1)
+    }
+
+    /// Commits to several small values through a single statement point
+    ///
+    /// The values, each below `2^bits`, are packed as `Σ values[j]·2^(bits·j)` into
+    /// one committed value. Unpacking constraints are added automatically: every
+    /// value is decomposed into boolean gates, so the packing is binding. This trades
+    /// `bits + 1` multiplication gates per value for fewer statement points, which
+    /// pays off when many small private inputs would otherwise be committed one by
+    /// one. Returns a wire carrying each unpacked value, in order.
+    pub fn commit_packed(
+        &mut self,
+        values: &[F],
+        bits: u32,
+        blinding: F,
+    ) -> Result<Vec<Wire>, PackingError> {
+        let capacity = F::MODULUS_BIT_SIZE - 1;
+        let width = bits as usize * values.len();
+        if bits == 0 || width > capacity as usize {
+            return Err(PackingError::TooWide {
+                values: values.len(),
+                bits,
+                capacity,
+            });
+        }
+        if let Some(index) = values
+            .iter()
+            .position(|value| value.into_bigint().num_bits() > bits)
+        {
+            return Err(PackingError::ValueTooLarge { index, bits });
+        }
+
+        let shift = F::from(2u64).pow([bits as u64]);
+        let mut packed = F::zero();
+        let mut scale = F::one();
+        let mut packing = Vec::with_capacity(values.len() + 1);
+        let mut unpacked = Vec::with_capacity(values.len());
+        for &value in values {
+            let wire = self.unpack_bits(value, bits);
+            packed += scale * value;
+            packing.push((wire, scale));
+            unpacked.push(wire);
+            scale *= shift;
+        }
+        let committed = self.commit_value(packed, blinding);
+        packing.push((committed, -F::one()));
+        self.add_linear_constraint(&packing, F::zero());
+        Ok(unpacked)
+    }
+
+    /// Allocates `value` as the left input of a gate and constrains it to the sum of
+    /// `bits` boolean gates
+    fn unpack_bits(&mut self, value: F, bits: u32) -> Wire {
+        let holder = self.add_mul_gate(value, F::one());
+        self.add_linear_constraint(&[(holder.right, F::one())], F::one());
+
+        let bigint = value.into_bigint();
+        let mut recomposition = vec![(holder.left, -F::one())];
+        let mut weight = F::one();
+        for i in 0..bits as usize {
+            // b · (b - 1) = 0 forces b ∈ {0, 1}
+            let bit = if bigint.get_bit(i) {
+                F::one()
+            } else {
+                F::zero()
+            };
+            let gate = self.add_mul_gate(bit, bit - F::one());
+            self.add_linear_constraint(
+                &[(gate.right, F::one()), (gate.left, -F::one())],
+                -F::one(),
+            );
+            self.add_linear_constraint(&[(gate.output, F::one())], F::zero());
+            recomposition.push((gate.left, weight));
+            weight.double_in_place();
+        }
+        self.add_linear_constraint(&recomposition, F::zero());
+        holder.left
+    }
+
+    /// Constrains `Σ coeff·wire = constant`
+    pub fn add_linear_constraint(&mut self, terms: &[(Wire, F)], constant: F) {
+        self.constraints.push((terms.to_vec(), constant));
+    }
+
+    /// Number of multiplication gates added so far
+    pub fn num_gates(&self) -> usize {
+        self.a_l.len()
+    }
+
+    /// Produces the circuit and its witness, padding the gates to a power of 2
+    ///
+    /// Each linear constraint becomes one row of
+    /// `W_L·a_L + W_R·a_R + W_O·a_O = W_V·v + c`; committed terms move to the
+    /// right-hand side with their sign flipped.
+    pub fn build(mut self) -> (Circuit<F>, Witness<F>) {
+        let n = self.a_l.len().next_power_of_two();
+        let m = self.v.len();
+        self.a_l.resize(n, F::zero());
+        self.a_r.resize(n, F::zero());
+        self.a_o.resize(n, F::zero());
+
+        let q = self.constraints.len();
+        let mut w_l = vec![vec![F::zero(); n]; q];
+        let mut w_r = vec![vec![F::zero(); n]; q];
+        let mut w_o = vec![vec![F::zero(); n]; q];
+        let mut w_v = vec![vec![F::zero(); m]; q];
+        let mut c = vec![F::zero(); q];
+        for (row, (terms, constant)) in self.constraints.into_iter().enumerate() {
+            for (wire, coeff) in terms {
+                match wire {
+                    Wire::Left(i) => w_l[row][i] += coeff,
+                    Wire::Right(i) => w_r[row][i] += coeff,
+                    Wire::Output(i) => w_o[row][i] += coeff,
+                    Wire::Committed(j) => w_v[row][j] -= coeff,
+                }
+            }
+            c[row] = constant;
+        }
+
+        let circuit = Circuit::new(w_l, w_r, w_o, w_v, c);
+        let witness = Witness {
+            a_l: self.a_l,
+            a_r: self.a_r,
+            a_o: self.a_o,
+            v: self.v,
+            gamma: self.gamma,
+        };
+        (circuit, witness)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PackingError {
+    #[error("Cannot pack {values} values of {bits} bits into one field element of {capacity} bits")]
+    TooWide {
+        values: usize,
+        bits: u32,
+        capacity: u32,
+    },
+    #[error("Value {index} does not fit in {bits} bits")]
+    ValueTooLarge { index: usize, bits: u32 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::{One, Zero};
+
+    /// Proves knowledge of committed `x`, `y` with `x * y = 33`
+    fn product(x: u64, y: u64) -> (Circuit<Fr>, Witness<Fr>) {
+        let mut builder = CircuitBuilder::new();
+        let x_wire = builder.commit_value(Fr::from(x), Fr::from(7u64));
+        let y_wire = builder.commit_value(Fr::from(y), Fr::from(9u64));
+        let gate = builder.add_mul_gate(Fr::from(x), Fr::from(y));
+        builder.add_linear_constraint(&[(gate.left, Fr::one()), (x_wire, -Fr::one())], Fr::zero());
+        builder.add_linear_constraint(&[(gate.right, Fr::one()), (y_wire, -Fr::one())], Fr::zero());
+        builder.add_linear_constraint(&[(gate.output, Fr::one())], Fr::from(33u64));
+        builder.build()
+    }
+
+    #[test]
+    fn satisfied_product() {
+        let (circuit, witness) = product(3, 11);
+        assert!(circuit.is_satisfied_by(&witness));
+        assert_eq!(witness.a_l.len(), 1);
+        assert_eq!(witness.v, vec![Fr::from(3u64), Fr::from(11u64)]);
+    }
+
+    #[test]
+    fn wrong_product_is_unsatisfied() {
+        let (circuit, witness) = product(3, 12);
+        assert!(!circuit.is_satisfied_by(&witness));
+    }
+
+    #[test]
+    fn packed_values_share_one_commitment() {
+        let mut builder = CircuitBuilder::<Fr>::new();
+        let values = [3u64, 11, 200].map(Fr::from);
+        let wires = builder.commit_packed(&values, 8, Fr::from(5u64)).unwrap();
+        // The unpacked wires are usable like any other
+        let gate = builder.add_mul_gate(values[0], values[1]);
+        builder.add_linear_constraint(
+            &[(gate.left, Fr::one()), (wires[0], -Fr::one())],
+            Fr::zero(),
+        );
+        builder.add_linear_constraint(
+            &[(gate.right, Fr::one()), (wires[1], -Fr::one())],
+            Fr::zero(),
+        );
+        builder.add_linear_constraint(&[(gate.output, Fr::one())], Fr::from(33u64));
+        let (circuit, mut witness) = builder.build();
+
+        assert_eq!(witness.v, vec![Fr::from(3u64 + (11 << 8) + (200 << 16))]);
+        assert!(circuit.is_satisfied_by(&witness));
+
+        // Opening the commitment to other values breaks the unpacking constraints
+        witness.v[0] += Fr::one();
+        assert!(!circuit.is_satisfied_by(&witness));
+    }
+
+    #[test]
+    fn packing_checks_widths() {
+        let mut builder = CircuitBuilder::<Fr>::new();
+        assert!(matches!(
+            builder.commit_packed(&[Fr::from(256u64)], 8, Fr::zero()),
+            Err(PackingError::ValueTooLarge { index: 0, bits: 8 })
+        ));
+        assert!(matches!(
+            builder.commit_packed(&[Fr::zero(); 4], 64, Fr::zero()),
+            Err(PackingError::TooWide { .. })
+        ));
+    }
+
+    #[test]
+    fn gates_are_padded_to_a_power_of_two() {
+        let mut builder = CircuitBuilder::<Fr>::new();
+        for i in 0..3u64 {
+            builder.add_mul_gate(Fr::from(i), Fr::from(i));
+        }
+        let (_, witness) = builder.build();
+        assert_eq!(witness.a_l.len(), 4);
+        assert_eq!(witness.a_o[3], Fr::zero());
+    }
+}
@@ -0,0 +1,523 @@
+use std::path::Path;
+
+use ark_bn254::{Fr, G1Projective};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use bulletproofs::circuit::types::{CRS as CircuitCRS, Statement as CircuitStatement};
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    crs::CrsParams,
+    fingerprint::Fingerprint,
+    sink::{FileSink, Sink, SinkError},
+    statement::{self, PublicSignalsError},
+};
+
+/// Current proof bundle format version
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// JSON Schema of the bundle format, published as `schema/proof-bundle.schema.json`
+pub const BUNDLE_SCHEMA: &str = include_str!("../schema/proof-bundle.schema.json");
+
+/// A self-contained proof: the public statement, the proof bytes and the CRS parameters
+///
+/// Points and proof bytes are hex-encoded; points use the compressed arkworks encoding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofBundle {
+    pub version: u32,
+    pub circuit: String,
+    /// Fingerprint of the `.r1cs` file the proof was produced for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_fingerprint: Option<Fingerprint>,
+    pub crs: CrsParams,
+    pub commitments: Vec<String>,
+    /// Public signals as decimal strings, like snarkjs; verifiers check them against
+    /// the statement's first commitments
+    #[serde(default)]
+    pub public_signals: Vec<String>,
+    pub proof: String,
+}
+
+impl ProofBundle {
+    pub fn new(
+        circuit: &str,
+        crs: CrsParams,
+        statement: &CircuitStatement<G1Projective>,
+        proof: &[u8],
+    ) -> Result<Self, BundleError> {
+        let commitments = statement::encode_commitments(statement)?
+            .iter()
+            .map(hex::encode)
+            .collect();
+
+        Ok(Self {
+            version: BUNDLE_VERSION,
+            circuit: circuit.to_string(),
+            circuit_fingerprint: None,
+            crs,
+            commitments,
+            public_signals: Vec::new(),
+            proof: hex::encode(proof),
+        })
+    }
+
+    /// Attaches the public signals the proof claims, e.g. `pipeline::public_signals`
+    pub fn with_public_signals(mut self, public_signals: Vec<String>) -> Self {
+        self.public_signals = public_signals;
+        self
+    }
+
+    pub fn with_circuit_fingerprint(mut self, fingerprint: Fingerprint) -> Self {
+        self.circuit_fingerprint = Some(fingerprint);
+        self
+    }
+
+    /// Decodes the committed statement
+    pub fn statement(&self) -> Result<CircuitStatement<G1Projective>, BundleError> {
+        let encoded = self
+            .commitments
+            .iter()
+            .map(hex::decode)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(statement::decode_commitments(&encoded)?)
+    }
+
+    pub fn proof_bytes(&self) -> Result<Vec<u8>, BundleError> {
+        Ok(hex::decode(&self.proof)?)
+    }
+
+    /// Decodes the public signals, rejecting anything but decimals below the field
+    /// modulus, which would alias another value
+    pub fn public_values(&self) -> Result<Vec<Fr>, BundleError> {
+        self.public_signals
+            .iter()
+            .map(|signal| {
+                parse_public_signal(signal).ok_or_else(|| BundleError::PublicSignal(signal.clone()))
+            })
+            .collect()
+    }
+
+    /// Checks the claimed public signals against `statement`, decoded from this
+    /// bundle, for a circuit with `expected` public signals
+    ///
+    /// Verifying the proof alone leaves the public signals unchecked.
+    pub fn check_public_signals(
+        &self,
+        crs: &CircuitCRS<G1Projective>,
+        statement: &CircuitStatement<G1Projective>,
+        expected: usize,
+    ) -> Result<(), BundleError> {
+        let values = self.public_values()?;
+        Ok(statement::check_public_signals(
+            crs, statement, &values, expected,
+        )?)
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, BundleError> {
+        Self::checked(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Reads the separate files written by `write_dir` back into a bundle
+    pub fn read_dir(dir: impl AsRef<Path>) -> Result<Self, BundleError> {
+        let dir = dir.as_ref();
+        let mut bundle: Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("statement.json"))?)?;
+        let public_signals: Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("public.json"))?)?;
+        if let Value::Object(fields) = &mut bundle {
+            fields.insert(
+                "proof".to_string(),
+                hex::encode(std::fs::read(dir.join("proof.bin"))?).into(),
+            );
+            fields.insert("public_signals".to_string(), public_signals);
+        }
+        Self::checked(serde_json::from_value(bundle)?)
+    }
+
+    /// A bundle file, or a directory written by `write_dir`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BundleError> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            Self::read_dir(path)
+        } else {
+            Self::read(path)
+        }
+    }
+
+    fn checked(bundle: Self) -> Result<Self, BundleError> {
+        if bundle.version != BUNDLE_VERSION {
+            return Err(BundleError::UnsupportedVersion(bundle.version));
+        }
+        Ok(bundle)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), BundleError> {
+        crate::atomic::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Writes the bundle as separate files for transmission to a verifier:
+    /// `proof.bin` (raw proof bytes), `statement.json` (the bundle without its proof
+    /// and public signals) and `public.json` (the public signals, like snarkjs).
+    pub fn write_dir(&self, dir: impl AsRef<Path>) -> Result<(), BundleError> {
+        self.write_parts(&mut FileSink::new(dir.as_ref()))
+    }
+
+    /// Same layout as `write_dir`, into any sink
+    pub fn write_parts(&self, sink: &mut dyn Sink) -> Result<(), BundleError> {
+        let statement = StatementFile {
+            version: self.version,
+            circuit: &self.circuit,
+            circuit_fingerprint: self.circuit_fingerprint.as_ref(),
+            crs: &self.crs,
+            commitments: &self.commitments,
+        };
+        sink.put("proof.bin", &self.proof_bytes()?)?;
+        sink.put(
+            "statement.json",
+            serde_json::to_string_pretty(&statement)?.as_bytes(),
+        )?;
+        sink.put(
+            "public.json",
+            serde_json::to_string_pretty(&self.public_signals)?.as_bytes(),
+        )?;
+        Ok(())
+    }
+
+    /// Stores the whole bundle as a single JSON artifact
+    pub fn put(&self, sink: &mut dyn Sink, name: &str) -> Result<(), BundleError> {
+        sink.put(name, serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// A way in which a bundle departs from `BUNDLE_SCHEMA`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaIssue {
+    /// Location of the offending value, e.g. `crs.seed` or `commitments[2]`
+    pub path: String,
+    pub message: String,
+}
+
+/// Checks the structure of a bundle against `BUNDLE_SCHEMA` without any cryptography
+///
+/// Versions, required fields, hex encodings and lengths are checked, so
+/// submissions can be linted cheaply at ingestion; whether the points lie on the
+/// curve and the proof verifies is left to the verifier. Every issue is reported.
+pub fn validate_bundle_json(json: &str) -> Vec<SchemaIssue> {
+    let mut issues = Vec::new();
+    let mut issue = |path: &str, message: String| {
+        issues.push(SchemaIssue {
+            path: path.to_string(),
+            message,
+        })
+    };
+    let root = match serde_json::from_str::<Value>(json) {
+        Ok(Value::Object(root)) => root,
+        Ok(other) => {
+            issue("", format!("expected an object, got {}", json_type(&other)));
+            return issues;
+        }
+        Err(e) => {
+            issue("", format!("not JSON: {e}"));
+            return issues;
+        }
+    };
+
+    for key in root.keys() {
+        if !matches!(
+            key.as_str(),
+            "version"
+                | "circuit"
+                | "circuit_fingerprint"
+                | "crs"
+                | "commitments"
+                | "public_signals"
+                | "proof"
+        ) {
+            issue(key, "unknown field".to_string());
+        }
+    }
+    match root.get("version") {
+        None => issue("version", "missing".to_string()),
+        Some(version) if version.as_u64() != Some(BUNDLE_VERSION.into()) => issue(
+            "version",
+            format!("unsupported version {version}, expected {BUNDLE_VERSION}"),
+        ),
+        Some(_) => {}
+    }
+    match root.get("circuit") {
+        None => issue("circuit", "missing".to_string()),
+        Some(Value::String(circuit)) if !circuit.is_empty() => {}
+        Some(_) => issue("circuit", "expected a non-empty string".to_string()),
+    }
+    if let Some(fingerprint) = root.get("circuit_fingerprint") {
+        let parsed = fingerprint.as_str().map(str::parse::<Fingerprint>);
+        match parsed {
+            Some(Ok(fingerprint)) if fingerprint.digest.len() == 32 => {}
+            _ => issue(
+                "circuit_fingerprint",
+                "expected <blake3|sha256>:<64 hex digits>".to_string(),
+            ),
+        }
+    }
+    match root.get("crs") {
+        None => issue("crs", "missing".to_string()),
+        Some(Value::Object(crs)) => validate_crs(crs, &mut issue),
+        Some(other) => issue(
+            "crs",
+            format!("expected an object, got {}", json_type(other)),
+        ),
+    }
+
+    let point_size = G1Projective::default().compressed_size();
+    match root.get("commitments") {
+        None => issue("commitments", "missing".to_string()),
+        // Circuits without public signals prove an empty statement
+        Some(Value::Array(commitments)) => {
+            for (i, commitment) in commitments.iter().enumerate() {
+                if let Err(message) = check_hex(commitment, Some(point_size)) {
+                    issue(&format!("commitments[{i}]"), message);
+                }
+            }
+        }
+        Some(other) => issue(
+            "commitments",
+            format!("expected an array, got {}", json_type(other)),
+        ),
+    }
+    match root.get("public_signals") {
+        None => {}
+        Some(Value::Array(signals)) => {
+            for (i, signal) in signals.iter().enumerate() {
+                if signal.as_str().and_then(parse_public_signal).is_none() {
+                    issue(
+                        &format!("public_signals[{i}]"),
+                        "expected a decimal string below the field modulus".to_string(),
+                    );
+                }
+            }
+        }
+        Some(other) => issue(
+            "public_signals",
+            format!("expected an array, got {}", json_type(other)),
+        ),
+    }
+    match root.get("proof") {
+        None => issue("proof", "missing".to_string()),
+        Some(proof) => {
+            if let Err(message) = check_hex(proof, None) {
+                issue("proof", message);
+            } else if proof.as_str().is_some_and(str::is_empty) {
+                issue("proof", "empty".to_string());
+            }
+        }
+    }
+    issues
+}
+
+fn validate_crs(crs: &Map<String, Value>, issue: &mut impl FnMut(&str, String)) {
+    for key in crs.keys().filter(|key| *key != "size" && *key != "seed") {
+        issue(&format!("crs.{key}"), "unknown field".to_string());
+    }
+    match crs.get("size").map(Value::as_u64) {
+        None => issue("crs.size", "missing".to_string()),
+        Some(Some(size)) if size.is_power_of_two() => {}
+        Some(_) => issue("crs.size", "expected a power of two".to_string()),
+    }
+    match crs.get("seed") {
+        None => issue("crs.seed", "missing".to_string()),
+        Some(seed) => {
+            if let Err(message) = check_hex(seed, Some(32)) {
+                issue("crs.seed", message);
+            }
+        }
+    }
+}
+
+/// A decimal below the field modulus, as `pipeline::public_signals` writes them
+fn parse_public_signal(signal: &str) -> Option<Fr> {
+    if signal.is_empty() || !signal.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let value: BigUint = signal.parse().ok()?;
+    (value < BigUint::from(Fr::MODULUS)).then(|| Fr::from(value))
+}
+
+/// Checks that `value` is a hex string, of `len` bytes when given
+fn check_hex(value: &Value, len: Option<usize>) -> Result<(), String> {
+    let Value::String(encoded) = value else {
+        return Err(format!("expected a hex string, got {}", json_type(value)));
+    };
+    let bytes = hex::decode(encoded).map_err(|e| format!("invalid hex: {e}"))?;
+    match len {
+        Some(len) if bytes.len() != len => {
+            Err(format!("expected {len} bytes, got {}", bytes.len()))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[derive(Serialize)]
+struct StatementFile<'a> {
+    version: u32,
+    circuit: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    circuit_fingerprint: Option<&'a Fingerprint>,
+    crs: &'a CrsParams,
+    commitments: &'a [String],
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("Failed to access proof bundle: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Malformed proof bundle: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid hex in proof bundle: {0}")]
+    Hex(#[from] hex::FromHexError),
+    #[error("Invalid point in proof bundle: {0}")]
+    Point(#[from] ark_serialize::SerializationError),
+    #[error(transparent)]
+    Sink(#[from] SinkError),
+    #[error("Unsupported proof bundle version {0}, expected {BUNDLE_VERSION}")]
+    UnsupportedVersion(u32),
+    #[error("Invalid public signal '{0}', expected a decimal below the field modulus")]
+    PublicSignal(String),
+    #[error(transparent)]
+    PublicSignals(#[from] PublicSignalsError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle() -> ProofBundle {
+        // The identity encodes like any point, and no cryptography happens here
+        let statement = statement::from_commitments(vec![G1Projective::default(); 2]);
+        ProofBundle::new("multiplier2", CrsParams::new(2), &statement, &[1, 2, 3]).unwrap()
+    }
+
+    #[test]
+    fn written_bundles_match_the_schema() {
+        let json = serde_json::to_string(&bundle()).unwrap();
+        assert!(validate_bundle_json(&json).is_empty());
+
+        // The published schema requires what the validator requires
+        let schema: Value = serde_json::from_str(BUNDLE_SCHEMA).unwrap();
+        assert_eq!(
+            schema["required"],
+            serde_json::json!(["version", "circuit", "crs", "commitments", "proof"])
+        );
+    }
+
+    #[test]
+    fn bundles_of_signal_free_circuits_validate() {
+        let statement = statement::from_commitments(Vec::new());
+        let bundle = ProofBundle::new("constant", CrsParams::new(1), &statement, &[1]).unwrap();
+        assert!(validate_bundle_json(&serde_json::to_string(&bundle).unwrap()).is_empty());
+    }
+
+    /// `out <== x * x` over wires [1, out, x], proved with `out = 9` and written out
+    #[test]
+    fn altered_public_signals_are_rejected() {
+        use crate::{conversion, pipeline};
+        use ark_circom::{CircomCircuit, circom::R1CS};
+        use rand::rngs::OsRng;
+
+        let one = Fr::from(1u64);
+        let circom = CircomCircuit {
+            r1cs: R1CS {
+                num_inputs: 2,
+                num_aux: 1,
+                num_variables: 3,
+                constraints: vec![(vec![(2, one)], vec![(2, one)], vec![(1, one)])],
+                wire_mapping: None,
+            },
+            witness: Some([1u64, 9, 3].map(Fr::from).to_vec()),
+        };
+        let (circuit, mut witness, _) = conversion::circom_to_bulletproofs(&circom).unwrap();
+        let public = conversion::public_signal_count(&circom.r1cs);
+        conversion::blind(&mut witness, public, &mut OsRng);
+        let params = CrsParams::new(circuit.dim());
+        let crs = params.derive();
+        let (statement, proof) = pipeline::prove(&crs, &circuit, &witness, &mut OsRng).unwrap();
+
+        let dir =
+            std::env::temp_dir().join(format!("circom-bp-bundle-test-{}", std::process::id()));
+        ProofBundle::new("square", params, &statement, &proof)
+            .unwrap()
+            .with_public_signals(pipeline::public_signals(&circom))
+            .write_dir(&dir)
+            .unwrap();
+        let check = || {
+            let bundle = ProofBundle::load(&dir).unwrap();
+            let statement = bundle.statement().unwrap();
+            pipeline::verify(
+                &crs,
+                &circuit,
+                &statement,
+                &bundle.proof_bytes().unwrap(),
+                &mut OsRng,
+            )
+            .unwrap();
+            bundle.check_public_signals(&crs, &statement, public)
+        };
+        check().unwrap();
+
+        // The proof still verifies, but no longer for the claimed output
+        std::fs::write(dir.join("public.json"), r#"["10"]"#).unwrap();
+        assert!(matches!(
+            check(),
+            Err(BundleError::PublicSignals(PublicSignalsError::Mismatch(0)))
+        ));
+        std::fs::write(dir.join("public.json"), r#"[]"#).unwrap();
+        assert!(matches!(
+            check(),
+            Err(BundleError::PublicSignals(PublicSignalsError::Count { .. }))
+        ));
+        std::fs::write(dir.join("public.json"), r#"["not a number"]"#).unwrap();
+        assert!(matches!(check(), Err(BundleError::PublicSignal(_))));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_every_structural_issue() {
+        let mut json = serde_json::to_value(bundle()).unwrap();
+        json["version"] = 2.into();
+        json["crs"]["size"] = 3.into();
+        json["commitments"][1] = "abcd".into();
+        json["proof"] = "xyz".into();
+        json["extra"] = true.into();
+        json.as_object_mut().unwrap().remove("circuit");
+
+        let issues = validate_bundle_json(&json.to_string());
+        let mut paths: Vec<_> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            [
+                "circuit",
+                "commitments[1]",
+                "crs.size",
+                "extra",
+                "proof",
+                "version"
+            ]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use circom_bp::{
+    audit::audit,
+    commitment::InputOpenings,
+    conversion::{
+        blind, circom_to_bulletproofs_committing, public_signal_count, statement_variables,
+    },
+    pipeline,
+    sym::SymbolTable,
+    template::InputLayout,
+};
+use clap::Args;
+use rand::rngs::OsRng;
+
+use super::CircuitArgs;
+
+#[derive(Args, Debug)]
+pub struct AuditArgs {
+    #[command(flatten)]
+    pub circuit: CircuitArgs,
+    /// Blindings written by `commit`, applied as `prove --openings` would
+    #[arg(long, env = "CIRCOM_BP_OPENINGS")]
+    pub openings: Option<PathBuf>,
+    /// Print the findings as a JSON array
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Builds the witness a proof would use and reports what its statement leaks
+///
+/// Fails when there is any finding, so CI can gate on a hiding configuration.
+pub fn run(args: AuditArgs) -> Result<(), Box<dyn std::error::Error>> {
+    args.circuit.check_curve()?;
+    let paths = args.circuit.paths();
+    let inputs = args.circuit.load_inputs(&paths)?;
+    let circom = pipeline::generate_witness_cached(&paths, &inputs, None)?;
+    let openings = args
+        .openings
+        .as_ref()
+        .map(InputOpenings::read)
+        .transpose()?;
+    let committed = openings
+        .as_ref()
+        .map(InputOpenings::variables)
+        .unwrap_or_default();
+    let (_, mut witness, _) = circom_to_bulletproofs_committing(&circom, &committed)?;
+    let public = public_signal_count(&circom.r1cs);
+    blind(&mut witness, public, &mut OsRng);
+    if let Some(openings) = &openings {
+        openings.apply(&mut witness, public)?;
+    }
+
+    let layout = InputLayout::read(&paths.r1cs)?;
+    // Names are a convenience; circuits compiled without `--sym` still get audited
+    let symbols = paths
+        .sym
+        .exists()
+        .then(|| SymbolTable::read(&paths.sym))
+        .transpose()?;
+    let names = symbols
+        .as_ref()
+        .map(SymbolTable::names_by_variable)
+        .unwrap_or_default();
+    let variables = statement_variables(&circom.r1cs, &committed);
+    let findings = audit(&witness, &variables, &layout, &names);
+
+    if args.json {
+        println!("{}", serde_json::to_string(&findings)?);
+    } else {
+        for finding in &findings {
+            let location = match (&finding.signal, finding.variable) {
+                (Some(signal), _) => format!(" {signal}"),
+                (None, Some(variable)) => format!(" variable {variable}"),
+                (None, None) => String::new(),
+            };
+            println!("⚠️  {:?}{location}: {}", finding.kind, finding.detail);
+        }
+    }
+
+    if !findings.is_empty() {
+        return Err(format!(
+            "{} findings; proofs of this witness are not hiding",
+            findings.len()
+        )
+        .into());
+    }
+    if !args.json {
+        println!("No findings");
+    }
+    Ok(())
+}
@@ -0,0 +1,335 @@
+use std::path::{Path, PathBuf};
+
+use ark_circom::CircomCircuit;
+use circom_bp::{
+    atomic,
+    bundle::ProofBundle,
+    commitment::InputOpenings,
+    compliance::{ComplianceLog, ComplianceRecord, JsonLinesLog},
+    conversion::{blind, convert_witness, public_signal_count},
+    conversion_cache::ConversionCache,
+    crs::{self, CrsParams},
+    encryption::InputsKey,
+    estimate::ResourceEstimate,
+    fingerprint::HashAlgorithm,
+    inputs::{self, InputError},
+    limits::ResourceLimits,
+    oracle,
+    pipeline::{self, RetryPolicy, WitnessGenerator, WitnessSource},
+    registry::{NewProof, VerificationStatus},
+    simplify,
+    sink::{self, FileSink, Sink},
+    witness_cache::WitnessCache,
+};
+use clap::Args;
+use rand::rngs::OsRng;
+use tracing::{error, info};
+
+use super::{CircuitArgs, progress, proofs::RegistryArgs};
+
+#[derive(Args, Debug)]
+pub struct BatchProveArgs {
+    #[command(flatten)]
+    pub circuit: CircuitArgs,
+    /// Directory of `*.json` input files, one proof is produced per file
+    #[arg(
+        long,
+        required_unless_present = "inputs_csv",
+        conflicts_with = "inputs_csv"
+    )]
+    pub inputs_dir: Option<PathBuf>,
+    /// CSV with a header of signal names, one proof is produced per row
+    #[arg(long)]
+    pub inputs_csv: Option<PathBuf>,
+    /// Directory or `http(s)://` URL receiving the `<job>.proof.json` bundles
+    /// (defaults to `proofs` beside the inputs)
+    #[arg(long, visible_alias = "out", env = "CIRCOM_BP_OUTPUT_DIR")]
+    pub out_dir: Option<String>,
+    /// Hex-encoded 32-byte seed the CRS is derived from
+    #[arg(long, env = "CIRCOM_BP_CRS_SEED")]
+    pub crs_seed: Option<String>,
+    /// Hash used to fingerprint the circuit in the bundles (blake3 or sha256)
+    #[arg(long, default_value_t = HashAlgorithm::Blake3, env = "CIRCOM_BP_HASH")]
+    pub hash: HashAlgorithm,
+    /// Blindings written by `commit`, so every proof opens the published commitments
+    #[arg(long, env = "CIRCOM_BP_OPENINGS")]
+    pub openings: Option<PathBuf>,
+    #[command(flatten)]
+    pub registry: RegistryArgs,
+    /// Append a JSON line describing each proof's cryptographic parameters to this file
+    #[arg(long, env = "CIRCOM_BP_COMPLIANCE_LOG")]
+    pub compliance_log: Option<PathBuf>,
+    /// Always run the witness calculator instead of reusing witnesses cached for the
+    /// same wasm and inputs
+    #[arg(long, env = "CIRCOM_BP_NO_WITNESS_CACHE")]
+    pub no_witness_cache: bool,
+    /// Always convert the circuit instead of reusing a conversion cached for the
+    /// same r1cs, simplification passes and committed variables
+    #[arg(long, env = "CIRCOM_BP_NO_CONVERSION_CACHE")]
+    pub no_conversion_cache: bool,
+    /// Check that the converted circuit is satisfied exactly when the R1CS is, as
+    /// debug builds always do
+    #[arg(long, env = "CIRCOM_BP_CHECK_CONVERSION")]
+    pub check_conversion: bool,
+    /// Drop constraints that repeat an earlier one up to a scalar multiple, and
+    /// trivial ones, before conversion; verifiers must pass `--dedup` too
+    #[arg(long, env = "CIRCOM_BP_DEDUP")]
+    pub dedup: bool,
+    /// Rewrite constraints repeating an earlier constraint's product as linear ones,
+    /// so they share its gate; verifiers must pass `--share-gates` too
+    #[arg(long, env = "CIRCOM_BP_SHARE_GATES")]
+    pub share_gates: bool,
+    /// Substitute away private variables defined by a linear constraint and used
+    /// at most once more before conversion; verifiers must pass `--eliminate` too
+    #[arg(long, conflicts_with = "openings", env = "CIRCOM_BP_ELIMINATE")]
+    pub eliminate: bool,
+    /// Witness calculator: the in-process wasm one, or the native one `circom --c`
+    /// generates (`<name>_cpp/<name>`, built with `make`)
+    #[arg(long, default_value_t = WitnessSource::Wasm, env = "CIRCOM_BP_WITNESS_SOURCE")]
+    pub witness_source: WitnessSource,
+    /// Prove each job up to this many times, with fresh randomness, when the prover
+    /// fails transiently
+    #[arg(
+        long,
+        default_value_t = RetryPolicy::default().max_attempts,
+        env = "CIRCOM_BP_MAX_ATTEMPTS"
+    )]
+    pub max_attempts: usize,
+}
+
+/// Proves every inputs file in a directory against a single CRS
+///
+/// The circuit is loaded, simplified and converted, and the CRS derived, once
+/// before the first job; every job only pays for its witness, placed into the
+/// converted circuit, and proving.
+pub fn run(args: BatchProveArgs) -> Result<(), Box<dyn std::error::Error>> {
+    args.circuit.check_curve()?;
+    let paths = args.circuit.paths();
+    let key = args.circuit.inputs_key()?;
+    let (jobs, inputs_dir) = match (&args.inputs_dir, &args.inputs_csv) {
+        (Some(dir), _) => (input_files(dir)?, dir.clone()),
+        (None, Some(csv)) => (
+            csv_rows(csv, key.as_ref())?,
+            atomic::parent_dir(csv).to_path_buf(),
+        ),
+        (None, None) => return Err("--inputs-dir or --inputs-csv is required".into()),
+    };
+    let mut sink: Box<dyn Sink> = match &args.out_dir {
+        Some(target) => sink::open(target),
+        None => Box::new(FileSink::new(inputs_dir.join("proofs"))),
+    };
+    let seed = match &args.crs_seed {
+        Some(seed) => crs::parse_seed(seed)?,
+        None => crs::DEFAULT_SEED,
+    };
+
+    let fingerprint = args.hash.digest_file(&paths.r1cs)?;
+    let openings = args
+        .openings
+        .as_ref()
+        .map(InputOpenings::read)
+        .transpose()?;
+    let committed = openings
+        .as_ref()
+        .map(InputOpenings::variables)
+        .unwrap_or_default();
+    let registry = args.registry.open()?;
+    let mut compliance_log = args.compliance_log.as_ref().map(JsonLinesLog::new);
+    let circuit_fingerprint = fingerprint.to_string();
+    let witness_cache =
+        (!args.no_witness_cache).then(|| WitnessCache::new(WitnessCache::default_dir()));
+    let conversion_cache =
+        (!args.no_conversion_cache).then(|| ConversionCache::new(ConversionCache::default_dir()));
+    let passes = super::simplify_passes(args.dedup, args.share_gates, args.eliminate);
+
+    if jobs.is_empty() {
+        return Err(format!("No jobs in {}", inputs_dir.display()).into());
+    }
+
+    let generator = WitnessGenerator::new(args.witness_source, &paths, witness_cache)?;
+    let mut r1cs = generator.r1cs().clone();
+    if args.dedup {
+        r1cs = simplify::deduplicate(&r1cs).0;
+    }
+    if args.share_gates {
+        r1cs = simplify::share_products(&r1cs).0;
+    }
+    let mut elimination = None;
+    if args.eliminate {
+        let (reduced, eliminated) = simplify::eliminate_intermediates(&r1cs);
+        r1cs = reduced;
+        elimination = Some(eliminated);
+    }
+    ResourceLimits::current().check_memory(ResourceEstimate::from_r1cs(&r1cs).memory_bytes)?;
+    let (circuit, _) = pipeline::convert_r1cs_cached(
+        &r1cs,
+        &committed,
+        &paths,
+        &passes,
+        conversion_cache.as_ref(),
+    )?;
+    let dense = circuit.to_dense();
+    let public = public_signal_count(&r1cs);
+    let crs_params = CrsParams::with_seed(dense.dim(), seed);
+    let crs = crs_params.derive();
+    if let Some(openings) = &openings
+        && (openings.circuit != args.circuit.circuit || openings.crs != crs_params)
+    {
+        return Err("openings were made for another circuit or CRS".into());
+    }
+    // Each job only swaps in its witness
+    let mut circom = CircomCircuit {
+        r1cs,
+        witness: None,
+    };
+    info!("Proving {} jobs for {}", jobs.len(), args.circuit.circuit);
+
+    let mut rng = OsRng;
+    let mut failed = 0;
+    let mut attempts = 0;
+    let policy = RetryPolicy {
+        max_attempts: args.max_attempts,
+    };
+    let progress = progress::bar(jobs.len());
+    for job in &jobs {
+        let name = job.name.clone();
+        progress.set_message(name.clone());
+        let result = (|| -> Result<String, Box<dyn std::error::Error>> {
+            let inputs_json = job.inputs_json(key.as_ref())?;
+            let inputs = args.circuit.parse_inputs(&inputs_json, &paths)?;
+            // Secret inputs must not leave a witness behind on disk
+            let values = generator.witness(&inputs, !job.private)?;
+            circom.witness = Some(match &elimination {
+                Some(elimination) => elimination.witness(generator.r1cs(), &values)?,
+                None => values,
+            });
+            let mut witness = convert_witness(&circom, &committed)?;
+            if oracle::enabled(args.check_conversion) {
+                oracle::check_conversion(&circom, &circuit, &witness)?;
+            }
+            pipeline::check_converted(&circom, &circuit, &witness, &paths, elimination.as_ref())?;
+            blind(&mut witness, public, &mut rng);
+            if let Some(openings) = &openings {
+                openings.apply(&mut witness, public)?;
+            }
+            let (result, tries) =
+                pipeline::prove_with_retry(&crs, &dense, &witness, &mut rng, policy);
+            attempts += tries;
+            let (statement, proof) = result?;
+
+            let artifact = format!("{name}.proof.json");
+            ProofBundle::new(&args.circuit.circuit, crs_params, &statement, &proof)?
+                .with_circuit_fingerprint(fingerprint.clone())
+                .with_public_signals(pipeline::public_signals(&circom))
+                .put(sink.as_mut(), &artifact)?;
+            let location = sink.location(&artifact);
+            if let Some(registry) = &registry {
+                registry.record(&NewProof {
+                    circuit: &args.circuit.circuit,
+                    circuit_fingerprint: &circuit_fingerprint,
+                    input_hash: &args.hash.digest(inputs_json.as_bytes()).to_string(),
+                    proof_path: &location,
+                    status: VerificationStatus::Unverified,
+                })?;
+            }
+            if let Some(log) = &mut compliance_log {
+                log.record(
+                    &ComplianceRecord::new(
+                        &args.circuit.circuit,
+                        fingerprint.clone(),
+                        crs_params,
+                        &witness,
+                        false,
+                    )
+                    .with_proof(location.clone()),
+                )?;
+            }
+            Ok(location)
+        })();
+
+        match result {
+            Ok(location) => progress.suspend(|| info!("✅ {name}: {location}")),
+            Err(e) => {
+                failed += 1;
+                progress.suspend(|| error!("❌ {name}: {e}"));
+            }
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    info!(
+        "{} of {} jobs proved in {attempts} proving attempts",
+        jobs.len() - failed,
+        jobs.len()
+    );
+    if failed > 0 {
+        return Err(format!("{failed} jobs failed").into());
+    }
+    Ok(())
+}
+
+/// One proof to produce: an inputs file, or a row of an inputs CSV
+struct Job {
+    name: String,
+    source: JobSource,
+    /// Whether the inputs were encrypted, see `inputs::is_private_source`
+    private: bool,
+}
+
+enum JobSource {
+    File(PathBuf),
+    Row(String),
+}
+
+impl Job {
+    fn inputs_json(&self, key: Option<&InputsKey>) -> Result<String, InputError> {
+        match &self.source {
+            JobSource::File(path) => inputs::read_inputs_decrypted(path, key),
+            JobSource::Row(json) => Ok(json.clone()),
+        }
+    }
+}
+
+/// Lists the `*.json` files of a directory in a stable order
+fn input_files(dir: &Path) -> std::io::Result<Vec<Job>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files
+        .into_iter()
+        .map(|path| Job {
+            name: file_stem(&path),
+            private: inputs::is_private_source(&path),
+            source: JobSource::File(path),
+        })
+        .collect())
+}
+
+/// One job per data row of a CSV, named `<csv stem>-<row>` counting from 1
+fn csv_rows(path: &Path, key: Option<&InputsKey>) -> Result<Vec<Job>, InputError> {
+    let stem = file_stem(path);
+    let private = inputs::is_private_source(path);
+    let rows = inputs::csv_inputs(&inputs::read_inputs_decrypted(path, key)?)?;
+    Ok(rows
+        .into_iter()
+        .enumerate()
+        .map(|(i, json)| Job {
+            name: format!("{stem}-{}", i + 1),
+            source: JobSource::Row(json),
+            private,
+        })
+        .collect())
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem().map_or_else(
+        || path.display().to_string(),
+        |stem| stem.to_string_lossy().into_owned(),
+    )
+}
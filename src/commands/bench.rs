@@ -0,0 +1,110 @@
+use std::time::{Duration, Instant};
+
+use circom_bp::{
+    conversion::circom_to_bulletproofs,
+    pipeline::{self, WitnessSource},
+    reorder::{self, CircuitOrder},
+};
+use clap::Args;
+use rand::rngs::OsRng;
+
+use super::CircuitArgs;
+
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub circuit: CircuitArgs,
+    /// Number of full pipeline runs to time
+    #[arg(long, short = 'n', default_value_t = 10)]
+    pub iterations: usize,
+    /// Reorder private variables and constraints by reverse Cuthill–McKee before
+    /// conversion, to compare prover locality against the compiled order
+    #[arg(long)]
+    pub reorder: bool,
+    /// Witness calculator to time: wasm, or the native one of `circom --c`
+    #[arg(long, default_value_t = WitnessSource::Wasm)]
+    pub witness_source: WitnessSource,
+}
+
+const PHASES: [&str; 5] = ["witness", "conversion", "crs", "prove", "verify"];
+
+pub fn run(args: BenchArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if args.iterations == 0 {
+        return Err("--iterations must be at least 1".into());
+    }
+
+    args.circuit.check_curve()?;
+    let paths = args.circuit.paths();
+    let inputs = args.circuit.load_inputs(&paths)?;
+    let mut rng = OsRng;
+
+    let mut samples: Vec<[Duration; PHASES.len()]> = Vec::with_capacity(args.iterations);
+    let mut proof_size = 0;
+    let mut bandwidth = (0, 0);
+    for _ in 0..args.iterations {
+        let mut timings = [Duration::ZERO; PHASES.len()];
+
+        let start = Instant::now();
+        let circom = pipeline::generate_witness_from(args.witness_source, &paths, &inputs, None)?;
+        timings[0] = start.elapsed();
+
+        let start = Instant::now();
+        let circom = if args.reorder {
+            let order = CircuitOrder::reverse_cuthill_mckee(&circom.r1cs);
+            let reordered = order.apply(&circom)?;
+            bandwidth = (
+                reorder::bandwidth(&circom.r1cs),
+                reorder::bandwidth(&reordered.r1cs),
+            );
+            reordered
+        } else {
+            circom
+        };
+        let (circuit, witness, _) = circom_to_bulletproofs(&circom)?;
+        timings[1] = start.elapsed();
+
+        let start = Instant::now();
+        let crs = pipeline::setup(&circuit, &mut rng);
+        timings[2] = start.elapsed();
+
+        let start = Instant::now();
+        let (statement, proof) = pipeline::prove(&crs, &circuit, &witness, &mut rng)?;
+        timings[3] = start.elapsed();
+
+        let start = Instant::now();
+        pipeline::verify(&crs, &circuit, &statement, &proof, &mut rng)?;
+        timings[4] = start.elapsed();
+
+        proof_size = proof.len();
+        samples.push(timings);
+    }
+
+    println!(
+        "Benchmarked {} over {} iterations",
+        args.circuit.circuit, args.iterations
+    );
+    for (phase_idx, phase) in PHASES.iter().enumerate() {
+        let (mean, stddev) = mean_stddev(samples.iter().map(|timings| timings[phase_idx]));
+        println!("{phase:>12}: {mean:>10.3} ms ± {stddev:.3} ms");
+    }
+    println!("{:>12}: {} bytes", "proof size", proof_size);
+    if args.reorder {
+        println!(
+            "{:>12}: {} -> {} after reordering",
+            "bandwidth", bandwidth.0, bandwidth.1
+        );
+    }
+
+    Ok(())
+}
+
+/// Mean and (population) standard deviation in milliseconds
+fn mean_stddev(samples: impl ExactSizeIterator<Item = Duration> + Clone) -> (f64, f64) {
+    let n = samples.len() as f64;
+    let mean = samples.clone().map(|d| d.as_secs_f64() * 1e3).sum::<f64>() / n;
+    let variance = samples
+        .map(|d| (d.as_secs_f64() * 1e3 - mean).powi(2))
+        .sum::<f64>()
+        / n;
+    (mean, variance.sqrt())
+}
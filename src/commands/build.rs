@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use circom_bp::compile::Compiler;
+use clap::Args;
+use tracing::info;
+
+/// Options for invoking the circom compiler
+#[derive(Args, Debug, Clone)]
+pub struct CompilerArgs {
+    /// circom executable to run
+    #[arg(long, default_value = "circom", env = "CIRCOM_BP_CIRCOM")]
+    pub circom: PathBuf,
+    /// Library directory passed to circom with `-l`
+    #[arg(short = 'l', long = "include")]
+    pub include: Vec<PathBuf>,
+}
+
+impl Default for CompilerArgs {
+    fn default() -> Self {
+        let Compiler { program, include } = Compiler::default();
+        Self {
+            circom: program,
+            include,
+        }
+    }
+}
+
+impl CompilerArgs {
+    pub fn compiler(&self) -> Compiler {
+        Compiler {
+            program: self.circom.clone(),
+            include: self.include.clone(),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct BuildArgs {
+    /// The `.circom` source to compile
+    pub source: PathBuf,
+    /// Where to place the outputs (defaults to the source's directory)
+    #[arg(long)]
+    pub out_dir: Option<PathBuf>,
+    #[command(flatten)]
+    pub compiler: CompilerArgs,
+}
+
+pub fn run(args: BuildArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let out_dir = args.out_dir.clone().unwrap_or_else(|| {
+        args.source
+            .parent()
+            .map_or_else(|| PathBuf::from("."), PathBuf::from)
+    });
+
+    info!("Compiling {}", args.source.display());
+    let paths = args.compiler.compiler().compile(&args.source, &out_dir)?;
+    info!(
+        "✅ Wrote {} and {}",
+        paths.r1cs.display(),
+        paths.wasm.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use circom_bp::{
+    commitment,
+    conversion::circom_to_bulletproofs_committing,
+    crs::{self, CrsParams},
+    pipeline,
+    sym::SymbolTable,
+};
+use clap::Args;
+use rand::rngs::OsRng;
+use tracing::info;
+
+use super::CircuitArgs;
+
+#[derive(Args, Debug)]
+pub struct CommitArgs {
+    #[command(flatten)]
+    pub circuit: CircuitArgs,
+    /// Input signals to commit to, e.g. `a,b` or `in[0]`
+    #[arg(long, required = true, value_delimiter = ',')]
+    pub signals: Vec<String>,
+    /// Hex-encoded 32-byte seed the CRS is derived from; must match the later proof
+    #[arg(long, env = "CIRCOM_BP_CRS_SEED")]
+    pub crs_seed: Option<String>,
+    /// Where to write the public commitments (defaults to `<circuit>.commitments.json`)
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+    /// Where to write the blindings, which are secret: anyone holding them can open
+    /// the commitments. The file is readable by its owner only (defaults to
+    /// `<circuit>.openings.json`)
+    #[arg(long)]
+    pub openings: Option<PathBuf>,
+}
+
+/// Commits to selected input signals ahead of proving
+///
+/// The commitments can be published right away; `prove --openings` later produces a
+/// proof whose statement contains exactly these commitments.
+pub fn run(args: CommitArgs) -> Result<(), Box<dyn std::error::Error>> {
+    args.circuit.check_curve()?;
+    let paths = args.circuit.paths();
+    let name = &args.circuit.circuit;
+    let seed = match &args.crs_seed {
+        Some(seed) => crs::parse_seed(seed)?,
+        None => crs::DEFAULT_SEED,
+    };
+
+    let inputs = args.circuit.load_inputs(&paths)?;
+    let circom = pipeline::generate_witness_cached(&paths, &inputs, None)?;
+    let symbols = SymbolTable::read(&paths.sym)?;
+    let signals = commitment::resolve_signals(&circom.r1cs, &symbols, &args.signals)?;
+    let variables: Vec<usize> = signals.iter().map(|(_, variable)| *variable).collect();
+    let (circuit, witness, _) = circom_to_bulletproofs_committing(&circom, &variables)?;
+
+    let crs_params = CrsParams::with_seed(circuit.dim(), seed);
+    let (commitments, openings) = commitment::commit(
+        name,
+        crs_params,
+        &crs_params.derive(),
+        &circom.r1cs,
+        &witness,
+        &signals,
+        &mut OsRng,
+    )?;
+
+    let out = args
+        .out
+        .unwrap_or_else(|| PathBuf::from(format!("{name}.commitments.json")));
+    let openings_path = args
+        .openings
+        .unwrap_or_else(|| PathBuf::from(format!("{name}.openings.json")));
+    openings.write(&openings_path)?;
+    commitments.write(&out)?;
+    info!(
+        "Committed to {} signals: {} (keep {} secret)",
+        signals.len(),
+        out.display(),
+        openings_path.display()
+    );
+    Ok(())
+}
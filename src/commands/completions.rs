@@ -0,0 +1,16 @@
+use clap::{Args, CommandFactory};
+use clap_complete::Shell;
+
+#[derive(Args, Debug)]
+pub struct CompletionsArgs {
+    /// Shell to generate the script for
+    pub shell: Shell,
+}
+
+/// Writes a completion script generated from the CLI definition to stdout
+pub fn run(args: CompletionsArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command = crate::Cli::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(args.shell, &mut command, name, &mut std::io::stdout());
+    Ok(())
+}
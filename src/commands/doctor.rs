@@ -0,0 +1,134 @@
+use std::path::{Path, PathBuf};
+
+use ark_bn254::Fr;
+use ark_ff::{One, Zero};
+use circom_bp::{
+    builder::CircuitBuilder,
+    crs::CrsParams,
+    curve::Curve,
+    pipeline::{self, CircuitPaths},
+};
+use clap::Args;
+use rand::rngs::OsRng;
+
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    /// Directory of compiled circuits to check
+    #[arg(long, default_value = "./circuits", env = "CIRCOM_BP_CIRCUITS_DIR")]
+    pub circuits_dir: PathBuf,
+}
+
+/// Outcome of a single readiness check
+struct Check {
+    name: String,
+    result: Result<String, String>,
+}
+
+/// Checks that this host can prove and verify, printing a readiness report
+pub fn run(args: DoctorArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let mut checks = vec![
+        Check {
+            name: "curves".into(),
+            result: Ok(Curve::ALL
+                .into_iter()
+                .filter(|curve| curve.is_compiled_in())
+                .map(|curve| curve.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")),
+        },
+        Check {
+            name: "threads".into(),
+            result: std::thread::available_parallelism()
+                .map(|n| format!("{n} available, {} in use", rayon::current_num_threads()))
+                .map_err(|e| e.to_string()),
+        },
+        Check {
+            name: "prove/verify self-test".into(),
+            result: self_test(),
+        },
+    ];
+    checks.extend(circuit_checks(&args.circuits_dir));
+
+    let mut failed = 0;
+    for check in &checks {
+        match &check.result {
+            Ok(detail) => println!("✅ {}: {detail}", check.name),
+            Err(reason) => {
+                failed += 1;
+                println!("❌ {}: {reason}", check.name);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{failed} of {} checks failed", checks.len()).into());
+    }
+    println!("Ready");
+    Ok(())
+}
+
+/// Derives a tiny CRS and proves `3 * 11 = 33`, exercising the whole proof stack
+fn self_test() -> Result<String, String> {
+    let mut builder = CircuitBuilder::<Fr>::new();
+    let gate = builder.add_mul_gate(Fr::from(3u64), Fr::from(11u64));
+    builder.add_linear_constraint(&[(gate.output, Fr::one())], Fr::from(33u64));
+    let x = builder.commit_value(Fr::from(3u64), Fr::from(5u64));
+    builder.add_linear_constraint(&[(gate.left, Fr::one()), (x, -Fr::one())], Fr::zero());
+    let (circuit, witness) = builder.build();
+
+    let mut rng = OsRng;
+    let crs = CrsParams::new(circuit.dim()).derive();
+    let (statement, proof) =
+        pipeline::prove(&crs, &circuit, &witness, &mut rng).map_err(|e| e.to_string())?;
+    pipeline::verify(&crs, &circuit, &statement, &proof, &mut rng).map_err(|e| e.to_string())?;
+    Ok(format!("{} byte proof verified", proof.len()))
+}
+
+/// Loads every compiled circuit: the r1cs parser and the wasm engine both get exercised
+fn circuit_checks(dir: &Path) -> Vec<Check> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            return vec![Check {
+                name: format!("circuits in {}", dir.display()),
+                result: Err(e.to_string()),
+            }];
+        }
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "r1cs"))
+        .filter_map(|path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .collect();
+    names.sort();
+    if names.is_empty() {
+        return vec![Check {
+            name: format!("circuits in {}", dir.display()),
+            result: Err("no .r1cs files found".into()),
+        }];
+    }
+
+    names
+        .into_iter()
+        .map(|name| {
+            let paths = CircuitPaths::new(dir, &name);
+            let result = pipeline::load_config(&paths)
+                .map(|config| {
+                    format!(
+                        "{} constraints, wasm instantiated",
+                        config.r1cs.constraints.len()
+                    )
+                })
+                .map_err(|e| e.to_string());
+            Check {
+                name: format!("circuit {name}"),
+                result,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use circom_bp::{
+    atomic,
+    encryption::{EncryptionError, InputsKey},
+    inputs,
+};
+use clap::Args;
+use rand::rngs::OsRng;
+use serde_json::{Map, Value};
+use tracing::info;
+
+#[derive(Args, Debug)]
+pub struct EncryptInputsArgs {
+    /// Plaintext inputs JSON; `-` reads stdin
+    pub inputs: PathBuf,
+    /// Where to write the encrypted inputs
+    #[arg(long, short)]
+    pub out: PathBuf,
+    /// File holding the hex key, created with a fresh key when missing; without it
+    /// the key in `CIRCOM_BP_INPUTS_KEY` is used
+    #[arg(long, env = "CIRCOM_BP_INPUTS_KEY_FILE")]
+    pub key_file: Option<PathBuf>,
+}
+
+/// Encrypts an inputs file for `prove --inputs-key-file`
+pub fn run(args: EncryptInputsArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let plaintext = inputs::read_inputs_json(&args.inputs)?;
+    // Catch malformed inputs now rather than after they are sealed
+    serde_json::from_str::<Map<String, Value>>(&plaintext)?;
+
+    let key = match &args.key_file {
+        Some(path) if path.exists() => InputsKey::read(path)?,
+        Some(path) => {
+            let key = InputsKey::generate(&mut OsRng);
+            atomic::write(path, key.to_hex() + "\n")?;
+            info!("Wrote new inputs key to {}", path.display());
+            key
+        }
+        None => InputsKey::from_env()?.ok_or(EncryptionError::MissingKey)?,
+    };
+    atomic::write(&args.out, key.encrypt(plaintext.as_bytes(), &mut OsRng))?;
+    info!("Wrote encrypted inputs to {}", args.out.display());
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use circom_bp::{
+    atomic,
+    template::{inputs_schema, inputs_template, read_input_signals},
+};
+use clap::Args;
+use tracing::info;
+
+use super::CircuitArgs;
+
+#[derive(Args, Debug)]
+pub struct InputsTemplateArgs {
+    #[command(flatten)]
+    pub circuit: CircuitArgs,
+    /// Print a JSON Schema of the inputs object instead, for services to validate
+    /// payloads before invoking the prover
+    #[arg(long, conflicts_with = "write")]
+    pub schema: bool,
+    /// Write the template to the inputs file instead of printing it
+    #[arg(long)]
+    pub write: bool,
+    /// Overwrite an existing inputs file when writing
+    #[arg(long, requires = "write")]
+    pub force: bool,
+}
+
+/// Emits a skeleton inputs file listing every input signal with a zero placeholder,
+/// or a JSON Schema of the inputs
+pub fn run(args: InputsTemplateArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let paths = args.circuit.paths();
+    let signals = read_input_signals(&paths)?;
+    if args.schema {
+        let schema = inputs_schema(&args.circuit.circuit, &signals);
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+        return Ok(());
+    }
+    let template = serde_json::to_string_pretty(&inputs_template(&signals))?;
+
+    if !args.write {
+        println!("{template}");
+        return Ok(());
+    }
+    if paths.inputs.exists() && !args.force {
+        return Err(format!(
+            "{} already exists, pass --force to overwrite it",
+            paths.inputs.display()
+        )
+        .into());
+    }
+    atomic::write(&paths.inputs, template + "\n")?;
+    info!(
+        "Wrote {} input signals to {}",
+        signals.len(),
+        paths.inputs.display()
+    );
+    Ok(())
+}
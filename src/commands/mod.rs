@@ -0,0 +1,229 @@
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use ark_bn254::Fr;
+use circom_bp::{
+    archive::{self, ArchiveError, ArchiveFormat},
+    compile::CompileError,
+    crs::CrsError,
+    curve::{Curve, CurveError},
+    encryption::InputsKey,
+    inputs::{self, InputError, InputOverride, UnknownInputs},
+    limits::LimitError,
+    pipeline::{self, CircuitPaths, PipelineError},
+};
+use clap::Args;
+
+use build::CompilerArgs;
+
+pub mod audit;
+pub mod batch_prove;
+pub mod bench;
+pub mod build;
+pub mod commit;
+pub mod completions;
+pub mod doctor;
+pub mod encrypt_inputs;
+pub mod inputs_template;
+pub mod progress;
+pub mod proofs;
+pub mod prove;
+pub mod run;
+pub mod sign_params;
+pub mod validate_bundle;
+pub mod verify_batch;
+pub mod watch;
+pub mod witness_diff;
+
+pub use circom_bp::exit_codes;
+
+/// Exit code for an error returned by a command
+pub fn exit_code(error: &(dyn Error + 'static)) -> u8 {
+    if let Some(e) = error.downcast_ref::<PipelineError>() {
+        exit_codes::for_category(e.category())
+    } else if let Some(e) = error.downcast_ref::<prove::ProveError>() {
+        exit_codes::for_category(e.category())
+    } else if let Some(LimitError::Memory { .. }) = error.downcast_ref::<LimitError>() {
+        exit_codes::RESOURCE_LIMIT
+    } else if error.is::<InputError>()
+        || error.is::<CrsError>()
+        || error.is::<CurveError>()
+        || error.is::<ArchiveError>()
+        || error.is::<CompileError>()
+        || error.is::<validate_bundle::InvalidBundles>()
+    {
+        exit_codes::INPUT
+    } else {
+        exit_codes::FAILURE
+    }
+}
+
+/// Names of the enabled simplification passes in the order the commands run them,
+/// as `ConversionCache::key` takes them
+pub fn simplify_passes(dedup: bool, share_gates: bool, eliminate: bool) -> Vec<&'static str> {
+    [
+        ("dedup", dedup),
+        ("share_gates", share_gates),
+        ("eliminate", eliminate),
+    ]
+    .into_iter()
+    .filter_map(|(pass, enabled)| enabled.then_some(pass))
+    .collect()
+}
+
+/// The temporary directory a `--source` is compiled into, removed when dropped
+#[derive(Debug)]
+pub struct BuildDir(PathBuf);
+
+impl Drop for BuildDir {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.0)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::warn!("Failed to remove {}: {e}", self.0.display());
+        }
+    }
+}
+
+/// Selects a compiled circuit by name
+#[derive(Args, Debug, Clone, Default)]
+pub struct CircuitArgs {
+    /// Circuit name, e.g. `multiplier2`, or a `.tar`, `.tar.zst` or `.zip` circuit archive
+    #[arg(
+        required_unless_present = "source",
+        default_value = "",
+        env = "CIRCOM_BP_CIRCUIT"
+    )]
+    pub circuit: String,
+    /// Compile this `.circom` source into a temporary directory and use its outputs
+    #[arg(long, conflicts_with = "circuit")]
+    pub source: Option<PathBuf>,
+    #[command(flatten)]
+    pub compiler: CompilerArgs,
+    /// Directory holding the compiled `.r1cs`, `_js/` wasm and `_inputs.json` files
+    #[arg(long, default_value = "./circuits", env = "CIRCOM_BP_CIRCUITS_DIR")]
+    pub circuits_dir: PathBuf,
+    /// Inputs file, instead of `<circuits-dir>/<circuit>_inputs.json`; `-` reads stdin
+    #[arg(long, env = "CIRCOM_BP_INPUTS")]
+    pub inputs: Option<PathBuf>,
+    /// JSON file of values for any signal missing from the inputs file
+    #[arg(long, env = "CIRCOM_BP_DEFAULTS")]
+    pub defaults: Option<PathBuf>,
+    /// File holding the hex key that decrypts inputs written by `encrypt-inputs`;
+    /// the key can also be given directly in `CIRCOM_BP_INPUTS_KEY`
+    #[arg(long, env = "CIRCOM_BP_INPUTS_KEY_FILE")]
+    pub inputs_key_file: Option<PathBuf>,
+    /// Set a signal, array element or bus field over the inputs, e.g. `path[3]=7`
+    #[arg(long = "set", value_name = "SIGNAL=VALUE")]
+    pub set: Vec<InputOverride>,
+    /// Reject input values at or above the field modulus instead of reducing them
+    #[arg(long, env = "CIRCOM_BP_STRICT_INPUTS")]
+    pub strict_inputs: bool,
+    /// Whether inputs naming no input signal are an `error` or ignored with a warning (`ignore`)
+    #[arg(long, default_value_t = UnknownInputs::Error, env = "CIRCOM_BP_UNKNOWN_INPUTS")]
+    pub unknown_inputs: UnknownInputs,
+    /// Curve to prove over; the r1cs must use its scalar field. Only bn254 is built in:
+    /// bls12-381 and pallas are recognized, but only to report an r1cs for them
+    #[arg(long, default_value_t = Curve::Bn254, env = "CIRCOM_BP_CURVE")]
+    pub curve: Curve,
+}
+
+impl CircuitArgs {
+    pub fn paths(&self) -> CircuitPaths {
+        let mut paths = CircuitPaths::new(&self.circuits_dir, &self.circuit);
+        if let Some(inputs) = &self.inputs {
+            paths.inputs = inputs.clone();
+        }
+        paths
+    }
+
+    /// Points these args at compiled outputs when given something else: a circuit
+    /// archive is unpacked, a `--source` compiled. A plain circuit name is left as is.
+    ///
+    /// The build directory of a `--source` is returned and removed when dropped, so
+    /// the caller keeps it for as long as the command runs.
+    pub fn resolve(&mut self) -> Result<Option<BuildDir>, Box<dyn Error>> {
+        if let Some(source) = &self.source {
+            let out_dir = std::env::temp_dir()
+                .join("circom-bp-build")
+                .join(std::process::id().to_string());
+            // Left behind by an earlier process that had the same pid
+            if out_dir.exists() {
+                std::fs::remove_dir_all(&out_dir)?;
+            }
+            let build_dir = BuildDir(out_dir.clone());
+            tracing::info!("Compiling {}", source.display());
+            let paths = self.compiler.compiler().compile(source, &out_dir)?;
+            let name = paths
+                .r1cs
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            // The inputs live with the source, not in the throwaway build directory
+            let source_dir = source.parent().unwrap_or(Path::new(""));
+            let inputs = source_dir.join(format!("{name}_inputs.json"));
+            if self.inputs.is_none() && inputs.exists() {
+                self.inputs = Some(inputs);
+            }
+            self.circuit = name;
+            self.circuits_dir = out_dir;
+            return Ok(Some(build_dir));
+        } else if ArchiveFormat::detect(&self.circuit).is_some() {
+            let extracted = archive::extract(&self.circuit, archive::default_cache_dir())?;
+            tracing::info!("Unpacked {} into {}", self.circuit, extracted.dir.display());
+            self.circuit = extracted.name;
+            self.circuits_dir = extracted.dir;
+        }
+        Ok(None)
+    }
+
+    /// Reads the inputs JSON at `path`, one of `paths().inputs`
+    ///
+    /// Without `--inputs`, a missing default file reads as `{}`: circuits whose
+    /// witness is fixed by constants take no inputs. A named file must exist.
+    pub fn read_inputs(&self, path: &Path) -> Result<String, InputError> {
+        if self.inputs.is_none() && !path.exists() {
+            tracing::info!("No {}, proving without inputs", path.display());
+            return Ok("{}".to_string());
+        }
+        inputs::read_inputs_decrypted(path, self.inputs_key()?.as_ref())
+    }
+
+    /// The key of encrypted inputs, from `--inputs-key-file` or `CIRCOM_BP_INPUTS_KEY`
+    pub fn inputs_key(&self) -> Result<Option<InputsKey>, InputError> {
+        Ok(match &self.inputs_key_file {
+            Some(path) => Some(InputsKey::read(path)?),
+            None => InputsKey::from_env()?,
+        })
+    }
+
+    /// Parses inputs JSON, completed from `--defaults` and overridden by `--set`,
+    /// according to `--strict-inputs` and checks it against the circuit at `paths`
+    /// according to `--unknown-inputs`
+    pub fn parse_inputs(
+        &self,
+        inputs_json: &str,
+        paths: &CircuitPaths,
+    ) -> Result<Vec<(String, Fr)>, PipelineError> {
+        let defaults_json = match &self.defaults {
+            Some(defaults) => Some(std::fs::read_to_string(defaults).map_err(InputError::from)?),
+            None => None,
+        };
+        let json = inputs::merge_inputs(inputs_json, defaults_json.as_deref(), &self.set)?;
+        let mut inputs = inputs::flatten_inputs(&json, self.strict_inputs)?;
+        pipeline::check_inputs(paths, &json, &mut inputs, self.unknown_inputs)?;
+        Ok(inputs)
+    }
+
+    /// `read_inputs` of `paths.inputs`, then `parse_inputs`
+    pub fn load_inputs(&self, paths: &CircuitPaths) -> Result<Vec<(String, Fr)>, PipelineError> {
+        self.parse_inputs(&self.read_inputs(&paths.inputs)?, paths)
+    }
+
+    /// Fails early when the circuit was compiled for another field than `--curve`
+    pub fn check_curve(&self) -> Result<(), CurveError> {
+        self.curve.check_r1cs(self.paths().r1cs)
+    }
+}
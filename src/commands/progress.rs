@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use indicatif::{ProgressBar, ProgressStyle};
+use tracing::Level;
+
+/// Runs `f` behind a spinner showing `message` and the elapsed time
+///
+/// Like every progress indicator here, the spinner is hidden when info logs are
+/// filtered out (`--quiet`) and indicatif skips drawing when stderr is not a terminal.
+pub fn spinner<T>(message: &'static str, f: impl FnOnce() -> T) -> T {
+    let spinner = if tracing::enabled!(Level::INFO) {
+        ProgressBar::new_spinner()
+    } else {
+        ProgressBar::hidden()
+    };
+    spinner.set_style(
+        ProgressStyle::with_template("{spinner} {msg} [{elapsed}]").expect("valid template"),
+    );
+    spinner.set_message(message);
+    spinner.enable_steady_tick(Duration::from_millis(100));
+
+    let result = f();
+    spinner.finish_and_clear();
+    result
+}
+
+/// Progress bar over `len` jobs; log through `ProgressBar::suspend` while it is shown
+pub fn bar(len: usize) -> ProgressBar {
+    let bar = if tracing::enabled!(Level::INFO) {
+        ProgressBar::new(len as u64)
+    } else {
+        ProgressBar::hidden()
+    };
+    bar.set_style(
+        ProgressStyle::with_template("{bar:40} {pos}/{len} {msg} [{elapsed}]")
+            .expect("valid template"),
+    );
+    bar
+}
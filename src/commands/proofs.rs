@@ -0,0 +1,87 @@
+use std::{path::PathBuf, time::Duration};
+
+use circom_bp::registry::{ProofRecord, Registry, RegistryError};
+use clap::{Args, Subcommand};
+
+/// Where generated proofs are recorded
+#[derive(Args, Debug, Clone, Default)]
+pub struct RegistryArgs {
+    /// sqlite database recording generated proofs and their verification status
+    #[arg(long, env = "CIRCOM_BP_REGISTRY")]
+    pub registry: Option<PathBuf>,
+}
+
+impl RegistryArgs {
+    /// The registry, if one was configured
+    pub fn open(&self) -> Result<Option<Registry>, RegistryError> {
+        self.registry.as_ref().map(Registry::open).transpose()
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ProofsArgs {
+    #[command(flatten)]
+    pub registry: RegistryArgs,
+    #[command(subcommand)]
+    pub command: ProofsCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProofsCommand {
+    /// List recorded proofs, oldest first
+    List {
+        /// Only list proofs of this circuit
+        #[arg(long)]
+        circuit: Option<String>,
+    },
+    /// Show everything recorded about one proof
+    Show { id: i64 },
+    /// Forget proofs whose files are gone, and optionally old ones
+    Prune {
+        /// Also forget proofs recorded more than this many days ago
+        #[arg(long)]
+        older_than_days: Option<u64>,
+    },
+}
+
+pub fn run(args: ProofsArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let registry = args
+        .registry
+        .open()?
+        .ok_or("No registry configured: pass --registry or set CIRCOM_BP_REGISTRY")?;
+
+    match args.command {
+        ProofsCommand::List { circuit } => {
+            for record in registry.list(circuit.as_deref())? {
+                println!(
+                    "{:>5}  {:<10}  {:<20}  {}",
+                    record.id, record.status, record.circuit, record.proof_path
+                );
+            }
+        }
+        ProofsCommand::Show { id } => {
+            let record = registry
+                .get(id)?
+                .ok_or_else(|| format!("No proof with id {id}"))?;
+            print_record(&record);
+        }
+        ProofsCommand::Prune { older_than_days } => {
+            let older_than = older_than_days.map(|days| Duration::from_secs(days * 24 * 60 * 60));
+            println!("Removed {} records", registry.prune(older_than)?);
+        }
+    }
+    Ok(())
+}
+
+fn print_record(record: &ProofRecord) {
+    println!("id:                  {}", record.id);
+    println!("circuit:             {}", record.circuit);
+    println!("circuit fingerprint: {}", record.circuit_fingerprint);
+    println!("input hash:          {}", record.input_hash);
+    println!("proof:               {}", record.proof_path);
+    println!("created at:          {}", record.created_at);
+    if let Some(verified_at) = record.verified_at {
+        println!("verified at:         {verified_at}");
+    }
+    println!("status:              {}", record.status);
+}
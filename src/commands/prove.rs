@@ -0,0 +1,600 @@
+use std::{collections::BTreeMap, path::PathBuf, time::Instant};
+
+use circom_bp::{
+    bundle::{BundleError, ProofBundle},
+    commitment::{CommitmentError, InputOpenings},
+    compliance::{ComplianceError, ComplianceLog, ComplianceRecord, JsonLinesLog},
+    conversion::{ConversionError, ConversionReport, PaddingStrategy, blind, public_signal_count},
+    conversion_cache::ConversionCache,
+    crs::{self, CrsError, CrsParams},
+    curve::CurveError,
+    estimate::ResourceEstimate,
+    fingerprint::HashAlgorithm,
+    inputs::{self, InputError},
+    limits::{LimitError, ResourceLimits},
+    oracle::{self, ConversionMismatch},
+    pipeline::{self, PipelineError, RetryPolicy, WitnessSource},
+    range::{self, BitDecomposition},
+    registry::{NewProof, RegistryError, VerificationStatus},
+    simplify::{self, Deduplication, Elimination, Sharing},
+    sink,
+    witness_cache::WitnessCache,
+    wtns,
+};
+use clap::Args;
+use rand::rngs::OsRng;
+use serde::Serialize;
+use tracing::info;
+
+use super::{CircuitArgs, progress, proofs::RegistryArgs};
+
+#[derive(Args, Debug, Default)]
+pub struct ProveArgs {
+    #[command(flatten)]
+    pub circuit: CircuitArgs,
+    /// Write `proof.bin`, `statement.json` and `public.json` into this directory,
+    /// or upload them with HTTP PUT when given an `http(s)://` URL
+    #[arg(
+        long = "output-dir",
+        visible_alias = "out",
+        env = "CIRCOM_BP_OUTPUT_DIR"
+    )]
+    pub out: Option<String>,
+    /// Hex-encoded 32-byte seed the CRS is derived from
+    #[arg(long, env = "CIRCOM_BP_CRS_SEED")]
+    pub crs_seed: Option<String>,
+    /// Hash used to fingerprint the circuit in written artifacts (blake3 or sha256)
+    #[arg(long, default_value_t = HashAlgorithm::Blake3, env = "CIRCOM_BP_HASH")]
+    pub hash: HashAlgorithm,
+    /// Print a single JSON report on stdout (progress is logged to stderr)
+    #[arg(long)]
+    pub json: bool,
+    /// Print the wall-clock time of every pipeline phase
+    #[arg(long)]
+    pub stats: bool,
+    /// How to fill the padding gates: zero, dummy (satisfied 1 · 1 = 1 gates) or
+    /// random (random satisfied gates)
+    #[arg(long, default_value_t = PaddingStrategy::Zero, env = "CIRCOM_BP_PADDING")]
+    pub padding: PaddingStrategy,
+    /// Shorthand for `--padding random`
+    #[arg(long, env = "CIRCOM_BP_RANDOM_PADDING")]
+    pub random_padding: bool,
+    /// Drop constraints that repeat an earlier one up to a scalar multiple, and
+    /// trivial ones, before conversion; verifiers must pass `--dedup` too
+    #[arg(long, env = "CIRCOM_BP_DEDUP")]
+    pub dedup: bool,
+    /// Rewrite constraints repeating an earlier constraint's product as linear ones,
+    /// so they share its gate; verifiers must pass `--share-gates` too
+    #[arg(long, env = "CIRCOM_BP_SHARE_GATES")]
+    pub share_gates: bool,
+    /// Substitute away private variables defined by a linear constraint and used
+    /// at most once more before conversion; verifiers must pass `--eliminate` too
+    #[arg(long, conflicts_with = "openings", env = "CIRCOM_BP_ELIMINATE")]
+    pub eliminate: bool,
+    /// Only parse the r1cs and estimate resource needs; no witness or proof is produced
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Prove this precomputed `.wtns` witness, e.g. from snarkjs, instead of running
+    /// the wasm witness calculator over inputs
+    #[arg(long, conflicts_with_all = ["inputs", "defaults", "set"])]
+    pub witness: Option<PathBuf>,
+    /// Also write the generated witness to this snarkjs-compatible `.wtns` file, to
+    /// cross-check it against the groth16/plonk toolchain
+    #[arg(long)]
+    pub witness_out: Option<PathBuf>,
+    /// Witness calculator: the in-process wasm one, or the native one `circom --c`
+    /// generates (`<name>_cpp/<name>`, built with `make`)
+    #[arg(long, default_value_t = WitnessSource::Wasm, env = "CIRCOM_BP_WITNESS_SOURCE")]
+    pub witness_source: WitnessSource,
+    /// Blindings written by `commit`, so the proof opens the published commitments
+    #[arg(long, env = "CIRCOM_BP_OPENINGS")]
+    pub openings: Option<PathBuf>,
+    #[command(flatten)]
+    pub registry: RegistryArgs,
+    /// Append a JSON line describing the proof's cryptographic parameters to this file
+    #[arg(long, env = "CIRCOM_BP_COMPLIANCE_LOG")]
+    pub compliance_log: Option<PathBuf>,
+    /// Always run the witness calculator instead of reusing witnesses cached for the
+    /// same wasm and inputs
+    #[arg(long, env = "CIRCOM_BP_NO_WITNESS_CACHE")]
+    pub no_witness_cache: bool,
+    /// Always convert the circuit instead of reusing a conversion cached for the
+    /// same r1cs, simplification passes and committed variables
+    #[arg(long, env = "CIRCOM_BP_NO_CONVERSION_CACHE")]
+    pub no_conversion_cache: bool,
+    /// Check that the converted circuit is satisfied exactly when the R1CS is, as
+    /// debug builds always do
+    #[arg(long, env = "CIRCOM_BP_CHECK_CONVERSION")]
+    pub check_conversion: bool,
+    /// Prove up to this many times, with fresh randomness, when the prover fails
+    /// transiently
+    #[arg(
+        long,
+        default_value_t = RetryPolicy::default().max_attempts,
+        env = "CIRCOM_BP_MAX_ATTEMPTS"
+    )]
+    pub max_attempts: usize,
+}
+
+impl ProveArgs {
+    /// The padding strategy, `--random-padding` overriding `--padding`
+    pub fn padding_strategy(&self) -> PaddingStrategy {
+        if self.random_padding {
+            PaddingStrategy::Random
+        } else {
+            self.padding
+        }
+    }
+}
+
+/// Pipeline phases in execution order, as keyed in `timings_ms`
+const PHASES: [&str; 5] = ["witness", "conversion", "crs", "prove", "verify"];
+
+/// Outcome of a `prove` run, printed as JSON with `--json`
+#[derive(Debug, Default, Serialize)]
+struct ProveReport {
+    circuit: String,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_category: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    witness_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failed_constraints: Option<usize>,
+    /// The first violated constraints, as `constraint i: a * b = a·b, expected c`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failures: Vec<String>,
+    constraints: Option<usize>,
+    variables: Option<usize>,
+    proof_size: Option<usize>,
+    proof_path: Option<String>,
+    /// Proving attempts made, including retries after transient failures
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attempts: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deduplication: Option<Deduplication>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sharing: Option<Sharing>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elimination: Option<Elimination>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    padding: Option<PaddingStrategy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conversion: Option<ConversionReport>,
+    timings_ms: BTreeMap<&'static str, f64>,
+}
+
+impl ProveReport {
+    /// Runs `f` as the named phase, recording its wall-clock time
+    fn time<T>(&mut self, phase: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.timings_ms
+            .insert(phase, start.elapsed().as_secs_f64() * 1e3);
+        result
+    }
+}
+
+pub fn run(args: ProveArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if args.dry_run {
+        return dry_run(&args);
+    }
+
+    let mut report = ProveReport {
+        circuit: args.circuit.circuit.clone(),
+        ..Default::default()
+    };
+    let result = prove(&args, &mut report);
+
+    report.success = result.is_ok();
+    if let Err(e) = &result {
+        report.error_category = Some(e.category());
+        report.error = Some(e.to_string());
+        if let ProveError::Pipeline(PipelineError::UnsatisfiedConstraints {
+            failed, first, ..
+        }) = e
+        {
+            report.failed_constraints = Some(*failed);
+            report.failures = first.iter().map(ToString::to_string).collect();
+        }
+    }
+    if args.json {
+        println!("{}", serde_json::to_string(&report)?);
+    } else if args.stats {
+        print_stats(&report);
+    }
+    Ok(result?)
+}
+
+fn dry_run(args: &ProveArgs) -> Result<(), Box<dyn std::error::Error>> {
+    args.circuit.check_curve()?;
+    let mut r1cs = pipeline::load_r1cs(&args.circuit.paths().r1cs)?;
+    let deduplication = args.dedup.then(|| {
+        let (deduplicated, deduplication) = simplify::deduplicate(&r1cs);
+        r1cs = deduplicated;
+        deduplication
+    });
+    let sharing = args.share_gates.then(|| {
+        let (shared, sharing) = simplify::share_products(&r1cs);
+        r1cs = shared;
+        sharing
+    });
+    let elimination = args.eliminate.then(|| {
+        let (reduced, elimination) = simplify::eliminate_intermediates(&r1cs);
+        r1cs = reduced;
+        elimination
+    });
+    let estimate = ResourceEstimate::from_r1cs(&r1cs);
+    let padding = args
+        .padding_strategy()
+        .overhead(&ConversionReport::from_r1cs(&r1cs));
+    if args.json {
+        println!("{}", serde_json::to_string(&estimate)?);
+        return Ok(());
+    }
+
+    println!(
+        "Constraints:       {} (padded to {} gates)",
+        estimate.constraints, estimate.gates
+    );
+    println!(
+        "Variables:         {} ({} committed)",
+        estimate.variables, estimate.commitments
+    );
+    if let Some(deduplication) = deduplication {
+        println!(
+            "Deduplicated:      {} constraints removed ({} repeated, {} trivial)",
+            deduplication.removed(),
+            deduplication.duplicates,
+            deduplication.trivial
+        );
+    }
+    if let Some(sharing) = sharing {
+        println!(
+            "Shared gates:      {} of {} products reuse an earlier gate",
+            sharing.shared, sharing.products_before
+        );
+    }
+    if let Some(elimination) = elimination {
+        println!(
+            "Eliminated:        {} intermediate variables and their constraints",
+            elimination.eliminated()
+        );
+    }
+    let decompositions = range::bit_decompositions(&r1cs);
+    if !decompositions.is_empty() {
+        println!(
+            "Range checks:      {} bit decompositions ({} bits, {} constraints)",
+            decompositions.len(),
+            decompositions.iter().map(|d| d.bits.len()).sum::<usize>(),
+            decompositions
+                .iter()
+                .map(BitDecomposition::constraints)
+                .sum::<usize>()
+        );
+    }
+    println!(
+        "Padding:           {} gates, {} rows ({}, {} random scalars)",
+        padding.gates, padding.rows, padding.strategy, padding.random_scalars
+    );
+    println!("CRS size:          {}", format_bytes(estimate.crs_bytes));
+    println!("Proof size:        ~{}", format_bytes(estimate.proof_bytes));
+    println!(
+        "Peak memory:       ~{}",
+        format_bytes(estimate.memory_bytes)
+    );
+    Ok(())
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Prints the phases that ran, in pipeline order, followed by their total
+fn print_stats(report: &ProveReport) {
+    for phase in PHASES {
+        if let Some(ms) = report.timings_ms.get(phase) {
+            println!("{phase:>12}: {ms:>10.3} ms");
+        }
+    }
+    let total: f64 = report.timings_ms.values().sum();
+    println!("{:>12}: {total:>10.3} ms", "total");
+    if let Some(conversion) = &report.conversion {
+        println!(
+            "{:>12}: {} used + {} padding ({:.1}% overhead)",
+            "gates",
+            conversion.used_gates,
+            conversion.padding_gates,
+            100.0 * conversion.padding_overhead()
+        );
+        println!(
+            "{:>12}: {} used + {} padding",
+            "rows",
+            conversion.rows - conversion.padding_rows,
+            conversion.padding_rows
+        );
+        println!(
+            "{:>12}: {} of {} constraints folded into one row each",
+            "linear", conversion.linear_constraints, conversion.constraints
+        );
+        println!(
+            "{:>12}: {} nonzero weights ({:.2}% dense)",
+            "density",
+            conversion.nonzero_weights,
+            100.0 * conversion.density()
+        );
+    }
+}
+
+fn prove(args: &ProveArgs, report: &mut ProveReport) -> Result<(), ProveError> {
+    args.circuit.check_curve()?;
+    let paths = args.circuit.paths();
+    let seed = match &args.crs_seed {
+        Some(seed) => crs::parse_seed(seed)?,
+        None => crs::DEFAULT_SEED,
+    };
+
+    let (mut circom, input_hash) = match &args.witness {
+        Some(wtns) => {
+            let circom = report.time("witness", || pipeline::load_witness(&paths, wtns))?;
+            (circom, args.hash.digest_file(wtns)?)
+        }
+        None => {
+            // Read once: stdin can't be read again for the registry's input hash
+            let inputs_json = args.circuit.read_inputs(&paths.inputs)?;
+            let inputs = args.circuit.parse_inputs(&inputs_json, &paths)?;
+            // Secret inputs must not leave a witness behind on disk
+            let private = inputs::is_private_source(&paths.inputs);
+            let witness_cache = (!args.no_witness_cache && !private)
+                .then(|| WitnessCache::new(WitnessCache::default_dir()));
+            let circom = report.time("witness", || {
+                pipeline::generate_witness_from(
+                    args.witness_source,
+                    &paths,
+                    &inputs,
+                    witness_cache.as_ref(),
+                )
+            })?;
+            (circom, args.hash.digest(inputs_json.as_bytes()))
+        }
+    };
+    report.witness_size = circom.witness.as_ref().map(Vec::len);
+    info!(
+        "Generated witness with {} values",
+        report.witness_size.unwrap_or(0)
+    );
+    if let (Some(path), Some(values)) = (&args.witness_out, &circom.witness) {
+        wtns::write_wtns(path, values).map_err(ProveError::WriteWitness)?;
+        info!("Wrote witness to {}", path.display());
+    }
+    if args.dedup {
+        let (r1cs, deduplication) = simplify::deduplicate(&circom.r1cs);
+        info!(
+            "Deduplication removed {} of {} constraints ({} repeated, {} trivial)",
+            deduplication.removed(),
+            deduplication.constraints_before,
+            deduplication.duplicates,
+            deduplication.trivial
+        );
+        circom.r1cs = r1cs;
+        report.deduplication = Some(deduplication);
+    }
+    if args.share_gates {
+        let (r1cs, sharing) = simplify::share_products(&circom.r1cs);
+        info!(
+            "Gate sharing rewrote {} of {} products as linear constraints",
+            sharing.shared, sharing.products_before
+        );
+        circom.r1cs = r1cs;
+        report.sharing = Some(sharing);
+    }
+    if args.eliminate {
+        let (reduced, elimination) =
+            simplify::eliminate_intermediates_in(&circom).map_err(PipelineError::from)?;
+        info!(
+            "Eliminated {} intermediate variables, leaving {} variables and {} constraints",
+            elimination.eliminated(),
+            elimination.variables_after,
+            elimination.constraints_after
+        );
+        circom = reduced;
+        report.elimination = Some(elimination);
+    }
+    // The CRS and dense weight matrices dominate; refuse before allocating them
+    ResourceLimits::current()
+        .check_memory(ResourceEstimate::from_r1cs(&circom.r1cs).memory_bytes)?;
+
+    // Convert to bulletproofs format with power-of-2 padding, committing the
+    // private signals of any openings alongside the public ones
+    let mut rng = OsRng;
+    let openings = args
+        .openings
+        .as_ref()
+        .map(InputOpenings::read)
+        .transpose()?;
+    let committed = openings
+        .as_ref()
+        .map(InputOpenings::variables)
+        .unwrap_or_default();
+    let elimination = report.elimination.clone();
+    let conversion_cache =
+        (!args.no_conversion_cache).then(|| ConversionCache::new(ConversionCache::default_dir()));
+    let passes = super::simplify_passes(args.dedup, args.share_gates, args.eliminate);
+    let (circuit, mut witness, conversion) = report.time("conversion", || {
+        let (circuit, witness, conversion) = pipeline::convert_cached(
+            &circom,
+            &committed,
+            &paths,
+            &passes,
+            conversion_cache.as_ref(),
+        )?;
+        if oracle::enabled(args.check_conversion) {
+            oracle::check_conversion(&circom, &circuit, &witness)?;
+        }
+        pipeline::check_converted(&circom, &circuit, &witness, &paths, elimination.as_ref())?;
+        Ok::<_, ProveError>((circuit.into_dense(), witness, conversion))
+    })?;
+    let padding = args.padding_strategy();
+    padding.apply(&mut witness, conversion.used_gates, &mut rng);
+    blind(&mut witness, public_signal_count(&circom.r1cs), &mut rng);
+    report.constraints = Some(conversion.constraints);
+    report.variables = Some(conversion.variables);
+    info!(
+        "Bulletproof circuit: {} constraints ({} linear, {} multiplicative) padded to {} gates, {} variables of which {} committed, {:.1}% sparse",
+        conversion.constraints,
+        conversion.linear_constraints,
+        conversion.multiplicative_constraints,
+        conversion.gates,
+        conversion.variables,
+        conversion.commitments,
+        conversion.sparsity * 100.0
+    );
+    info!(
+        "Padding: {} gates and {} rows, filled with {padding}",
+        conversion.padding_gates, conversion.padding_rows
+    );
+    report.padding = Some(padding);
+    report.conversion = Some(conversion);
+
+    // Derive the CRS (circuit dimension is already power-of-2)
+    let crs_params = CrsParams::with_seed(circuit.dim(), seed);
+    // Commitments published by `commit` only hold under the same CRS
+    if let Some(openings) = &openings {
+        if openings.circuit != args.circuit.circuit || openings.crs != crs_params {
+            return Err(ProveError::OpeningsMismatch);
+        }
+        openings.apply(&mut witness, public_signal_count(&circom.r1cs))?;
+    }
+    info!("Generating CRS with size: {}", crs_params.size);
+    let crs = report.time("crs", || {
+        progress::spinner("Generating CRS", || crs_params.derive())
+    });
+
+    info!("Generating proof...");
+    let policy = RetryPolicy {
+        max_attempts: args.max_attempts,
+    };
+    let (result, attempts) = report.time("prove", || {
+        progress::spinner("Proving", || {
+            pipeline::prove_with_retry(&crs, &circuit, &witness, &mut rng, policy)
+        })
+    });
+    report.attempts = Some(attempts);
+    let (statement, proof) = result?;
+    report.proof_size = Some(proof.len());
+
+    info!("Verifying proof...");
+    report.time("verify", || {
+        progress::spinner("Verifying", || {
+            pipeline::verify(&crs, &circuit, &statement, &proof, &mut rng)
+        })
+    })?;
+
+    info!("✅ Proof verified successfully!");
+
+    if let Some(out) = &args.out {
+        let fingerprint = args.hash.digest_file(&paths.r1cs)?;
+        let mut sink = sink::open(out);
+        ProofBundle::new(&args.circuit.circuit, crs_params, &statement, &proof)?
+            .with_circuit_fingerprint(fingerprint.clone())
+            .with_public_signals(pipeline::public_signals(&circom))
+            .write_parts(sink.as_mut())?;
+        report.proof_path = Some(sink.location("proof.bin"));
+        info!("Wrote proof artifacts to {out}");
+
+        if let Some(registry) = args.registry.open()? {
+            registry.record(&NewProof {
+                circuit: &args.circuit.circuit,
+                circuit_fingerprint: &fingerprint.to_string(),
+                input_hash: &input_hash.to_string(),
+                proof_path: &sink.location("proof.bin"),
+                status: VerificationStatus::Verified,
+            })?;
+        }
+    }
+
+    if let Some(path) = &args.compliance_log {
+        let mut record = ComplianceRecord::new(
+            &args.circuit.circuit,
+            args.hash.digest_file(&paths.r1cs)?,
+            crs_params,
+            &witness,
+            args.padding_strategy() == PaddingStrategy::Random,
+        );
+        if let Some(location) = &report.proof_path {
+            record = record.with_proof(location.clone());
+        }
+        JsonLinesLog::new(path).record(&record)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ProveError {
+    #[error(transparent)]
+    Pipeline(#[from] PipelineError),
+    #[error(transparent)]
+    Crs(#[from] CrsError),
+    #[error(transparent)]
+    Curve(#[from] CurveError),
+    #[error(transparent)]
+    Bundle(#[from] BundleError),
+    #[error(transparent)]
+    Commitment(#[from] CommitmentError),
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+    #[error(transparent)]
+    Compliance(#[from] ComplianceError),
+    #[error(transparent)]
+    Limit(#[from] LimitError),
+    #[error(transparent)]
+    ConversionMismatch(#[from] ConversionMismatch),
+    #[error("Openings were made for another circuit or CRS")]
+    OpeningsMismatch,
+    #[error("Failed to write witness: {0}")]
+    WriteWitness(std::io::Error),
+    #[error("Failed to fingerprint circuit: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<InputError> for ProveError {
+    fn from(e: InputError) -> Self {
+        ProveError::Pipeline(e.into())
+    }
+}
+
+impl From<ConversionError> for ProveError {
+    fn from(e: ConversionError) -> Self {
+        ProveError::Pipeline(e.into())
+    }
+}
+
+impl ProveError {
+    pub(crate) fn category(&self) -> &'static str {
+        match self {
+            ProveError::Pipeline(e) => e.category(),
+            ProveError::Crs(_) | ProveError::Curve(_) => "input",
+            ProveError::Bundle(_)
+            | ProveError::Registry(_)
+            | ProveError::Compliance(_)
+            | ProveError::WriteWitness(_) => "output",
+            ProveError::Commitment(_) | ProveError::OpeningsMismatch => "input",
+            ProveError::Limit(_) => "resource",
+            ProveError::ConversionMismatch(_) => "conversion",
+            ProveError::Io(_) => "load",
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use tracing::info;
+
+use super::{
+    CircuitArgs,
+    build::CompilerArgs,
+    prove::{self, ProveArgs},
+};
+
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    /// The `.circom` source to compile and prove
+    pub source: PathBuf,
+    /// Inputs file (defaults to `<name>_inputs.json` next to the compiled outputs)
+    #[arg(long)]
+    pub inputs: Option<PathBuf>,
+    /// Where to place the compiled outputs (defaults to the source's directory)
+    #[arg(long)]
+    pub out_dir: Option<PathBuf>,
+    #[command(flatten)]
+    pub compiler: CompilerArgs,
+}
+
+/// Compiles, proves and verifies in one go, stopping at the first stage that fails
+pub fn run(args: RunArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let out_dir = args.out_dir.clone().unwrap_or_else(|| {
+        args.source
+            .parent()
+            .map_or_else(|| PathBuf::from("."), PathBuf::from)
+    });
+
+    info!("Compiling {}", args.source.display());
+    let paths = args.compiler.compiler().compile(&args.source, &out_dir)?;
+    let circuit = paths
+        .r1cs
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    prove::run(ProveArgs {
+        circuit: CircuitArgs {
+            circuit,
+            circuits_dir: out_dir,
+            inputs: args.inputs,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+}
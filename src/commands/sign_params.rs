@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use circom_bp::{
+    atomic,
+    conversion::r1cs_to_bulletproofs,
+    crs::{self, CrsParams},
+    fingerprint::HashAlgorithm,
+    manifest::{self, ParamsManifest},
+    pipeline,
+};
+use clap::Args;
+use rand::rngs::OsRng;
+use tracing::info;
+
+use super::CircuitArgs;
+
+#[derive(Args, Debug)]
+pub struct KeygenArgs {
+    /// Where to write the hex-encoded secret key
+    #[arg(long)]
+    pub out: PathBuf,
+}
+
+/// Generates an ed25519 key for signing params manifests
+pub fn keygen(args: KeygenArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if args.out.exists() {
+        return Err(format!("{} already exists", args.out.display()).into());
+    }
+    let key = manifest::generate_key(&mut OsRng);
+    atomic::write(&args.out, hex::encode(key.to_bytes()) + "\n")?;
+    info!("Wrote secret key to {}", args.out.display());
+    println!("{}", hex::encode(key.verifying_key().to_bytes()));
+    Ok(())
+}
+
+#[derive(Args, Debug)]
+pub struct SignParamsArgs {
+    #[command(flatten)]
+    pub circuit: CircuitArgs,
+    /// File holding the hex-encoded secret key written by `keygen`
+    #[arg(long, env = "CIRCOM_BP_SIGNING_KEY")]
+    pub key: PathBuf,
+    /// Hex-encoded 32-byte seed the CRS is derived from
+    #[arg(long, env = "CIRCOM_BP_CRS_SEED")]
+    pub crs_seed: Option<String>,
+    /// Hash used to fingerprint the circuit (blake3 or sha256)
+    #[arg(long, default_value_t = HashAlgorithm::Blake3, env = "CIRCOM_BP_HASH")]
+    pub hash: HashAlgorithm,
+    /// Where to write the signed manifest (defaults to `<circuit>.params.json`)
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+/// Signs the circuit fingerprint and CRS parameters for distribution to verifiers
+pub fn run(args: SignParamsArgs) -> Result<(), Box<dyn std::error::Error>> {
+    args.circuit.check_curve()?;
+    let paths = args.circuit.paths();
+    let name = &args.circuit.circuit;
+    let seed = match &args.crs_seed {
+        Some(seed) => crs::parse_seed(seed)?,
+        None => crs::DEFAULT_SEED,
+    };
+    let key = manifest::parse_signing_key(&std::fs::read_to_string(&args.key)?)?;
+
+    let circuit = r1cs_to_bulletproofs(&pipeline::load_r1cs(&paths.r1cs)?)?;
+    let signed = ParamsManifest {
+        circuit: name.clone(),
+        circuit_fingerprint: args.hash.digest_file(&paths.r1cs)?,
+        crs: CrsParams::with_seed(circuit.dim(), seed),
+    }
+    .sign(&key)?;
+
+    let out = args
+        .out
+        .unwrap_or_else(|| PathBuf::from(format!("{name}.params.json")));
+    signed.write(&out)?;
+    info!("Wrote signed params manifest to {}", out.display());
+    Ok(())
+}
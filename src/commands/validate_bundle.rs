@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+
+use circom_bp::bundle::{BUNDLE_SCHEMA, SchemaIssue, validate_bundle_json};
+use clap::Args;
+use serde::Serialize;
+
+#[derive(Args, Debug)]
+pub struct ValidateBundleArgs {
+    /// Proof bundles to check
+    #[arg(required_unless_present = "print_schema")]
+    pub bundles: Vec<PathBuf>,
+    /// Print one JSON report per bundle on stdout
+    #[arg(long)]
+    pub json: bool,
+    /// Print the JSON Schema bundles are checked against and exit
+    #[arg(long)]
+    pub print_schema: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct BundleReport {
+    bundle: String,
+    valid: bool,
+    issues: Vec<SchemaIssue>,
+}
+
+/// Lints proof bundles against the published schema, without verifying them
+pub fn run(args: ValidateBundleArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if args.print_schema {
+        print!("{BUNDLE_SCHEMA}");
+        return Ok(());
+    }
+
+    let mut invalid = 0;
+    for path in &args.bundles {
+        let issues = match std::fs::read_to_string(path) {
+            Ok(json) => validate_bundle_json(&json),
+            Err(e) => vec![SchemaIssue {
+                path: String::new(),
+                message: format!("unreadable: {e}"),
+            }],
+        };
+        if !issues.is_empty() {
+            invalid += 1;
+        }
+        let report = BundleReport {
+            bundle: path.display().to_string(),
+            valid: issues.is_empty(),
+            issues,
+        };
+        if args.json {
+            println!("{}", serde_json::to_string(&report)?);
+            continue;
+        }
+        if report.valid {
+            println!("✅ {}", report.bundle);
+        } else {
+            println!("❌ {}", report.bundle);
+            for issue in &report.issues {
+                let path = if issue.path.is_empty() {
+                    "<root>"
+                } else {
+                    &issue.path
+                };
+                println!("   {path}: {}", issue.message);
+            }
+        }
+    }
+
+    if invalid > 0 {
+        return Err(InvalidBundles {
+            invalid,
+            total: args.bundles.len(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{invalid} of {total} proof bundles do not match the schema")]
+pub struct InvalidBundles {
+    pub invalid: usize,
+    pub total: usize,
+}
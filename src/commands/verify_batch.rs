@@ -0,0 +1,319 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use circom_bp::{
+    bundle::ProofBundle,
+    commitment::InputCommitments,
+    conversion::{public_signal_count, r1cs_to_bulletproofs_committing},
+    crs,
+    estimate::verification_group_ops,
+    manifest::{self, SignedManifest},
+    pipeline,
+    profile::VerifierProfile,
+    registry::VerificationStatus,
+    simplify,
+};
+use clap::Args;
+use rand::rngs::OsRng;
+use serde::Serialize;
+use tracing::{error, info, warn};
+
+use super::{CircuitArgs, progress, proofs::RegistryArgs};
+
+#[derive(Args, Debug)]
+pub struct VerifyBatchArgs {
+    #[command(flatten)]
+    pub circuit: CircuitArgs,
+    /// Proof bundles to verify; directories are searched for `*.proof.json` files
+    #[arg(required = true)]
+    pub bundles: Vec<PathBuf>,
+    /// Commitments published by `commit` that every proof must open
+    #[arg(long, env = "CIRCOM_BP_COMMITMENTS")]
+    pub commitments: Option<PathBuf>,
+    /// Only accept proofs whose CRS matches a params manifest signed by this
+    /// hex-encoded ed25519 public key
+    #[arg(
+        long,
+        value_name = "PUBKEY",
+        requires = "params",
+        env = "CIRCOM_BP_PARAMS_PUBKEY"
+    )]
+    pub require_signed_params: Option<String>,
+    /// Signed params manifest written by `sign-params`
+    #[arg(long, requires = "require_signed_params", env = "CIRCOM_BP_PARAMS")]
+    pub params: Option<PathBuf>,
+    /// Bundle of optional checks to run: standard, strict or paranoid
+    #[arg(long, default_value_t = VerifierProfile::Standard, env = "CIRCOM_BP_PROFILE")]
+    pub profile: VerifierProfile,
+    /// Rebuild the circuit without repeated and trivial constraints, for proofs made
+    /// with `--dedup`
+    #[arg(long, env = "CIRCOM_BP_DEDUP")]
+    pub dedup: bool,
+    /// Rebuild the circuit with repeated products sharing a gate, for proofs made
+    /// with `--share-gates`
+    #[arg(long, env = "CIRCOM_BP_SHARE_GATES")]
+    pub share_gates: bool,
+    /// Rebuild the circuit without the intermediate variables substituted away by
+    /// `--eliminate`, for proofs made with it
+    #[arg(long, conflicts_with = "commitments", env = "CIRCOM_BP_ELIMINATE")]
+    pub eliminate: bool,
+    /// Hex-encoded 32-byte CRS seed the strict profiles expect (defaults to the built-in seed)
+    #[arg(long, env = "CIRCOM_BP_CRS_SEED")]
+    pub crs_seed: Option<String>,
+    #[command(flatten)]
+    pub registry: RegistryArgs,
+    /// Print a cost breakdown of every verification: deserialization, statement
+    /// reconstruction, transcript replay, MSM time and group operations
+    #[arg(long)]
+    pub report: bool,
+    /// Print the cost breakdown as one JSON object per bundle instead
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Where the time of verifying one bundle went, printed with `--report`
+#[derive(Debug, Default, Serialize)]
+struct CostReport {
+    bundle: String,
+    verified: bool,
+    deserialize_ms: f64,
+    /// CRS derivation (or cache lookup) and the checks of the bundle against the circuit
+    statement_ms: f64,
+    transcript_ms: f64,
+    /// Checking the proof, dominated by the verifier's multi-scalar multiplication
+    msm_ms: f64,
+    total_ms: f64,
+    /// Scalar multiplications in the verifier's MSM
+    group_ops: usize,
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1e3
+}
+
+/// Verifies many proof bundles of one circuit, reporting a verdict per bundle
+pub fn run(args: VerifyBatchArgs) -> Result<(), Box<dyn std::error::Error>> {
+    args.circuit.check_curve()?;
+    let checks = args.profile.checks();
+    if checks.require_signed_params && args.require_signed_params.is_none() {
+        return Err(format!(
+            "the {} profile requires --require-signed-params and --params",
+            args.profile
+        )
+        .into());
+    }
+    let expected_seed = match &args.crs_seed {
+        Some(seed) => crs::parse_seed(seed)?,
+        None => crs::DEFAULT_SEED,
+    };
+    let r1cs_path = args.circuit.paths().r1cs;
+    let r1cs_bytes = std::fs::read(&r1cs_path)?;
+    let mut r1cs = pipeline::load_r1cs(&r1cs_path)?;
+    if args.dedup {
+        r1cs = simplify::deduplicate(&r1cs).0;
+    }
+    if args.share_gates {
+        r1cs = simplify::share_products(&r1cs).0;
+    }
+    if args.eliminate {
+        r1cs = simplify::eliminate_intermediates(&r1cs).0;
+    }
+    let commitments = args
+        .commitments
+        .as_ref()
+        .map(InputCommitments::read)
+        .transpose()?;
+    if let Some(commitments) = &commitments
+        && commitments.circuit != args.circuit.circuit
+    {
+        return Err(format!("commitments are for circuit '{}'", commitments.circuit).into());
+    }
+    // Proofs opening commitments also commit those signals in their statement
+    let committed = commitments
+        .as_ref()
+        .map(InputCommitments::variables)
+        .unwrap_or_default();
+    let circuit = r1cs_to_bulletproofs_committing(&r1cs, &committed)?;
+    let public = public_signal_count(&r1cs);
+    let num_commitments = circuit.w_v.first().map_or(0, Vec::len);
+    let signed_crs = match (&args.require_signed_params, &args.params) {
+        (Some(public_key), Some(params)) => {
+            let signed = SignedManifest::read(params)?;
+            let manifest = signed.verify(&manifest::parse_verifying_key(public_key)?)?;
+            if manifest.circuit != args.circuit.circuit {
+                return Err(
+                    format!("params manifest is for circuit '{}'", manifest.circuit).into(),
+                );
+            }
+            let fingerprint = &manifest.circuit_fingerprint;
+            if fingerprint.algorithm.digest(&r1cs_bytes) != *fingerprint {
+                return Err(format!(
+                    "{} does not match the signed fingerprint",
+                    r1cs_path.display()
+                )
+                .into());
+            }
+            Some(manifest.crs)
+        }
+        _ => None,
+    };
+
+    let registry = args.registry.open()?;
+
+    let bundles = bundle_files(&args.bundles)?;
+    let mut crs_cache = HashMap::new();
+    let mut rng = OsRng;
+    let mut failed = 0;
+    let progress = progress::bar(bundles.len());
+    for path in &bundles {
+        let mut cost = CostReport {
+            bundle: path.display().to_string(),
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let result = (|| -> Result<(), Box<dyn std::error::Error>> {
+            let phase = Instant::now();
+            let bundle = ProofBundle::load(path)?;
+            let statement = bundle.statement()?;
+            let proof = bundle.proof_bytes()?;
+            cost.deserialize_ms = millis(phase.elapsed());
+
+            let phase = Instant::now();
+            if bundle.circuit != args.circuit.circuit {
+                return Err(format!("bundle is for circuit '{}'", bundle.circuit).into());
+            }
+            if let Some(expected) = &bundle.circuit_fingerprint {
+                let actual = expected.algorithm.digest(&r1cs_bytes);
+                if actual != *expected {
+                    return Err(
+                        format!("circuit fingerprint {expected} does not match {actual}").into(),
+                    );
+                }
+            } else if checks.require_fingerprint {
+                return Err("bundle carries no circuit fingerprint".into());
+            }
+            if bundle.crs.size != circuit.dim() {
+                return Err(format!(
+                    "CRS size {} does not match circuit dimension {}",
+                    bundle.crs.size,
+                    circuit.dim()
+                )
+                .into());
+            }
+            if signed_crs.is_some_and(|signed| signed != bundle.crs) {
+                return Err("CRS parameters differ from the signed params manifest".into());
+            }
+            if checks.require_expected_seed
+                && signed_crs.is_none()
+                && bundle.crs.seed != expected_seed
+            {
+                return Err("CRS was derived from an unexpected seed".into());
+            }
+            if checks.check_statement_shape && statement.v.len() != num_commitments {
+                return Err(format!(
+                    "statement has {} commitments, the circuit expects {num_commitments}",
+                    statement.v.len()
+                )
+                .into());
+            }
+            let derived;
+            let crs = if checks.rederive_crs {
+                derived = bundle.crs.derive();
+                &derived
+            } else {
+                &*crs_cache
+                    .entry(bundle.crs)
+                    .or_insert_with(|| bundle.crs.derive())
+            };
+            bundle.check_public_signals(crs, &statement, public)?;
+            if let Some(commitments) = &commitments {
+                if commitments.crs != bundle.crs {
+                    return Err("commitments were made under another CRS".into());
+                }
+                commitments.check(&statement)?;
+            }
+            cost.statement_ms = millis(phase.elapsed());
+
+            cost.group_ops = verification_group_ops(circuit.dim(), statement.v.len());
+            let timings = pipeline::verify_timed(crs, &circuit, &statement, &proof, &mut rng)?;
+            cost.transcript_ms = millis(timings.transcript);
+            cost.msm_ms = millis(timings.check);
+            Ok(())
+        })();
+        cost.total_ms = millis(start.elapsed());
+        cost.verified = result.is_ok();
+        if args.json {
+            progress.suspend(|| serde_json::to_string(&cost).map(|json| println!("{json}")))?;
+        } else if args.report {
+            progress.suspend(|| print_cost(&cost));
+        }
+
+        let status = match result {
+            Ok(()) => {
+                progress.suspend(|| info!("✅ {}", path.display()));
+                VerificationStatus::Verified
+            }
+            Err(e) => {
+                failed += 1;
+                progress.suspend(|| error!("❌ {}: {e}", path.display()));
+                VerificationStatus::Failed
+            }
+        };
+        if let Some(registry) = &registry
+            && let Err(e) = registry.set_status(&path.display().to_string(), status)
+        {
+            progress.suspend(|| warn!("Failed to record verification of {}: {e}", path.display()));
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    info!(
+        "{} of {} proofs verified",
+        bundles.len() - failed,
+        bundles.len()
+    );
+    if failed > 0 {
+        return Err(format!("{failed} proofs failed verification").into());
+    }
+    Ok(())
+}
+
+fn print_cost(cost: &CostReport) {
+    println!("{}", cost.bundle);
+    for (phase, ms) in [
+        ("deserialize", cost.deserialize_ms),
+        ("statement", cost.statement_ms),
+        ("transcript", cost.transcript_ms),
+        ("msm", cost.msm_ms),
+        ("total", cost.total_ms),
+    ] {
+        println!("{phase:>12}: {ms:>10.3} ms");
+    }
+    println!("{:>12}: {:>10}", "group ops", cost.group_ops);
+}
+
+/// Expands directories into their `*.proof.json` files, in a stable order; a
+/// directory written by `prove --output-dir` is one proof itself
+fn bundle_files(paths: &[PathBuf]) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() && !path.join("statement.json").is_file() {
+            let mut found = Vec::new();
+            for entry in std::fs::read_dir(path)? {
+                let entry = entry?.path();
+                if entry.to_string_lossy().ends_with(".proof.json") {
+                    found.push(entry);
+                }
+            }
+            found.sort();
+            files.extend(found);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
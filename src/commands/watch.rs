@@ -0,0 +1,127 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use circom_bp::{
+    conversion::circom_to_sparse,
+    crs::CrsParams,
+    pipeline::{self, CircuitPaths},
+};
+use clap::Args;
+use rand::rngs::OsRng;
+use tracing::{info, warn};
+
+use super::CircuitArgs;
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    #[command(flatten)]
+    pub circuit: CircuitArgs,
+    /// Only generate the witness and check the circuit, skipping proving
+    #[arg(long)]
+    pub check_only: bool,
+    /// How often to poll the watched files, in milliseconds
+    #[arg(long, default_value_t = 500, env = "CIRCOM_BP_WATCH_INTERVAL_MS")]
+    pub interval_ms: u64,
+}
+
+/// Polls the circuit files and re-runs the pipeline after every change
+///
+/// Given a circuit name, the compiled wasm/r1cs and the inputs are watched. Given a
+/// `.circom` source, the source and the inputs are watched and every run starts by
+/// recompiling into `--circuits-dir`. A `--source` is watched the same way, with
+/// the temporary build directory it was first compiled into.
+pub fn run(args: WatchArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let source = Path::new(&args.circuit.circuit);
+    let (source, paths) = if let Some(source) = &args.circuit.source {
+        (Some(source.clone()), args.circuit.paths())
+    } else if source.extension().is_some_and(|ext| ext == "circom") {
+        let name = source
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let circuit = CircuitArgs {
+            circuit: name,
+            ..args.circuit.clone()
+        };
+        (Some(source.to_path_buf()), circuit.paths())
+    } else {
+        (None, args.circuit.paths())
+    };
+    if paths.inputs == Path::new("-") {
+        return Err(
+            "watch re-reads its inputs on every change and cannot take them from stdin".into(),
+        );
+    }
+    let watched = match &source {
+        Some(source) => vec![source.clone(), paths.inputs.clone()],
+        None => vec![paths.inputs.clone(), paths.wasm.clone(), paths.r1cs.clone()],
+    };
+    let compiler = args.circuit.compiler.compiler();
+    info!("Watching {} (Ctrl-C to stop)", display_paths(&watched));
+
+    let mut last_seen = None;
+    loop {
+        let stamps = modification_times(&watched);
+        if last_seen.as_ref() != Some(&stamps) {
+            last_seen = Some(stamps);
+            let start = Instant::now();
+            let result = match &source {
+                Some(source) => compiler
+                    .compile(source, &args.circuit.circuits_dir)
+                    .map_err(Into::into)
+                    .and_then(|_| run_once(&paths, &args)),
+                None => run_once(&paths, &args),
+            };
+            match result {
+                Ok(summary) => info!("✅ {summary} ({} ms)", start.elapsed().as_millis()),
+                Err(e) => warn!("❌ {e}"),
+            }
+        }
+        std::thread::sleep(Duration::from_millis(args.interval_ms));
+    }
+}
+
+fn run_once(paths: &CircuitPaths, args: &WatchArgs) -> Result<String, Box<dyn std::error::Error>> {
+    args.circuit.curve.check_r1cs(&paths.r1cs)?;
+    let inputs = args.circuit.load_inputs(paths)?;
+    let circom = pipeline::generate_witness_cached(paths, &inputs, None)?;
+    let (circuit, witness, _) = circom_to_sparse(&circom, &[])?;
+    if args.check_only {
+        if !circuit.is_satisfied_by(&witness) {
+            return Err("circuit not satisfied by witness".into());
+        }
+        return Ok(format!(
+            "satisfied, {} constraints",
+            circom.r1cs.constraints.len()
+        ));
+    }
+
+    let mut rng = OsRng;
+    let circuit = circuit.into_dense();
+    let crs = CrsParams::new(circuit.dim()).derive();
+    let (statement, proof) = pipeline::prove(&crs, &circuit, &witness, &mut rng)?;
+    pipeline::verify(&crs, &circuit, &statement, &proof, &mut rng)?;
+    Ok(format!("proved and verified, {} byte proof", proof.len()))
+}
+
+/// Missing files map to `None`, so their (re)appearance also counts as a change
+fn modification_times(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| {
+            std::fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .ok()
+        })
+        .collect()
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use ark_bn254::Fr;
+use circom_bp::{r1cs::signed, sym::SymbolTable, wtns::read_wtns};
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct WitnessDiffArgs {
+    /// Baseline `.wtns` file
+    pub a: PathBuf,
+    /// `.wtns` file to compare against the baseline
+    pub b: PathBuf,
+    /// `.sym` file used to name the differing signals
+    #[arg(long)]
+    pub sym: Option<PathBuf>,
+}
+
+/// Lists the signals whose values differ between two witnesses
+pub fn run(args: WitnessDiffArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let a = read_wtns::<Fr>(&args.a)?;
+    let b = read_wtns::<Fr>(&args.b)?;
+    let symbols = args.sym.as_ref().map(SymbolTable::read).transpose()?;
+    let names = symbols
+        .as_ref()
+        .map(SymbolTable::names_by_label)
+        .unwrap_or_default();
+
+    if a.len() != b.len() {
+        println!("Witness lengths differ: {} vs {}", a.len(), b.len());
+    }
+
+    let mut differing = 0;
+    for (label, (x, y)) in a.iter().zip(&b).enumerate() {
+        if x == y {
+            continue;
+        }
+        differing += 1;
+        let name = names
+            .get(&label)
+            .map_or_else(|| format!("signal {label}"), |name| name.to_string());
+        println!("{name}: {x} -> {y} (Δ {})", signed(*y - x));
+    }
+
+    println!("{differing} of {} signals differ", a.len().min(b.len()));
+    Ok(())
+}
@@ -0,0 +1,472 @@
+use std::path::Path;
+
+use ark_bn254::{Fr, G1Projective};
+use ark_circom::circom::R1CS;
+use ark_ff::{UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use bulletproofs::circuit::types::{CRS as CircuitCRS, Statement as CircuitStatement, Witness};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::conversion;
+use crate::crs::CrsParams;
+use crate::r1cs::ONE_WIRE;
+use crate::sym::SymbolTable;
+
+/// Pedersen commitments to selected input signals, published ahead of a proof
+///
+/// Each commitment uses the same generators as the statement commitment to the
+/// signal's variable, so a proof built with the matching openings carries exactly
+/// these points in its statement. Private signals are only committed in proofs
+/// converted with `circom_to_bulletproofs_committing` over `variables()`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputCommitments {
+    pub circuit: String,
+    pub crs: CrsParams,
+    pub commitments: Vec<SignalCommitment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignalCommitment {
+    pub signal: String,
+    /// R1CS variable of the signal
+    pub variable: usize,
+    /// Statement position of the commitment, see `conversion::statement_variables`
+    pub position: usize,
+    /// Compressed curve point
+    #[serde(with = "hex::serde")]
+    pub commitment: Vec<u8>,
+}
+
+/// The blindings opening a set of `InputCommitments`; these stay with the prover
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputOpenings {
+    pub circuit: String,
+    pub crs: CrsParams,
+    pub openings: Vec<SignalOpening>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignalOpening {
+    pub signal: String,
+    pub variable: usize,
+    pub position: usize,
+    /// Compressed scalar
+    #[serde(with = "hex::serde")]
+    pub blinding: Vec<u8>,
+}
+
+/// Finds the R1CS variable of each named main-component signal, such as `a` or `in[2]`
+///
+/// Public signals are refused: their statement commitments stay unblinded, so
+/// that verifiers can check them against the published values.
+pub fn resolve_signals(
+    r1cs: &R1CS<Fr>,
+    symbols: &SymbolTable,
+    signals: &[String],
+) -> Result<Vec<(String, usize)>, CommitmentError> {
+    let labels = symbols.names_by_label();
+    signals
+        .iter()
+        .map(|signal| {
+            let qualified = format!("main.{signal}");
+            let label = labels
+                .iter()
+                .find(|(_, name)| **name == qualified)
+                .map(|(&label, _)| label)
+                .ok_or_else(|| CommitmentError::UnknownSignal(signal.clone()))?;
+            // Simplified circuits only keep some signals, reached through the wire mapping
+            let variable = match &r1cs.wire_mapping {
+                Some(wire_mapping) => wire_mapping.iter().position(|&wire| wire == label),
+                None => Some(label),
+            }
+            .filter(|&variable| variable < r1cs.num_variables)
+            .ok_or_else(|| CommitmentError::OptimizedAway(signal.clone()))?;
+            if is_public(variable, conversion::public_signal_count(r1cs)) {
+                return Err(CommitmentError::PublicSignal(signal.clone()));
+            }
+            Ok((signal.clone(), variable))
+        })
+        .collect()
+}
+
+/// Commits to the witness values of the given variables under fresh random blindings
+///
+/// `witness` must come from `circom_to_bulletproofs_committing` over the signals'
+/// variables, so that each of them has a statement position.
+pub fn commit<R: RngCore + CryptoRng>(
+    circuit: &str,
+    crs_params: CrsParams,
+    crs: &CircuitCRS<G1Projective>,
+    r1cs: &R1CS<Fr>,
+    witness: &Witness<Fr>,
+    signals: &[(String, usize)],
+    rng: &mut R,
+) -> Result<(InputCommitments, InputOpenings), CommitmentError> {
+    let variables: Vec<usize> = signals.iter().map(|(_, variable)| *variable).collect();
+    let statement_variables = conversion::statement_variables(r1cs, &variables);
+    let public = conversion::public_signal_count(r1cs);
+    let mut gamma = vec![Fr::zero(); witness.v.len()];
+    let mut positions = Vec::with_capacity(signals.len());
+    let mut openings = Vec::with_capacity(signals.len());
+    for (signal, variable) in signals {
+        if *variable == ONE_WIRE {
+            return Err(CommitmentError::ConstantWire(signal.clone()));
+        }
+        if is_public(*variable, public) {
+            return Err(CommitmentError::PublicSignal(signal.clone()));
+        }
+        let blinding = Fr::rand(rng);
+        let position = statement_variables
+            .iter()
+            .position(|v| v == variable)
+            .filter(|&position| position < gamma.len())
+            .ok_or_else(|| CommitmentError::NotCommitted(signal.clone()))?;
+        gamma[position] = blinding;
+        positions.push(position);
+        openings.push(SignalOpening {
+            signal: signal.clone(),
+            variable: *variable,
+            position,
+            blinding: encode(&blinding)?,
+        });
+    }
+
+    // Committing through the statement guarantees the prover's generators are used
+    let statement = CircuitStatement::new(
+        crs,
+        &Witness {
+            a_l: witness.a_l.clone(),
+            a_r: witness.a_r.clone(),
+            a_o: witness.a_o.clone(),
+            v: witness.v.clone(),
+            gamma,
+        },
+    );
+    let commitments = signals
+        .iter()
+        .zip(positions)
+        .map(
+            |((signal, variable), position)| -> Result<_, CommitmentError> {
+                Ok(SignalCommitment {
+                    signal: signal.clone(),
+                    variable: *variable,
+                    position,
+                    commitment: encode(&statement.v[position])?,
+                })
+            },
+        )
+        .collect::<Result<_, _>>()?;
+
+    Ok((
+        InputCommitments {
+            circuit: circuit.to_string(),
+            crs: crs_params,
+            commitments,
+        },
+        InputOpenings {
+            circuit: circuit.to_string(),
+            crs: crs_params,
+            openings,
+        },
+    ))
+}
+
+impl InputCommitments {
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, CommitmentError> {
+        read_json(path)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), CommitmentError> {
+        write_json(self, path)
+    }
+
+    /// The committed variables, to convert the circuit with
+    pub fn variables(&self) -> Vec<usize> {
+        self.commitments.iter().map(|c| c.variable).collect()
+    }
+
+    /// Checks that a proof statement opens every commitment
+    pub fn check(&self, statement: &CircuitStatement<G1Projective>) -> Result<(), CommitmentError> {
+        for commitment in &self.commitments {
+            let expected = G1Projective::deserialize_compressed(commitment.commitment.as_slice())?;
+            if statement.v.get(commitment.position) != Some(&expected) {
+                return Err(CommitmentError::Mismatch(commitment.signal.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl InputOpenings {
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, CommitmentError> {
+        read_json(path)
+    }
+
+    /// Writes the openings readable by their owner only (mode 0600 on Unix), as
+    /// anyone holding the blindings can open the commitments
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), CommitmentError> {
+        let json = serde_json::to_string_pretty(self)? + "\n";
+        crate::atomic::write_with_mode(path, json, 0o600)?;
+        Ok(())
+    }
+
+    /// The committed variables, to convert the circuit with
+    pub fn variables(&self) -> Vec<usize> {
+        self.openings.iter().map(|o| o.variable).collect()
+    }
+
+    /// Blinds the committed variables so the statement reproduces the published
+    /// commitments, for a circuit with `public` public signals
+    ///
+    /// The first `public` statement positions hold the public signals, which
+    /// must stay unblinded and are never opened.
+    pub fn apply(&self, witness: &mut Witness<Fr>, public: usize) -> Result<(), CommitmentError> {
+        for opening in &self.openings {
+            // The constant is fixed by the circuit's `c` and has no commitment to open
+            if opening.variable == ONE_WIRE {
+                return Err(CommitmentError::ConstantWire(opening.signal.clone()));
+            }
+            if is_public(opening.variable, public) || opening.position < public {
+                return Err(CommitmentError::PublicSignal(opening.signal.clone()));
+            }
+            let blinding = Fr::deserialize_compressed(opening.blinding.as_slice())?;
+            *witness
+                .gamma
+                .get_mut(opening.position)
+                .ok_or_else(|| CommitmentError::NotCommitted(opening.signal.clone()))? = blinding;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `variable` is one of the `public` public signals, which follow the
+/// constant wire
+fn is_public(variable: usize, public: usize) -> bool {
+    (ONE_WIRE + 1..=public).contains(&variable)
+}
+
+fn encode(value: &impl CanonicalSerialize) -> Result<Vec<u8>, SerializationError> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_json<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, CommitmentError> {
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+fn write_json<T: Serialize>(value: &T, path: impl AsRef<Path>) -> Result<(), CommitmentError> {
+    crate::atomic::write(path, serde_json::to_string_pretty(value)? + "\n")?;
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommitmentError {
+    #[error("Failed to access commitment file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Malformed commitment file: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Malformed commitment or blinding: {0}")]
+    Point(#[from] SerializationError),
+    #[error("No input signal named '{0}' in the symbol file")]
+    UnknownSignal(String),
+    #[error("Signal '{0}' is not an R1CS variable of the circuit")]
+    OptimizedAway(String),
+    #[error("Signal '{0}' is not committed in the statement of this conversion")]
+    NotCommitted(String),
+    #[error("Signal '{0}' resolves to the constant-one wire, which is never committed")]
+    ConstantWire(String),
+    #[error(
+        "Signal '{0}' is public; its value is published with the proof and checked against an unblinded commitment, so it cannot be committed"
+    )]
+    PublicSignal(String),
+    #[error("Proof does not open the commitment to '{0}'")]
+    Mismatch(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::{circom_to_bulletproofs, circom_to_bulletproofs_committing};
+    use ark_circom::CircomCircuit;
+
+    /// `c <== a * b` over wires [1, c, a, b]
+    fn multiplier() -> CircomCircuit<Fr> {
+        let one = Fr::from(1u64);
+        CircomCircuit {
+            r1cs: R1CS {
+                num_inputs: 2,
+                num_aux: 2,
+                num_variables: 4,
+                constraints: vec![(vec![(2, one)], vec![(3, one)], vec![(1, one)])],
+                wire_mapping: None,
+            },
+            witness: Some([1u64, 33, 3, 11].map(Fr::from).to_vec()),
+        }
+    }
+
+    #[test]
+    fn proof_opens_published_commitments() {
+        let mut rng = rand::rngs::OsRng;
+        let circom = multiplier();
+        let symbols = SymbolTable::parse("1,1,0,main.c\n2,2,0,main.a\n3,3,0,main.b\n").unwrap();
+        let signals = resolve_signals(&circom.r1cs, &symbols, &["a".into(), "b".into()]).unwrap();
+        assert_eq!(signals, [("a".to_string(), 2), ("b".to_string(), 3)]);
+
+        // Private signals are only committed when asked for
+        let (_, witness, _) = circom_to_bulletproofs(&circom).unwrap();
+        let crs_params = CrsParams::new(2);
+        assert!(matches!(
+            commit(
+                "multiplier",
+                crs_params,
+                &crs_params.derive(),
+                &circom.r1cs,
+                &witness,
+                &signals,
+                &mut rng
+            ),
+            Err(CommitmentError::NotCommitted(_))
+        ));
+
+        let variables: Vec<usize> = signals.iter().map(|(_, variable)| *variable).collect();
+        let (circuit, mut witness, _) =
+            circom_to_bulletproofs_committing(&circom, &variables).unwrap();
+        let crs_params = CrsParams::new(circuit.dim());
+        let crs = crs_params.derive();
+        let (commitments, openings) = commit(
+            "multiplier",
+            crs_params,
+            &crs,
+            &circom.r1cs,
+            &witness,
+            &signals,
+            &mut rng,
+        )
+        .unwrap();
+        assert_eq!(commitments.variables(), [2, 3]);
+        assert_eq!(openings.openings[1].position, 2);
+
+        // Without the openings the statement commits with zero blinding
+        let (statement, _) = crate::pipeline::prove(&crs, &circuit, &witness, &mut rng).unwrap();
+        assert!(matches!(
+            commitments.check(&statement),
+            Err(CommitmentError::Mismatch(_))
+        ));
+
+        openings.apply(&mut witness, 1).unwrap();
+        let (statement, proof) =
+            crate::pipeline::prove(&crs, &circuit, &witness, &mut rng).unwrap();
+        commitments.check(&statement).unwrap();
+        crate::pipeline::verify(&crs, &circuit, &statement, &proof, &mut rng).unwrap();
+    }
+
+    #[test]
+    fn constant_wire_is_never_committed() {
+        let (_, mut witness, _) = circom_to_bulletproofs(&multiplier()).unwrap();
+        let crs_params = CrsParams::new(4);
+        let signals = [("one".to_string(), ONE_WIRE)];
+        assert!(matches!(
+            commit(
+                "multiplier",
+                crs_params,
+                &crs_params.derive(),
+                &multiplier().r1cs,
+                &witness,
+                &signals,
+                &mut rand::rngs::OsRng
+            ),
+            Err(CommitmentError::ConstantWire(_))
+        ));
+
+        let openings = InputOpenings {
+            circuit: "multiplier".into(),
+            crs: crs_params,
+            openings: vec![SignalOpening {
+                signal: "one".into(),
+                variable: ONE_WIRE,
+                position: 0,
+                blinding: encode(&Fr::from(5u64)).unwrap(),
+            }],
+        };
+        assert!(matches!(
+            openings.apply(&mut witness, 1),
+            Err(CommitmentError::ConstantWire(_))
+        ));
+        assert!(witness.gamma.iter().all(|gamma| gamma.is_zero()));
+    }
+
+    #[test]
+    fn public_signals_are_never_committed() {
+        let circom = multiplier();
+        let symbols = SymbolTable::parse("1,1,0,main.c\n2,2,0,main.a\n3,3,0,main.b\n").unwrap();
+        assert!(matches!(
+            resolve_signals(&circom.r1cs, &symbols, &["c".into()]),
+            Err(CommitmentError::PublicSignal(_))
+        ));
+
+        let (_, mut witness, _) = circom_to_bulletproofs(&circom).unwrap();
+        let crs_params = CrsParams::new(2);
+        let signals = [("c".to_string(), 1)];
+        assert!(matches!(
+            commit(
+                "multiplier",
+                crs_params,
+                &crs_params.derive(),
+                &circom.r1cs,
+                &witness,
+                &signals,
+                &mut rand::rngs::OsRng
+            ),
+            Err(CommitmentError::PublicSignal(_))
+        ));
+
+        // An openings file claiming one would blind the public commitment
+        let openings = InputOpenings {
+            circuit: "multiplier".into(),
+            crs: crs_params,
+            openings: vec![SignalOpening {
+                signal: "c".into(),
+                variable: 1,
+                position: 0,
+                blinding: encode(&Fr::from(5u64)).unwrap(),
+            }],
+        };
+        assert!(matches!(
+            openings.apply(&mut witness, 1),
+            Err(CommitmentError::PublicSignal(_))
+        ));
+        assert!(witness.gamma.iter().all(|gamma| gamma.is_zero()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn openings_are_written_for_their_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("circom-bp-openings-test-{}", std::process::id()));
+        let path = dir.join("multiplier.openings.json");
+        let openings = InputOpenings {
+            circuit: "multiplier".into(),
+            crs: CrsParams::new(2),
+            openings: Vec::new(),
+        };
+        openings.write(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(InputOpenings::read(&path).unwrap(), openings);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unknown_signals_are_rejected() {
+        let symbols = SymbolTable::parse("1,1,0,main.c\n").unwrap();
+        assert!(matches!(
+            resolve_signals(&multiplier().r1cs, &symbols, &["x".into()]),
+            Err(CommitmentError::UnknownSignal(_))
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::pipeline::CircuitPaths;
+
+/// Invokes the circom compiler
+#[derive(Debug, Clone)]
+pub struct Compiler {
+    /// The `circom` executable (`circom2` when installed through npm)
+    pub program: PathBuf,
+    /// Library directories passed with `-l`
+    pub include: Vec<PathBuf>,
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self {
+            program: PathBuf::from("circom"),
+            include: Vec::new(),
+        }
+    }
+}
+
+impl Compiler {
+    /// Compiles `source` with `--r1cs --wasm --sym` into `out_dir`
+    ///
+    /// circom names its outputs after the source file, which is exactly the layout
+    /// `CircuitPaths::new(out_dir, <stem>)` expects.
+    pub fn compile(&self, source: &Path, out_dir: &Path) -> Result<CircuitPaths, CompileError> {
+        let name = source
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .ok_or_else(|| CompileError::InvalidSource(source.to_path_buf()))?;
+        std::fs::create_dir_all(out_dir)?;
+
+        let mut command = Command::new(&self.program);
+        command
+            .arg(source)
+            .args(["--r1cs", "--wasm", "--sym", "-o"])
+            .arg(out_dir);
+        for include in &self.include {
+            command.arg("-l").arg(include);
+        }
+
+        let output = command.output().map_err(|e| CompileError::Spawn {
+            program: self.program.clone(),
+            source: e,
+        })?;
+        if !output.status.success() {
+            // circom reports errors on both streams depending on the version
+            let mut message = String::from_utf8_lossy(&output.stderr).into_owned();
+            message.push_str(&String::from_utf8_lossy(&output.stdout));
+            return Err(CompileError::Failed {
+                status: output.status.to_string(),
+                message: message.trim().to_string(),
+            });
+        }
+
+        let paths = CircuitPaths::new(out_dir, &name);
+        for artifact in [&paths.r1cs, &paths.wasm] {
+            if !artifact.exists() {
+                return Err(CompileError::MissingOutput(artifact.clone()));
+            }
+        }
+        Ok(paths)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CompileError {
+    #[error("Not a circom source file: {0}")]
+    InvalidSource(PathBuf),
+    #[error("Failed to run {}: {source}", .program.display())]
+    Spawn {
+        program: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("circom failed ({status}):\n{message}")]
+    Failed { status: String, message: String },
+    #[error("circom did not produce {0}")]
+    MissingOutput(PathBuf),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
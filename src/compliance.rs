@@ -0,0 +1,206 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ark_bn254::Fr;
+use ark_ff::{PrimeField, Zero};
+use bulletproofs::circuit::types::Witness;
+use num_bigint::BigUint;
+use serde::Serialize;
+
+use crate::{
+    atomic::{self, DirLock},
+    bundle::BUNDLE_VERSION,
+    crs::CrsParams,
+    curve::Curve,
+    fingerprint::{Fingerprint, HashAlgorithm},
+    pipeline::{self, DOMAIN_LABEL},
+};
+
+/// The cryptographic choices behind one proof, for audit trails
+///
+/// Everything a reviewer would otherwise have to reconstruct from the code: which
+/// curve and field, how the generators were derived, how the Fiat-Shamir transcript
+/// is laid out and hashed, and how much of the statement is blinded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComplianceRecord {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub tool_version: String,
+    pub protocol: String,
+    pub bundle_version: u32,
+    pub curve: String,
+    /// Scalar field modulus, decimal
+    pub field_modulus: String,
+    pub circuit: String,
+    pub circuit_fingerprint: Fingerprint,
+    pub generators: GeneratorDerivation,
+    pub transcript: TranscriptParameters,
+    pub blinding: BlindingPolicy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GeneratorDerivation {
+    /// How generators are sampled from `crs.seed`
+    pub method: &'static str,
+    pub crs: CrsParams,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TranscriptParameters {
+    /// Sponge the Fiat-Shamir challenges are squeezed from
+    pub sponge: &'static str,
+    pub domain_label: &'static str,
+    /// Digest of the domain separator, which pins the transcript layout
+    pub domain_separator: Fingerprint,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlindingPolicy {
+    /// `none`, `partial` or `full`, judged by the statement commitments' blindings
+    pub policy: &'static str,
+    pub blinded_commitments: usize,
+    pub commitments: usize,
+    pub random_padding: bool,
+}
+
+impl ComplianceRecord {
+    /// Describes a proof of `witness` under `crs`
+    pub fn new(
+        circuit: &str,
+        circuit_fingerprint: Fingerprint,
+        crs: CrsParams,
+        witness: &Witness<Fr>,
+        random_padding: bool,
+    ) -> Self {
+        let commitments = witness.v.len();
+        let blinded_commitments = witness.gamma.iter().filter(|g| !g.is_zero()).count();
+        let policy = match blinded_commitments {
+            0 => "none",
+            n if n == commitments => "full",
+            _ => "partial",
+        };
+        let domain_separator = pipeline::domain_separator(commitments, crs.size);
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            protocol: "bulletproofs-arithmetic-circuit".to_string(),
+            bundle_version: BUNDLE_VERSION,
+            curve: Curve::Bn254.to_string(),
+            field_modulus: BigUint::from(Fr::MODULUS).to_string(),
+            circuit: circuit.to_string(),
+            circuit_fingerprint,
+            generators: GeneratorDerivation {
+                method: "chacha20-seeded-uniform",
+                crs,
+            },
+            transcript: TranscriptParameters {
+                sponge: "keccak-f1600-duplex",
+                domain_label: DOMAIN_LABEL,
+                domain_separator: HashAlgorithm::Blake3.digest(domain_separator.as_bytes()),
+            },
+            blinding: BlindingPolicy {
+                policy,
+                blinded_commitments,
+                commitments,
+                random_padding,
+            },
+            proof: None,
+        }
+    }
+
+    pub fn with_proof(mut self, location: impl Into<String>) -> Self {
+        self.proof = Some(location.into());
+        self
+    }
+}
+
+/// Destination of compliance records
+pub trait ComplianceLog {
+    fn record(&mut self, record: &ComplianceRecord) -> Result<(), ComplianceError>;
+}
+
+/// Appends each record as one JSON line to a file
+#[derive(Debug, Clone)]
+pub struct JsonLinesLog {
+    pub path: PathBuf,
+}
+
+impl JsonLinesLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl ComplianceLog for JsonLinesLog {
+    fn record(&mut self, record: &ComplianceRecord) -> Result<(), ComplianceError> {
+        let line = serde_json::to_string(record)? + "\n";
+        // Provers sharing the log append one whole line at a time
+        let _lock = DirLock::acquire(atomic::parent_dir(&self.path))?;
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Keeps records in memory
+#[derive(Debug, Clone, Default)]
+pub struct MemoryLog {
+    pub records: Vec<ComplianceRecord>,
+}
+
+impl ComplianceLog for MemoryLog {
+    fn record(&mut self, record: &ComplianceRecord) -> Result<(), ComplianceError> {
+        self.records.push(record.clone());
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ComplianceError {
+    #[error("Failed to write compliance log: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to encode compliance record: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_blinding_of_the_statement() {
+        let witness = Witness {
+            a_l: vec![Fr::zero(); 2],
+            a_r: vec![Fr::zero(); 2],
+            a_o: vec![Fr::zero(); 2],
+            v: vec![Fr::from(3u64), Fr::from(11u64)],
+            gamma: vec![Fr::from(5u64), Fr::zero()],
+        };
+        let record = ComplianceRecord::new(
+            "multiplier2",
+            HashAlgorithm::Blake3.digest(b"r1cs"),
+            CrsParams::new(2),
+            &witness,
+            false,
+        )
+        .with_proof("proofs/proof.bin");
+
+        let mut log = MemoryLog::default();
+        log.record(&record).unwrap();
+        let json = serde_json::to_value(&log.records[0]).unwrap();
+        assert_eq!(json["blinding"]["policy"], "partial");
+        assert_eq!(json["curve"], "bn254");
+        assert_eq!(json["generators"]["crs"]["size"], 2);
+        assert_eq!(json["proof"], "proofs/proof.bin");
+    }
+}
@@ -1,83 +1,1048 @@
-use ark_circom::CircomCircuit;
+use std::{fmt, ops::Range, str::FromStr};
+
+use ark_circom::{CircomCircuit, circom::R1CS};
 use ark_ff::{Field, PrimeField};
 use bulletproofs::circuit::types::{Circuit, Witness};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::estimate::ResourceEstimate;
+use crate::r1cs::{self, ONE_WIRE, WireMappingError};
+use crate::sparse::SparseCircuit;
+
+/// Shape of a converted circuit, for logging and alerting on circuit growth
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversionReport {
+    pub constraints: usize,
+    pub variables: usize,
+    /// Variables committed in the statement: the public signals plus any private
+    /// variables committed on request, but not the constant wire
+    pub commitments: usize,
+    /// Gates of the multiplicative constraints plus the gates carrying
+    /// uncommitted private variables
+    pub used_gates: usize,
+    /// Multiplication gates after power-of-2 padding
+    pub gates: usize,
+    /// Linear constraints, three per multiplicative and one per linear R1CS
+    /// constraint, after power-of-2 padding; this is what `Circuit::size` reports
+    pub rows: usize,
+    /// Gates added only to reach a power of 2
+    pub padding_gates: usize,
+    /// Empty rows added only to reach a power of 2
+    pub padding_rows: usize,
+    /// Nonzero entries across `w_l`, `w_r`, `w_o` and `w_v`; constant terms are in
+    /// `c` instead
+    pub nonzero_weights: usize,
+    /// Fraction of weight matrix entries that are zero
+    pub sparsity: f64,
+    /// Constraints with an empty A or B side, i.e. `0 = C·w`, which are folded
+    /// into a single row without a gate
+    pub linear_constraints: usize,
+    pub multiplicative_constraints: usize,
+    pub estimated_proof_bytes: usize,
+}
+
+impl ConversionReport {
+    /// Computes the report from the R1CS alone, so no witness is needed
+    pub fn from_r1cs<F: PrimeField>(r1cs: &R1CS<F>) -> Self {
+        Self::committing(r1cs, &[])
+    }
+
+    /// Like `from_r1cs`, for a conversion that also commits the `committed` variables
+    pub fn committing<F: PrimeField>(r1cs: &R1CS<F>, committed: &[usize]) -> Self {
+        let constraints = r1cs.constraints.len();
+        let layout = VariableLayout::new(r1cs, committed);
+        let gates = layout.used_gates.next_power_of_two();
+        let rows = layout.rows.next_power_of_two();
+        let nonzero = |lc: &[(usize, F)]| {
+            lc.iter()
+                .filter(|&&(v, coeff)| v != ONE_WIRE && !coeff.is_zero())
+                .count()
+        };
+
+        // Every row of a multiplicative constraint also selects one gate wire
+        let linear_constraints = constraints - layout.constraint_gates;
+        let mut nonzero_weights = 3 * layout.constraint_gates;
+        for (a, b, c) in &r1cs.constraints {
+            nonzero_weights += nonzero(a) + nonzero(b) + nonzero(c);
+        }
+        let cells = rows * (3 * gates + layout.statement.len());
+        Self {
+            constraints,
+            variables: r1cs.num_variables,
+            commitments: layout.statement.len(),
+            used_gates: layout.used_gates,
+            gates,
+            rows,
+            padding_gates: gates - layout.used_gates,
+            padding_rows: rows - layout.rows,
+            nonzero_weights,
+            sparsity: if cells == 0 {
+                1.0
+            } else {
+                1.0 - nonzero_weights as f64 / cells as f64
+            },
+            linear_constraints,
+            multiplicative_constraints: layout.constraint_gates,
+            estimated_proof_bytes: ResourceEstimate::from_dimensions(
+                constraints,
+                linear_constraints,
+                r1cs.num_variables,
+                layout.statement.len(),
+            )
+            .proof_bytes,
+        }
+    }
+
+    /// Fraction of weight matrix entries that are nonzero, `1 - sparsity`
+    pub fn density(&self) -> f64 {
+        1.0 - self.sparsity
+    }
+
+    /// Fraction of the padded gates that are padding, the share of the proving work
+    /// spent only on reaching a power of 2
+    pub fn padding_overhead(&self) -> f64 {
+        self.padding_gates as f64 / self.gates as f64
+    }
+}
+
+/// Where an R1CS variable lives in a converted circuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    /// The constant wire, whose terms move into `c`
+    Constant,
+    /// Statement commitment `v[j]`
+    Committed(usize),
+    /// Left wire of a gate
+    Left(usize),
+    /// Right wire of a gate
+    Right(usize),
+}
+
+/// Placement of the R1CS variables: public and requested variables are committed
+/// in `v`, the other private variables are gate wires after the constraint gates,
+/// and the constant wire is no variable at all
+///
+/// Only multiplicative constraints take a gate; linear ones are a single row.
+#[derive(Debug)]
+struct VariableLayout {
+    slots: Vec<Slot>,
+    /// R1CS variable of each statement position
+    statement: Vec<usize>,
+    /// Gate of each constraint, `None` for linear constraints
+    gates: Vec<Option<usize>>,
+    /// Gates taken by multiplicative constraints, which come first
+    constraint_gates: usize,
+    used_gates: usize,
+    /// Rows before padding
+    rows: usize,
+}
+
+impl VariableLayout {
+    fn new<F: PrimeField>(r1cs: &R1CS<F>, committed: &[usize]) -> Self {
+        let public = r1cs.num_inputs.min(r1cs.num_variables);
+        let mut slots: Vec<Option<Slot>> = vec![None; r1cs.num_variables];
+        if let Some(slot) = slots.get_mut(ONE_WIRE) {
+            *slot = Some(Slot::Constant);
+        }
+        let mut statement = Vec::with_capacity(public + committed.len());
+        for variable in (ONE_WIRE + 1..public).chain(committed.iter().copied()) {
+            if let Some(slot) = slots.get_mut(variable)
+                && slot.is_none()
+            {
+                *slot = Some(Slot::Committed(statement.len()));
+                statement.push(variable);
+            }
+        }
+
+        // Multiplicative constraints take the first gates in constraint order
+        let mut gates = Vec::with_capacity(r1cs.constraints.len());
+        let mut constraint_gates = 0;
+        for (a, b, _) in &r1cs.constraints {
+            if r1cs::is_linear(a, b) {
+                gates.push(None);
+            } else {
+                gates.push(Some(constraint_gates));
+                constraint_gates += 1;
+            }
+        }
+
+        // Uncommitted private variables fill the gates two at a time; their
+        // output wire is just the product and no constraint weighs it
+        let first_gate = constraint_gates;
+        let mut wires = 0;
+        for slot in slots.iter_mut().filter(|slot| slot.is_none()) {
+            let gate = first_gate + wires / 2;
+            *slot = Some(if wires % 2 == 0 {
+                Slot::Left(gate)
+            } else {
+                Slot::Right(gate)
+            });
+            wires += 1;
+        }
+
+        Self {
+            slots: slots.into_iter().flatten().collect(),
+            statement,
+            rows: r1cs.constraints.len() + 2 * constraint_gates,
+            gates,
+            constraint_gates,
+            used_gates: first_gate + wires.div_ceil(2),
+        }
+    }
+}
+
+/// The R1CS variable committed at each statement position of a conversion that
+/// also commits the `committed` variables
+///
+/// The public signals come first in variable order, without the constant wire, so
+/// public variable `i` sits at position `i - 1`; committed private variables
+/// follow them in the given order.
+pub fn statement_variables<F: PrimeField>(r1cs: &R1CS<F>, committed: &[usize]) -> Vec<usize> {
+    VariableLayout::new(r1cs, committed).statement
+}
+
+/// Where each R1CS constraint lands in the converted circuit: its rows, and its
+/// gate when it is multiplicative
+///
+/// Rows follow constraint order, three per multiplicative constraint and one per
+/// linear one, so a checker can hold each constraint against exactly the part of
+/// the circuit converted from it. The placement is the same whatever is committed.
+pub fn constraint_placement<F: PrimeField>(r1cs: &R1CS<F>) -> Vec<(Range<usize>, Option<usize>)> {
+    let mut next_row = 0;
+    VariableLayout::new(r1cs, &[])
+        .gates
+        .into_iter()
+        .map(|gate| {
+            let start = next_row;
+            next_row += if gate.is_some() { 3 } else { 1 };
+            (start..next_row, gate)
+        })
+        .collect()
+}
 
 /// Converts a Circom R1CS circuit to Bulletproofs format with power-of-2 padding
 /// 
-/// Transforms R1CS constraints A·w ⊙ B·w = C·w into bulletproof weight matrices.
-/// The circuit and witness are automatically padded to the next power of 2.
+/// Each R1CS constraint A·w ⊙ B·w = C·w becomes one multiplication gate whose
+/// left, right and output wires hold A·w, B·w and C·w, except linear constraints
+/// with an empty A or B side, which need no gate. Only the public signals
+/// are committed in the statement's `v`; private variables are carried as gate
+/// wires, so the statement reveals nothing about them. Terms of the constant
+/// wire are constants and move into `c`. The gates are padded to
+/// the next power of 2.
+/// A `ConversionReport` describing the resulting shape is returned alongside.
 pub fn circom_to_bulletproofs<Fr: Field + PrimeField>(
     circom_circuit: &CircomCircuit<Fr>,
-) -> Result<(Circuit<Fr>, Witness<Fr>), ConversionError> {
+) -> Result<(Circuit<Fr>, Witness<Fr>, ConversionReport), ConversionError> {
+    circom_to_bulletproofs_committing(circom_circuit, &[])
+}
+
+/// Like `circom_to_bulletproofs`, but also commits the `committed` private variables
+/// in `v`, so their commitments can be published or linked to other proofs
+///
+/// Their statement positions are given by `statement_variables`.
+pub fn circom_to_bulletproofs_committing<Fr: Field + PrimeField>(
+    circom_circuit: &CircomCircuit<Fr>,
+    committed: &[usize],
+) -> Result<(Circuit<Fr>, Witness<Fr>, ConversionReport), ConversionError> {
+    let (circuit, witness, report) = circom_to_sparse(circom_circuit, committed)?;
+    Ok((circuit.into_dense(), witness, report))
+}
+
+/// Like `circom_to_bulletproofs_committing`, keeping the weight matrices sparse
+///
+/// The dense matrices take rows × gates field elements, which is prohibitive for
+/// large circuits; checks that don't prove can work on the sparse circuit alone.
+pub fn circom_to_sparse<Fr: Field + PrimeField>(
+    circom_circuit: &CircomCircuit<Fr>,
+    committed: &[usize],
+) -> Result<(SparseCircuit<Fr>, Witness<Fr>, ConversionReport), ConversionError> {
+    let r1cs = &circom_circuit.r1cs;
+    if circom_circuit.witness.is_none() {
+        return Err(ConversionError::MissingWitness);
+    }
+    let circuit = r1cs_to_sparse(r1cs, committed)?;
+    let witness = convert_witness(circom_circuit, committed)?;
+    Ok((circuit, witness, ConversionReport::committing(r1cs, committed)))
+}
+
+/// The witness half of `circom_to_sparse`, for a circuit converted earlier
+///
+/// The circuit of `r1cs_to_sparse` over the same R1CS and `committed` variables is
+/// the one this witness satisfies, so a cached conversion only needs this.
+pub fn convert_witness<Fr: Field + PrimeField>(
+    circom_circuit: &CircomCircuit<Fr>,
+    committed: &[usize],
+) -> Result<Witness<Fr>, ConversionError> {
     let r1cs = &circom_circuit.r1cs;
     let witness_values = circom_circuit.witness.as_ref()
         .ok_or(ConversionError::MissingWitness)?;
     
-    let constraints_count = r1cs.constraints.len();
-    let variables_count = r1cs.num_variables;
+    let layout = VariableLayout::new(r1cs, committed);
+    let gates_count = layout.used_gates.next_power_of_two();
     
-    if variables_count == 0 || constraints_count == 0 {
-        return Err(ConversionError::EmptyCircuit);
+    // Extract witness values in variable order
+    let values = r1cs::variable_values(r1cs, witness_values)?;
+    // An R1CS without variables has no constant wire to check
+    if let Some(one) = values.get(ONE_WIRE)
+        && !one.is_one()
+    {
+        return Err(ConversionError::ConstantWire {
+            value: r1cs::signed(*one),
+        });
     }
     
-    // Bulletproofs requires power-of-2 dimensions
-    let padded_variables_count = variables_count.next_power_of_two();
+    // The gate of a constraint carries (A·w, B·w, C·w); padding gates stay 0 · 0 = 0
+    let mut a_l = vec![Fr::zero(); gates_count];
+    let mut a_r = vec![Fr::zero(); gates_count];
+    let mut a_o = vec![Fr::zero(); gates_count];
+    for ((a_coeffs, b_coeffs, c_coeffs), gate) in r1cs.constraints.iter().zip(&layout.gates) {
+        if let Some(gate) = *gate {
+            a_l[gate] = r1cs::evaluate(a_coeffs, &values);
+            a_r[gate] = r1cs::evaluate(b_coeffs, &values);
+            a_o[gate] = r1cs::evaluate(c_coeffs, &values);
+        }
+    }
+    for (&slot, &value) in layout.slots.iter().zip(&values) {
+        match slot {
+            Slot::Constant | Slot::Committed(_) => {}
+            Slot::Left(gate) => a_l[gate] = value,
+            Slot::Right(gate) => a_r[gate] = value,
+        }
+    }
+    for gate in layout.constraint_gates..layout.used_gates {
+        a_o[gate] = a_l[gate] * a_r[gate];
+    }
     
-    // Initialize bulletproof constraint matrices with power-of-2 padding
-    let zero_row = vec![Fr::zero(); padded_variables_count];
-    let mut w_l = vec![zero_row.clone(); constraints_count];
-    let mut w_r = vec![zero_row.clone(); constraints_count];
-    let mut w_o = vec![zero_row.clone(); constraints_count];
-    let w_v = vec![zero_row; constraints_count];
-    let c = vec![Fr::zero(); constraints_count];
+    let v = layout.statement.iter().map(|&variable| values[variable]).collect::<Vec<_>>();
+    Ok(Witness {
+        a_l,
+        a_r,
+        a_o,
+        gamma: vec![Fr::zero(); v.len()],
+        v,
+    })
+}
+
+/// Converts the constraints of a Circom R1CS into a Bulletproofs circuit
+///
+/// Only the circuit shape is produced, so verifiers can rebuild the circuit
+/// without access to any witness.
+///
+/// A multiplicative constraint yields a gate g and three linear constraints tying
+/// its wires to the variables: `a_l[g] = A·w`, `a_r[g] = B·w` and `a_o[g] = C·w`,
+/// where each variable is either a commitment in `v` or a wire of a later gate.
+/// Together with the gate relation `a_l ⊙ a_r = a_o` these enforce
+/// A·w ⊙ B·w = C·w. A linear constraint with an empty A or B side is the single
+/// linear constraint `0 = C·w` and takes no gate. The linear constraints are
+/// padded to a power of 2 with empty rows, so `Circuit::size` is the padded row
+/// count; `ConversionReport::constraints` keeps the R1CS count.
+pub fn r1cs_to_bulletproofs<Fr: Field + PrimeField>(
+    r1cs: &R1CS<Fr>,
+) -> Result<Circuit<Fr>, ConversionError> {
+    r1cs_to_bulletproofs_committing(r1cs, &[])
+}
+
+/// Like `r1cs_to_bulletproofs`, for proofs that also commit the `committed` variables
+pub fn r1cs_to_bulletproofs_committing<Fr: Field + PrimeField>(
+    r1cs: &R1CS<Fr>,
+    committed: &[usize],
+) -> Result<Circuit<Fr>, ConversionError> {
+    Ok(r1cs_to_sparse(r1cs, committed)?.into_dense())
+}
+
+/// The circuit of `r1cs_to_bulletproofs_committing` with sparse weight matrices
+///
+/// The rows are streamed straight into the final circuit in one pass over the
+/// constraints, which also validates them, so the memory taken is that of the
+/// coefficients plus the per-variable layout, never rows × gates.
+pub fn r1cs_to_sparse<Fr: Field + PrimeField>(
+    r1cs: &R1CS<Fr>,
+    committed: &[usize],
+) -> Result<SparseCircuit<Fr>, ConversionError> {
+    let variables_count = r1cs.num_variables;
+    
+    // Skipping a commitment would shift the statement positions of those after it
+    if let Some(&variable) = committed.iter().find(|&&v| v >= variables_count) {
+        return Err(ConversionError::InvalidCommitment {
+            variable,
+            variables: variables_count,
+        });
+    }
+    
+    // Bulletproofs requires a power-of-2 number of gates; a circuit without
+    // constraints or private variables still gets one, and one empty row
+    let layout = VariableLayout::new(r1cs, committed);
+    let gates_count = layout.used_gates.next_power_of_two();
     
-    // Map R1CS constraints to bulletproof weight matrices
-    for (i, (a_coeffs, b_coeffs, c_coeffs)) in r1cs.constraints.iter().enumerate() {
-        // A -> w_l, B -> w_r, -C -> w_o (negated to move to LHS)
-        for &(var_idx, coeff) in a_coeffs {
-            if var_idx < padded_variables_count {
-                w_l[i][var_idx] = coeff;
+    // A gate's three rows bind its left, right and output wire in turn; a linear
+    // constraint's single row selects no wire and reads 0 = C·w
+    let mut circuit = SparseCircuit::with_capacity(
+        gates_count,
+        layout.statement.len(),
+        layout.rows.next_power_of_two(),
+    );
+    for (constraint, ((a_coeffs, b_coeffs, c_coeffs), gate)) in
+        r1cs.constraints.iter().zip(&layout.gates).enumerate()
+    {
+        // A malformed r1cs may reference variables it doesn't have; dropping those
+        // terms would prove a different circuit than the one compiled
+        for (side, lc) in [(Side::A, a_coeffs), (Side::B, b_coeffs), (Side::C, c_coeffs)] {
+            if let Some(&(variable, _)) = lc.iter().find(|(v, _)| *v >= variables_count) {
+                return Err(ConversionError::InvalidConstraint {
+                    constraint,
+                    side,
+                    variable,
+                    variables: variables_count,
+                });
             }
         }
-        for &(var_idx, coeff) in b_coeffs {
-            if var_idx < padded_variables_count {
-                w_r[i][var_idx] = coeff;
+        let sides = match *gate {
+            Some(gate) => [
+                Some((Some((0, gate)), a_coeffs)),
+                Some((Some((1, gate)), b_coeffs)),
+                Some((Some((2, gate)), c_coeffs)),
+            ],
+            None => [Some((None, c_coeffs)), None, None],
+        };
+        for (selector, coeffs) in sides.into_iter().flatten() {
+            let mut wires = [Vec::new(), Vec::new(), Vec::new()];
+            let mut w_v = Vec::new();
+            let mut c = Fr::zero();
+            if let Some((wire, gate)) = selector {
+                wires[wire].push((gate, Fr::one()));
             }
-        }
-        for &(var_idx, coeff) in c_coeffs {
-            if var_idx < padded_variables_count {
-                w_o[i][var_idx] = -coeff;
+            for &(var_idx, coeff) in coeffs {
+                // Committed terms and constants sit on the right-hand side, wires
+                // move left negated
+                match layout.slots[var_idx] {
+                    Slot::Constant => c += coeff,
+                    Slot::Committed(j) => w_v.push((j, coeff)),
+                    Slot::Left(gate) => wires[0].push((gate, -coeff)),
+                    Slot::Right(gate) => wires[1].push((gate, -coeff)),
+                }
             }
+            let [w_l, w_r, w_o] = wires;
+            circuit.push_row(w_l, w_r, w_o, w_v, c);
         }
     }
     
-    // Extract and pad witness values
-    let mut witness = if let Some(wire_mapping) = &r1cs.wire_mapping {
-        wire_mapping.iter()
-            .take(variables_count)
-            .map(|&idx| witness_values.get(idx).copied().unwrap_or(Fr::zero()))
-            .collect::<Vec<_>>()
-    } else {
-        witness_values[..variables_count].to_vec()
-    };
-    witness.resize(padded_variables_count, Fr::zero());
-    
-    let circuit = Circuit::new(w_l, w_r, w_o, w_v, c);
-    let bp_witness = Witness {
-        a_l: vec![Fr::zero(); padded_variables_count],
-        a_r: vec![Fr::zero(); padded_variables_count],
-        a_o: vec![Fr::zero(); padded_variables_count],
-        v: witness,
-        gamma: vec![Fr::zero(); padded_variables_count],
-    };
+    // Pad the rows to a power of 2 too with empty rows, which read 0 = 0
+    while !circuit.size().is_power_of_two() {
+        circuit.push_row(Vec::new(), Vec::new(), Vec::new(), Vec::new(), Fr::zero());
+    }
     
-    Ok((circuit, bp_witness))
+    Ok(circuit)
+}
+
+/// How the padding gates of a converted witness are filled
+///
+/// Padding rows are empty under every strategy, so verifiers rebuild the same
+/// circuit whichever one the prover chose.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaddingStrategy {
+    /// Zero wires, as the conversion leaves them
+    #[default]
+    Zero,
+    /// Satisfied `1 · 1 = 1` gates, so no wire of the circuit is left at zero
+    Dummy,
+    /// Random satisfied gates, which leave no recognizable structure in the
+    /// proof's wire commitments
+    Random,
+}
+
+/// Work a padding strategy adds on top of the padded dimensions, which are the
+/// same for every strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PaddingOverhead {
+    pub strategy: PaddingStrategy,
+    pub gates: usize,
+    pub rows: usize,
+    /// Field elements sampled to fill the padding
+    pub random_scalars: usize,
+}
+
+impl PaddingStrategy {
+    pub const ALL: [PaddingStrategy; 3] = [Self::Zero, Self::Dummy, Self::Random];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PaddingStrategy::Zero => "zero",
+            PaddingStrategy::Dummy => "dummy",
+            PaddingStrategy::Random => "random",
+        }
+    }
+
+    /// Fills the gates of `witness` past the first `used`
+    pub fn apply<Fr: Field + PrimeField, R: RngCore + CryptoRng>(
+        self,
+        witness: &mut Witness<Fr>,
+        used: usize,
+        rng: &mut R,
+    ) {
+        match self {
+            PaddingStrategy::Zero => {}
+            PaddingStrategy::Dummy => {
+                for i in used..witness.a_l.len() {
+                    witness.a_l[i] = Fr::one();
+                    witness.a_r[i] = Fr::one();
+                    witness.a_o[i] = Fr::one();
+                }
+            }
+            PaddingStrategy::Random => randomize_padding(witness, used, rng),
+        }
+    }
+
+    /// What this strategy adds for a circuit of the given shape
+    pub fn overhead(self, report: &ConversionReport) -> PaddingOverhead {
+        PaddingOverhead {
+            strategy: self,
+            gates: report.padding_gates,
+            rows: report.padding_rows,
+            random_scalars: match self {
+                PaddingStrategy::Random => 2 * report.padding_gates,
+                PaddingStrategy::Zero | PaddingStrategy::Dummy => 0,
+            },
+        }
+    }
+}
+
+impl fmt::Display for PaddingStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for PaddingStrategy {
+    type Err = UnknownPaddingStrategy;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zero" => Ok(PaddingStrategy::Zero),
+            "dummy" => Ok(PaddingStrategy::Dummy),
+            "random" => Ok(PaddingStrategy::Random),
+            other => Err(UnknownPaddingStrategy(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown padding strategy '{0}', expected zero, dummy or random")]
+pub struct UnknownPaddingStrategy(pub String);
+
+/// Replaces the zero padding of a witness with random self-consistent values
+///
+/// Gates past the first `used` are padding: their weight-matrix columns are zero,
+/// so any values satisfying `a_l * a_r = a_o` keep the circuit satisfied.
+/// Random values remove the recognizable all-zero structure that padding
+/// otherwise leaves in the proof's wire commitments.
+pub fn randomize_padding<Fr: Field + PrimeField, R: RngCore + CryptoRng>(
+    witness: &mut Witness<Fr>,
+    used: usize,
+    rng: &mut R,
+) {
+    for i in used..witness.a_l.len() {
+        witness.a_l[i] = Fr::rand(rng);
+        witness.a_r[i] = Fr::rand(rng);
+        witness.a_o[i] = witness.a_l[i] * witness.a_r[i];
+    }
+}
+
+/// Number of public signals, which take the first statement positions
+///
+/// Every variable below `num_inputs` except the constant wire is public.
+pub fn public_signal_count<F: PrimeField>(r1cs: &R1CS<F>) -> usize {
+    r1cs.num_inputs.min(r1cs.num_variables).saturating_sub(1)
+}
+
+/// Samples random blinding factors for the committed private variables, the
+/// statement positions after the first `public`
+///
+/// The conversion leaves every `gamma` at zero, so each commitment `v·G` can be
+/// checked against guesses of its value. A fresh `gamma` makes it hiding. The
+/// public signals keep `gamma = 0`: the verifier must be able to check their
+/// commitments against the claimed values, which binds those values to the
+/// proof. Openings applied afterwards replace the blindings of the signals they
+/// commit.
+pub fn blind<Fr: Field + PrimeField, R: RngCore + CryptoRng>(
+    witness: &mut Witness<Fr>,
+    public: usize,
+    rng: &mut R,
+) {
+    for gamma in witness.gamma.iter_mut().skip(public) {
+        *gamma = Fr::rand(rng);
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum ConversionError {
-    #[error("Circuit witness is missing")]
+    #[error("Circuit witness is missing; conversion needs a value for every R1CS variable")]
     MissingWitness,
-    #[error("Circuit is empty")]
-    EmptyCircuit,
-}
\ No newline at end of file
+    #[error("Witness assigns {value} to the constant wire {}, which must hold 1", ONE_WIRE)]
+    ConstantWire {
+        /// The assigned value, as a signed integer
+        value: String,
+    },
+    #[error(transparent)]
+    WireMapping(#[from] WireMappingError),
+    #[error(
+        "Constraint {constraint} references variable {variable} on its {side} side, but the R1CS only has {variables} variables"
+    )]
+    InvalidConstraint {
+        constraint: usize,
+        side: Side,
+        variable: usize,
+        variables: usize,
+    },
+    #[error("Cannot commit variable {variable}: the R1CS only has {variables} variables")]
+    InvalidCommitment { variable: usize, variables: usize },
+}
+
+impl ConversionError {
+    /// Short machine-readable name of the failure, for reports and logs
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ConversionError::MissingWitness => "missing_witness",
+            ConversionError::ConstantWire { .. } => "constant_wire",
+            ConversionError::WireMapping(_) => "wire_mapping",
+            ConversionError::InvalidConstraint { .. } => "invalid_constraint",
+            ConversionError::InvalidCommitment { .. } => "invalid_commitment",
+        }
+    }
+}
+
+/// Side of an R1CS constraint `A·w ⊙ B·w = C·w`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    A,
+    B,
+    C,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Side::A => "A",
+            Side::B => "B",
+            Side::C => "C",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::Zero;
+
+    type Constraint = (Vec<(usize, Fr)>, Vec<(usize, Fr)>, Vec<(usize, Fr)>);
+
+    fn fr(x: i64) -> Fr {
+        Fr::from(x)
+    }
+
+    fn lc(terms: &[(usize, i64)]) -> Vec<(usize, Fr)> {
+        terms.iter().map(|&(idx, coeff)| (idx, fr(coeff))).collect()
+    }
+
+    /// Dense row of width `n` with the given nonzero entries
+    fn row(n: usize, terms: &[(usize, i64)]) -> Vec<Fr> {
+        let mut row = vec![Fr::zero(); n];
+        for &(idx, coeff) in terms {
+            row[idx] = fr(coeff);
+        }
+        row
+    }
+
+    fn values(xs: &[i64]) -> Vec<Fr> {
+        xs.iter().copied().map(fr).collect()
+    }
+
+    fn circom(
+        num_inputs: usize,
+        num_variables: usize,
+        constraints: Vec<Constraint>,
+        witness: Option<Vec<Fr>>,
+    ) -> CircomCircuit<Fr> {
+        CircomCircuit {
+            r1cs: R1CS {
+                num_inputs,
+                num_aux: num_variables - num_inputs,
+                num_variables,
+                constraints,
+                wire_mapping: None,
+            },
+            witness,
+        }
+    }
+
+    /// `c <== a * b` over wires [1, c, a, b]
+    fn multiplier() -> CircomCircuit<Fr> {
+        circom(
+            2,
+            4,
+            vec![(lc(&[(2, 1)]), lc(&[(3, 1)]), lc(&[(1, 1)]))],
+            Some(values(&[1, 33, 3, 11])),
+        )
+    }
+
+    #[test]
+    fn multiplier_gate() {
+        let (circuit, witness, _) = circom_to_bulletproofs(&multiplier()).unwrap();
+
+        // Gate 0 is the constraint, gate 1 carries the private `a` and `b`,
+        // and the fourth row is padding
+        let empty = || row(2, &[]);
+        assert_eq!(circuit.w_l, vec![row(2, &[(0, 1), (1, -1)]), empty(), empty(), empty()]);
+        assert_eq!(circuit.w_r, vec![empty(), row(2, &[(0, 1), (1, -1)]), empty(), empty()]);
+        assert_eq!(circuit.w_o, vec![empty(), empty(), row(2, &[(0, 1)]), empty()]);
+        let no_v = || row(1, &[]);
+        assert_eq!(circuit.w_v, vec![no_v(), no_v(), row(1, &[(0, 1)]), no_v()]);
+        assert_eq!(circuit.c, values(&[0; 4]));
+        assert_eq!(circuit.size(), 4);
+
+        // Only the public output is committed, the constant wire is not
+        assert_eq!(witness.v, values(&[33]));
+        assert_eq!(witness.a_l, values(&[3, 3]));
+        assert_eq!(witness.a_r, values(&[11, 11]));
+        assert_eq!(witness.a_o, values(&[33, 33]));
+        assert_eq!(witness.gamma, values(&[0]));
+        assert!(circuit.is_satisfied_by(&witness));
+    }
+
+    #[test]
+    fn wrong_product_is_unsatisfied() {
+        let mut circom = multiplier();
+        circom.witness = Some(values(&[1, 34, 3, 11]));
+        let (circuit, mut witness, _) = circom_to_bulletproofs(&circom).unwrap();
+        assert!(!circuit.is_satisfied_by(&witness));
+
+        // Gate values consistent with each other but not with `v` are caught too
+        witness.a_o[0] = fr(33);
+        assert!(!circuit.is_satisfied_by(&witness));
+    }
+
+    #[test]
+    fn requested_private_variables_are_committed() {
+        let circom = multiplier();
+        assert_eq!(statement_variables(&circom.r1cs, &[3, 1, 3]), [1, 3]);
+
+        let (circuit, witness, report) =
+            circom_to_bulletproofs_committing(&circom, &[3, 1, 3]).unwrap();
+        assert_eq!(witness.v, values(&[33, 11]));
+        // `a` keeps a gate of its own
+        assert_eq!(witness.a_l, values(&[3, 3]));
+        assert_eq!(witness.a_r, values(&[11, 0]));
+        assert_eq!(circuit.w_v[1], row(2, &[(1, 1)]));
+        assert_eq!(report.commitments, 2);
+        assert!(circuit.is_satisfied_by(&witness));
+    }
+
+    /// simpleCheck: `a + b === c`, `b * c === d`, `out <== c + d` over wires [1, out, a, b, c, d]
+    #[test]
+    fn mixed_linear_and_multiplicative_constraints() {
+        let circom = circom(
+            5,
+            6,
+            vec![
+                (lc(&[]), lc(&[]), lc(&[(2, 1), (3, 1), (4, -1)])),
+                (lc(&[(3, 1)]), lc(&[(4, 1)]), lc(&[(5, 1)])),
+                (lc(&[]), lc(&[]), lc(&[(1, 1), (4, -1), (5, -1)])),
+            ],
+            Some(values(&[1, 168, 3, 11, 14, 154])),
+        );
+        let (circuit, witness, report) = circom_to_bulletproofs(&circom).unwrap();
+
+        // The four public signals are committed at their variable index minus
+        // one; the linear constraints are single rows, so `b * c` is gate 0 and
+        // the private `d` sits on gate 1
+        assert_eq!(
+            circuit.w_v[..5],
+            vec![
+                row(4, &[(1, 1), (2, 1), (3, -1)]),
+                row(4, &[(2, 1)]),
+                row(4, &[(3, 1)]),
+                row(4, &[]),
+                row(4, &[(0, 1), (3, -1)]),
+            ]
+        );
+        assert_eq!(circuit.w_v[5..], vec![row(4, &[]); 3]);
+        assert_eq!(circuit.w_l[0], row(2, &[]));
+        assert_eq!(circuit.w_l[1], row(2, &[(0, 1)]));
+        assert_eq!(circuit.w_l[3], row(2, &[(1, -1)]));
+        assert_eq!(circuit.w_l[4], row(2, &[(1, 1)]));
+        assert_eq!(circuit.w_o[3], row(2, &[(0, 1)]));
+        assert_eq!(circuit.c, values(&[0; 8]));
+
+        // Linear constraints take no gate
+        assert_eq!(witness.a_l, values(&[11, 154]));
+        assert_eq!(witness.a_r, values(&[14, 0]));
+        assert_eq!(witness.a_o, values(&[154, 0]));
+        assert_eq!(witness.v, values(&[168, 3, 11, 14]));
+        assert_eq!(witness.gamma, values(&[0; 4]));
+        assert!(circuit.is_satisfied_by(&witness));
+
+        assert_eq!(report.commitments, 4);
+        assert_eq!(report.used_gates, 2);
+        assert_eq!(report.gates, 2);
+        assert_eq!(report.rows, 8);
+        assert_eq!(report.padding_rows, 3);
+        assert_eq!(report.linear_constraints, 2);
+        assert_eq!(report.multiplicative_constraints, 1);
+        // 9 coefficients and 3 wire selectors among the 8 × (3 × 2 + 4) entries
+        assert_eq!(report.nonzero_weights, 12);
+        assert_eq!(report.sparsity, 1.0 - 12.0 / 80.0);
+        assert!((report.density() - 12.0 / 80.0).abs() < 1e-12);
+        assert_eq!(report.padding_overhead(), 0.0);
+    }
+
+    /// `(a + 2) * (b - 1) === c + 7` and `a + b + 3 === d` over wires [1, c, a, b, d]
+    #[test]
+    fn constant_terms_move_into_c() {
+        let circom = circom(
+            2,
+            5,
+            vec![
+                (lc(&[(2, 1), (0, 2)]), lc(&[(3, 1), (0, -1)]), lc(&[(1, 1), (0, 7)])),
+                (lc(&[]), lc(&[]), lc(&[(2, 1), (3, 1), (0, 3), (4, -1)])),
+            ],
+            Some(values(&[1, 43, 3, 11, 17])),
+        );
+        let (circuit, witness, _) = circom_to_bulletproofs(&circom).unwrap();
+
+        // Constants move into `c` rather than weighing a committed constant wire
+        let no_v = || row(1, &[]);
+        assert_eq!(circuit.w_v, vec![no_v(), no_v(), row(1, &[(0, 1)]), no_v()]);
+        assert_eq!(circuit.c, values(&[2, -1, 7, 3]));
+        assert_eq!(circuit.w_l[0], row(4, &[(0, 1), (1, -1)]));
+        assert_eq!(circuit.w_r[1], row(4, &[(0, 1), (1, -1)]));
+        assert_eq!(circuit.w_l[3], row(4, &[(1, -1), (2, 1)]));
+        assert_eq!(circuit.w_r[3], row(4, &[(1, -1)]));
+        assert_eq!(witness.v, values(&[43]));
+        assert_eq!(witness.gamma, values(&[0]));
+        assert_eq!(witness.a_l, values(&[5, 3, 17, 0]));
+        assert_eq!(witness.a_r, values(&[10, 11, 0, 0]));
+        assert_eq!(witness.a_o, values(&[50, 33, 0, 0]));
+        assert!(circuit.is_satisfied_by(&witness));
+    }
+
+    /// `(a + 2a) * b === out + 2out` over wires [1, out, a, b], listing `a` and
+    /// `out` twice in the same linear combination
+    #[test]
+    fn repeated_variables_accumulate() {
+        let circom = circom(
+            2,
+            4,
+            vec![(lc(&[(2, 1), (2, 2)]), lc(&[(3, 1)]), lc(&[(1, 1), (1, 2)]))],
+            Some(values(&[1, 33, 3, 11])),
+        );
+        let (circuit, witness, _) = circom_to_bulletproofs(&circom).unwrap();
+        assert_eq!(circuit.w_l[0], row(2, &[(0, 1), (1, -3)]));
+        assert_eq!(circuit.w_v[2], row(1, &[(0, 3)]));
+        assert_eq!(witness.a_l, values(&[9, 3]));
+        assert_eq!(witness.a_o, values(&[99, 33]));
+        assert!(circuit.is_satisfied_by(&witness));
+
+        let (sparse, witness, _) = circom_to_sparse(&circom, &[]).unwrap();
+        assert!(sparse.is_satisfied_by(&witness));
+    }
+
+    #[test]
+    fn sparse_circuit_matches_the_dense_one() {
+        let mut circom = multiplier();
+        let (sparse, witness, _) = circom_to_sparse(&circom, &[]).unwrap();
+        let (dense, _, _) = circom_to_bulletproofs(&circom).unwrap();
+        assert_eq!(sparse.to_dense().w_l, dense.w_l);
+        assert_eq!(sparse.to_dense().w_v, dense.w_v);
+        assert_eq!((sparse.size(), sparse.dim()), (dense.size(), dense.dim()));
+        // The padded row count is reserved up front, so the rows never reallocate
+        assert_eq!(sparse.c.capacity(), sparse.size());
+        assert_eq!(sparse.clone().into_dense().w_o, dense.w_o);
+        // Three wire selectors plus the coefficients of `a`, `b` and `c`
+        assert_eq!(sparse.nonzero_weights(), 6);
+        assert!(sparse.is_satisfied_by(&witness));
+
+        circom.witness = Some(values(&[1, 34, 3, 11]));
+        let (sparse, witness, _) = circom_to_sparse(&circom, &[]).unwrap();
+        assert!(!sparse.is_satisfied_by(&witness));
+    }
+
+    #[test]
+    fn constant_wire_must_hold_one() {
+        let mut circom = multiplier();
+        circom.witness = Some(values(&[0, 33, 3, 11]));
+        let Err(e) = circom_to_bulletproofs(&circom) else {
+            panic!("expected the constant wire to be rejected");
+        };
+        assert_eq!(e.kind(), "constant_wire");
+        assert_eq!(
+            e.to_string(),
+            "Witness assigns 0 to the constant wire 0, which must hold 1"
+        );
+    }
+
+    #[test]
+    fn wire_mapping_selects_witness_values() {
+        let mut circom = multiplier();
+        circom.r1cs.wire_mapping = Some(vec![0, 1, 3, 4]);
+        circom.witness = Some(values(&[1, 33, 99, 3, 11]));
+
+        let (_, witness, _) = circom_to_bulletproofs(&circom).unwrap();
+        assert_eq!(witness.v, values(&[33]));
+        assert_eq!(witness.a_l[1], fr(3));
+        assert_eq!(witness.a_r[1], fr(11));
+
+        // A mapping reaching past the witness no longer reads zeros
+        circom.r1cs.wire_mapping = Some(vec![0, 1, 3, 5]);
+        assert!(matches!(
+            circom_to_bulletproofs(&circom),
+            Err(ConversionError::WireMapping(WireMappingError::OutOfRange {
+                variable: 3,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn random_padding_still_proves() {
+        let mut rng = rand::rngs::OsRng;
+        let circom = circom(
+            2,
+            5,
+            vec![(lc(&[(2, 1)]), lc(&[(3, 1)]), lc(&[(1, 1)])); 3],
+            Some(values(&[1, 33, 3, 11, 7])),
+        );
+        let (circuit, mut witness, report) = circom_to_bulletproofs(&circom).unwrap();
+        randomize_padding(&mut witness, report.used_gates, &mut rng);
+
+        // Three constraint gates and two private variable gates are untouched,
+        // the three padding gates are random
+        assert_eq!(report.used_gates, 5);
+        assert_eq!(witness.a_l[..5], values(&[3, 3, 3, 3, 7]));
+        assert_eq!(witness.a_r[..5], values(&[11, 11, 11, 11, 0]));
+        assert!(witness.a_l[5..].iter().all(|x| !x.is_zero()));
+        assert!(circuit.is_satisfied_by(&witness));
+
+        let crs = crate::crs::CrsParams::new(circuit.dim()).derive();
+        let (statement, proof) = crate::pipeline::prove(&crs, &circuit, &witness, &mut rng).unwrap();
+        crate::pipeline::verify(&crs, &circuit, &statement, &proof, &mut rng).unwrap();
+    }
+
+    #[test]
+    fn padding_strategies_keep_the_circuit_satisfied() {
+        let mut rng = rand::rngs::OsRng;
+        let circom = circom(
+            2,
+            5,
+            vec![(lc(&[(2, 1)]), lc(&[(3, 1)]), lc(&[(1, 1)])); 3],
+            Some(values(&[1, 33, 3, 11, 7])),
+        );
+        let (circuit, _, report) = circom_to_bulletproofs(&circom).unwrap();
+        assert_eq!((report.padding_gates, report.padding_rows), (3, 7));
+
+        for strategy in PaddingStrategy::ALL {
+            let (_, mut padded, _) = circom_to_bulletproofs(&circom).unwrap();
+            strategy.apply(&mut padded, report.used_gates, &mut rng);
+            assert_eq!(padded.a_l[..5], values(&[3, 3, 3, 3, 7]));
+            assert!(circuit.is_satisfied_by(&padded));
+            assert_eq!(strategy.name().parse::<PaddingStrategy>().unwrap(), strategy);
+        }
+        assert_eq!(PaddingStrategy::Random.overhead(&report).random_scalars, 6);
+        assert_eq!(PaddingStrategy::Dummy.overhead(&report).random_scalars, 0);
+        assert!("ones".parse::<PaddingStrategy>().is_err());
+    }
+
+    #[test]
+    fn blinding_hides_committed_private_variables_only() {
+        let mut rng = rand::rngs::OsRng;
+        let circom = multiplier();
+        // `c` is public, `a` committed on request
+        let (circuit, mut witness, _) = circom_to_bulletproofs_committing(&circom, &[2]).unwrap();
+        let crs = crate::crs::CrsParams::new(circuit.dim()).derive();
+        let (unblinded, _) = crate::pipeline::prove(&crs, &circuit, &witness, &mut rng).unwrap();
+
+        assert_eq!(public_signal_count(&circom.r1cs), 1);
+        blind(&mut witness, public_signal_count(&circom.r1cs), &mut rng);
+        assert!(witness.gamma[0].is_zero() && !witness.gamma[1].is_zero());
+        let (statement, proof) = crate::pipeline::prove(&crs, &circuit, &witness, &mut rng).unwrap();
+        assert_eq!(statement.v[0], unblinded.v[0]);
+        assert_ne!(statement.v[1], unblinded.v[1]);
+        crate::pipeline::verify(&crs, &circuit, &statement, &proof, &mut rng).unwrap();
+    }
+
+    #[test]
+    fn missing_witness_is_rejected() {
+        let mut circom = multiplier();
+        circom.witness = None;
+        assert!(matches!(
+            circom_to_bulletproofs(&circom),
+            Err(ConversionError::MissingWitness)
+        ));
+    }
+
+    #[test]
+    fn out_of_range_variables_are_rejected() {
+        let mut circom = multiplier();
+        circom.r1cs.constraints.push((lc(&[(2, 1)]), lc(&[(7, 1)]), lc(&[(1, 1)])));
+        assert!(matches!(
+            circom_to_bulletproofs(&circom),
+            Err(ConversionError::InvalidConstraint {
+                constraint: 1,
+                side: Side::B,
+                variable: 7,
+                variables: 4,
+            })
+        ));
+        assert!(r1cs_to_bulletproofs(&circom.r1cs).is_err());
+    }
+
+    #[test]
+    fn out_of_range_commitments_are_rejected() {
+        let circom = multiplier();
+        let e = circom_to_sparse(&circom, &[2, 4]).unwrap_err();
+        assert!(matches!(
+            e,
+            ConversionError::InvalidCommitment {
+                variable: 4,
+                variables: 4,
+            }
+        ));
+        assert_eq!(e.kind(), "invalid_commitment");
+    }
+
+    /// `out <-- 5` alone: no constraints, one public output
+    #[test]
+    fn circuit_without_constraints_commits_its_signals() {
+        let circom = circom(2, 2, vec![], Some(values(&[1, 5])));
+        let (circuit, witness, report) = circom_to_sparse(&circom, &[]).unwrap();
+        assert_eq!((circuit.dim(), circuit.size()), (1, 1));
+        assert_eq!(witness.v, values(&[5]));
+        assert!(circuit.is_satisfied_by(&witness));
+        assert_eq!(
+            (report.gates, report.rows, report.padding_gates, report.padding_rows),
+            (1, 1, 1, 1)
+        );
+        assert_eq!(report.padding_overhead(), 1.0);
+        assert_eq!(report, ConversionReport::from_r1cs(&circom.r1cs));
+    }
+
+    /// A template without signals has only the constant wire, and an R1CS without
+    /// variables not even that; both still get a one-gate circuit
+    #[test]
+    fn circuits_without_signals_convert() {
+        for circom in [
+            circom(1, 1, vec![], Some(values(&[1]))),
+            circom(0, 0, vec![], Some(vec![])),
+        ] {
+            let (circuit, witness, _) = circom_to_sparse(&circom, &[]).unwrap();
+            assert_eq!((circuit.dim(), circuit.size(), circuit.commitments), (1, 1, 0));
+            assert!(witness.v.is_empty());
+            assert!(circuit.is_satisfied_by(&witness));
+        }
+        assert!(matches!(
+            circom_to_sparse(&circom(1, 1, vec![], Some(values(&[2]))), &[]),
+            Err(ConversionError::ConstantWire { .. })
+        ));
+    }
+}
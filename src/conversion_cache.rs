@@ -0,0 +1,138 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use ark_bn254::Fr;
+
+use crate::atomic;
+use crate::conversion::ConversionReport;
+use crate::sparse::SparseCircuit;
+
+/// Converted circuits stored as `.circuit` files, each with its `ConversionReport`
+/// in a `.json` file beside it, named by what they were converted from
+///
+/// The circuit is a function of the R1CS, the simplification passes applied to it
+/// and the committed variables only, so the key is the hash of the `.r1cs` bytes
+/// and of those choices. Proving the same circuit again then skips conversion and
+/// only places the new witness.
+#[derive(Debug, Clone)]
+pub struct ConversionCache {
+    pub dir: PathBuf,
+}
+
+impl ConversionCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `<temp>/circom-bp-circuits`
+    pub fn default_dir() -> PathBuf {
+        std::env::temp_dir().join("circom-bp-circuits")
+    }
+
+    /// Hex cache key for converting the R1CS at `r1cs` after the named `passes`,
+    /// in the order they ran, committing the `committed` variables
+    ///
+    /// The crate version is hashed too, so an upgrade changing the layout never
+    /// reads circuits converted by an older one.
+    pub fn key(r1cs: impl AsRef<Path>, passes: &[&str], committed: &[usize]) -> io::Result<String> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(blake3::hash(&std::fs::read(r1cs)?).as_bytes());
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        for pass in passes {
+            hasher.update(&[0]);
+            hasher.update(pass.as_bytes());
+        }
+        hasher.update(&[0]);
+        for &variable in committed {
+            hasher.update(&(variable as u64).to_le_bytes());
+        }
+        Ok(hasher.finalize().to_hex().to_string())
+    }
+
+    fn paths(&self, key: &str) -> (PathBuf, PathBuf) {
+        (
+            self.dir.join(format!("{key}.circuit")),
+            self.dir.join(format!("{key}.json")),
+        )
+    }
+
+    /// The cached circuit and report for `key`, if any; unreadable entries and
+    /// circuits disagreeing with their report count as misses
+    pub fn get(&self, key: &str) -> Option<(SparseCircuit<Fr>, ConversionReport)> {
+        let (circuit, report) = self.paths(key);
+        let report: ConversionReport = serde_json::from_slice(&std::fs::read(report).ok()?).ok()?;
+        let circuit = SparseCircuit::decode(&std::fs::read(circuit).ok()?).ok()?;
+        (circuit.dim() == report.gates
+            && circuit.size() == report.rows
+            && circuit.commitments == report.commitments)
+            .then_some((circuit, report))
+    }
+
+    /// Stores the circuit before its report, so a reader never finds a report
+    /// without the circuit it describes
+    pub fn put(
+        &self,
+        key: &str,
+        circuit: &SparseCircuit<Fr>,
+        report: &ConversionReport,
+    ) -> io::Result<()> {
+        let (circuit_path, report_path) = self.paths(key);
+        atomic::write(circuit_path, circuit.encode().map_err(io::Error::other)?)?;
+        atomic::write(report_path, serde_json::to_vec(report)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::circom_to_sparse;
+    use ark_circom::{CircomCircuit, circom::R1CS};
+
+    #[test]
+    fn keys_follow_r1cs_passes_and_commitments() {
+        let dir =
+            std::env::temp_dir().join(format!("circom-bp-ccache-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let r1cs = dir.join("circuit.r1cs");
+        std::fs::write(&r1cs, b"r1cs v1").unwrap();
+
+        let key = ConversionCache::key(&r1cs, &[], &[]).unwrap();
+        assert_eq!(key, ConversionCache::key(&r1cs, &[], &[]).unwrap());
+        assert_ne!(key, ConversionCache::key(&r1cs, &["dedup"], &[]).unwrap());
+        assert_ne!(key, ConversionCache::key(&r1cs, &[], &[3]).unwrap());
+        assert_ne!(
+            ConversionCache::key(&r1cs, &["dedup", "eliminate"], &[]).unwrap(),
+            ConversionCache::key(&r1cs, &["eliminate", "dedup"], &[]).unwrap()
+        );
+        std::fs::write(&r1cs, b"r1cs v2").unwrap();
+        assert_ne!(key, ConversionCache::key(&r1cs, &[], &[]).unwrap());
+
+        // `c <== a * b` over wires [1, c, a, b]
+        let one = Fr::from(1u64);
+        let circom = CircomCircuit {
+            r1cs: R1CS {
+                num_inputs: 2,
+                num_aux: 2,
+                num_variables: 4,
+                constraints: vec![(vec![(2, one)], vec![(3, one)], vec![(1, one)])],
+                wire_mapping: None,
+            },
+            witness: Some([1u64, 33, 3, 11].map(Fr::from).to_vec()),
+        };
+        let (circuit, _, report) = circom_to_sparse(&circom, &[]).unwrap();
+        let cache = ConversionCache::new(dir.join("cache"));
+        assert_eq!(cache.get(&key), None);
+        cache.put(&key, &circuit, &report).unwrap();
+        assert_eq!(cache.get(&key), Some((circuit.clone(), report.clone())));
+
+        // A report describing another circuit is a miss rather than a wrong proof
+        let mut other = report.clone();
+        other.gates *= 2;
+        cache.put(&key, &circuit, &other).unwrap();
+        assert_eq!(cache.get(&key), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
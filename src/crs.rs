@@ -0,0 +1,49 @@
+use ark_bn254::G1Projective;
+use bulletproofs::circuit::types::CRS as CircuitCRS;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+/// Seed used when the caller does not pick one
+pub const DEFAULT_SEED: [u8; 32] = *b"circom-bp/default-crs-seed/v1.00";
+
+/// Everything needed to re-derive a CRS
+///
+/// Generators are sampled from a ChaCha20 stream keyed by `seed`, so a prover and
+/// a verifier running in different processes agree on the CRS by exchanging these
+/// parameters instead of the generators themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CrsParams {
+    pub size: usize,
+    #[serde(with = "hex::serde")]
+    pub seed: [u8; 32],
+}
+
+impl CrsParams {
+    pub fn new(size: usize) -> Self {
+        Self::with_seed(size, DEFAULT_SEED)
+    }
+
+    pub fn with_seed(size: usize, seed: [u8; 32]) -> Self {
+        Self { size, seed }
+    }
+
+    pub fn derive(&self) -> CircuitCRS<G1Projective> {
+        let mut rng = ChaCha20Rng::from_seed(self.seed);
+        CircuitCRS::rand(self.size, &mut rng)
+    }
+}
+
+/// Parses a 32-byte hex seed, as accepted by `--crs-seed`
+pub fn parse_seed(seed: &str) -> Result<[u8; 32], CrsError> {
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(seed.trim_start_matches("0x"), &mut bytes)
+        .map_err(|e| CrsError::InvalidSeed(e.to_string()))?;
+    Ok(bytes)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CrsError {
+    #[error("Invalid CRS seed, expected 32 hex-encoded bytes: {0}")]
+    InvalidSeed(String),
+}
@@ -0,0 +1,240 @@
+use std::io::Read;
+use std::{fmt, path::Path, str::FromStr};
+
+use ark_ff::{BigInteger, PrimeField};
+
+/// Curves a circuit can be proven over, as selected with `--curve`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Curve {
+    #[default]
+    Bn254,
+    Bls12_381,
+    Pallas,
+}
+
+impl Curve {
+    pub const ALL: [Curve; 3] = [Curve::Bn254, Curve::Bls12_381, Curve::Pallas];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Curve::Bn254 => "bn254",
+            Curve::Bls12_381 => "bls12-381",
+            Curve::Pallas => "pallas",
+        }
+    }
+
+    /// The name circom's `-p` flag uses for this curve's scalar field
+    ///
+    /// circom names a prime after the curve whose base field it is, so Pallas's
+    /// scalar field is its `vesta`; `-p pallas` is Pallas's base field.
+    pub fn circom_prime(self) -> &'static str {
+        match self {
+            Curve::Bn254 => "bn128",
+            Curve::Bls12_381 => "bls12381",
+            Curve::Pallas => "vesta",
+        }
+    }
+
+    /// Whether the proving pipeline is built for this curve
+    pub fn is_compiled_in(self) -> bool {
+        matches!(self, Curve::Bn254)
+    }
+
+    /// The order of the curve's scalar field, big-endian
+    ///
+    /// circom calls this the prime (`-p bn128`, `-p bls12381`, `-p vesta`); the
+    /// `.r1cs` must have been compiled for it.
+    pub fn scalar_modulus(self) -> Vec<u8> {
+        match self {
+            Curve::Bn254 => ark_bn254::Fr::MODULUS.to_bytes_be(),
+            Curve::Bls12_381 => {
+                hex::decode("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001")
+                    .expect("valid hex")
+            }
+            Curve::Pallas => {
+                hex::decode("40000000000000000000000000000000224698fc0994a8dd8c46eb2100000001")
+                    .expect("valid hex")
+            }
+        }
+    }
+
+    /// Checks that an `.r1cs` was compiled over this curve's scalar field and that
+    /// the pipeline supports the curve
+    pub fn check_r1cs(self, path: impl AsRef<Path>) -> Result<(), CurveError> {
+        self.check_prime(&read_r1cs_prime(path)?)
+    }
+
+    /// Checks a big-endian prime against this curve's scalar field
+    pub fn check_prime(self, prime: &[u8]) -> Result<(), CurveError> {
+        let expected = self.scalar_modulus();
+        if prime != expected {
+            return Err(CurveError::FieldMismatch {
+                curve: self,
+                found: Curve::ALL
+                    .into_iter()
+                    .find(|curve| curve.scalar_modulus() == prime),
+                prime: format!("0x{}", hex::encode(prime)),
+                expected: format!("0x{}", hex::encode(expected)),
+            });
+        }
+        if !self.is_compiled_in() {
+            return Err(CurveError::NotCompiledIn(self));
+        }
+        Ok(())
+    }
+}
+
+/// Reads the prime of an `.r1cs` file, big-endian with leading zeros stripped
+///
+/// Like `.wtns`, the file is an iden3 container; the header (section 1) starts with
+/// the field element width followed by the little-endian prime.
+pub fn read_r1cs_prime(path: impl AsRef<Path>) -> Result<Vec<u8>, CurveError> {
+    r1cs_prime(std::io::BufReader::new(std::fs::File::open(path)?))
+}
+
+pub fn r1cs_prime(mut file: impl Read) -> Result<Vec<u8>, CurveError> {
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic)?;
+    if &magic != b"r1cs" {
+        return Err(CurveError::NotR1cs);
+    }
+    let _version = read_u32(&mut file)?;
+    let num_sections = read_u32(&mut file)?;
+    for _ in 0..num_sections {
+        let section_type = read_u32(&mut file)?;
+        let size = read_u64(&mut file)?;
+        if section_type == 1 {
+            let field_size = read_u32(&mut file)? as usize;
+            let mut prime = vec![0u8; field_size];
+            file.read_exact(&mut prime)?;
+            prime.reverse();
+            let leading_zeros = prime.iter().take_while(|&&byte| byte == 0).count();
+            return Ok(prime.split_off(leading_zeros));
+        }
+        std::io::copy(&mut (&mut file).take(size), &mut std::io::sink())?;
+    }
+    Err(CurveError::NotR1cs)
+}
+
+fn read_u32(reader: &mut impl Read) -> std::io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(reader: &mut impl Read) -> std::io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+impl fmt::Display for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Curve {
+    type Err = CurveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Curve::ALL
+            .into_iter()
+            .find(|curve| curve.name() == s)
+            .ok_or_else(|| CurveError::UnknownCurve(s.to_string()))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CurveError {
+    #[error("Unknown curve '{0}', expected bn254, bls12-381 or pallas")]
+    UnknownCurve(String),
+    #[error("Curve {0} is not compiled into this build; only bn254 is supported")]
+    NotCompiledIn(Curve),
+    #[error(
+        "The r1cs was compiled for prime {prime}{}, but {curve} has scalar field {expected}; \
+         recompile with `circom -p {}` or pass the matching --curve",
+        found.map_or_else(String::new, |found| format!(" ({found})")),
+        curve.circom_prime()
+    )]
+    FieldMismatch {
+        curve: Curve,
+        /// The curve whose scalar field the r1cs prime is, if any known one
+        found: Option<Curve>,
+        prime: String,
+        expected: String,
+    },
+    #[error("Failed to read r1cs: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not an .r1cs file")]
+    NotR1cs,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r1cs_bytes(prime_le: &[u8]) -> Vec<u8> {
+        let mut header = (prime_le.len() as u32).to_le_bytes().to_vec();
+        header.extend(prime_le);
+        let mut bytes = b"r1cs".to_vec();
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend(2u32.to_le_bytes());
+        // An unrelated section before the header must be skipped
+        for (section_type, section) in [(2u32, vec![0u8; 3]), (1u32, header)] {
+            bytes.extend(section_type.to_le_bytes());
+            bytes.extend((section.len() as u64).to_le_bytes());
+            bytes.extend(section);
+        }
+        bytes
+    }
+
+    #[test]
+    fn reads_header_prime() {
+        let bytes = r1cs_bytes(&ark_bn254::Fr::MODULUS.to_bytes_le());
+        let prime = r1cs_prime(bytes.as_slice()).unwrap();
+        Curve::Bn254.check_prime(&prime).unwrap();
+    }
+
+    #[test]
+    fn mismatch_names_both_moduli() {
+        let prime = Curve::Bls12_381.scalar_modulus();
+        let message = Curve::Bn254.check_prime(&prime).unwrap_err().to_string();
+        assert!(message.contains(&hex::encode(&prime)), "{message}");
+        assert!(message.contains("(bls12-381)"), "{message}");
+        assert!(
+            message.contains(&hex::encode(Curve::Bn254.scalar_modulus())),
+            "{message}"
+        );
+        assert!(message.contains("circom -p bn128"), "{message}");
+    }
+
+    #[test]
+    fn names_the_field_of_mismatched_r1cs() {
+        let prime = Curve::Pallas.scalar_modulus();
+        let Err(CurveError::FieldMismatch { found, .. }) = Curve::Bn254.check_prime(&prime) else {
+            panic!("expected a field mismatch");
+        };
+        assert_eq!(found, Some(Curve::Pallas));
+        assert!(matches!(
+            Curve::Pallas.check_prime(&prime),
+            Err(CurveError::NotCompiledIn(Curve::Pallas))
+        ));
+    }
+
+    #[test]
+    fn circom_pallas_prime_is_no_pallas_scalar_field() {
+        // What `circom -p pallas` writes: Pallas's base field, Vesta's scalar field
+        let mut pallas_base =
+            hex::decode("40000000000000000000000000000000224698fc094cf91b992d30ed00000001")
+                .unwrap();
+        pallas_base.reverse();
+        let prime = r1cs_prime(r1cs_bytes(&pallas_base).as_slice()).unwrap();
+        let error = Curve::Pallas.check_prime(&prime).unwrap_err();
+        assert!(matches!(
+            error,
+            CurveError::FieldMismatch { found: None, .. }
+        ));
+        assert!(error.to_string().contains("circom -p vesta"), "{error}");
+    }
+}
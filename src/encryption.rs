@@ -0,0 +1,148 @@
+use std::{fmt, path::Path};
+
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, KeyInit, Payload},
+};
+use rand::{CryptoRng, RngCore};
+
+/// Leading bytes of an encrypted inputs file, also authenticated with the contents
+pub const MAGIC: &[u8; 8] = b"cbpenc01";
+/// Environment variable holding a hex-encoded inputs key
+pub const KEY_ENV: &str = "CIRCOM_BP_INPUTS_KEY";
+
+const NONCE_BYTES: usize = 12;
+
+/// AES-256-GCM key for inputs files
+///
+/// Encrypted files are `MAGIC || nonce || ciphertext`, with a fresh random 96-bit
+/// nonce per file and `MAGIC` as associated data. Provers decrypt in memory, so
+/// sensitive inputs never sit on disk in plaintext.
+#[derive(Clone, PartialEq, Eq)]
+pub struct InputsKey([u8; 32]);
+
+impl InputsKey {
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut key = [0u8; 32];
+        rng.fill_bytes(&mut key);
+        Self(key)
+    }
+
+    pub fn from_hex(hex_key: &str) -> Result<Self, EncryptionError> {
+        let bytes = hex::decode(hex_key.trim()).map_err(|_| EncryptionError::InvalidKey)?;
+        Ok(Self(
+            bytes.try_into().map_err(|_| EncryptionError::InvalidKey)?,
+        ))
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// Reads a key file holding the hex-encoded key
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, EncryptionError> {
+        Self::from_hex(&std::fs::read_to_string(path)?)
+    }
+
+    /// The key in `CIRCOM_BP_INPUTS_KEY`, if set
+    pub fn from_env() -> Result<Option<Self>, EncryptionError> {
+        std::env::var(KEY_ENV)
+            .ok()
+            .map(|hex_key| Self::from_hex(&hex_key))
+            .transpose()
+    }
+
+    pub fn encrypt<R: RngCore + CryptoRng>(&self, plaintext: &[u8], rng: &mut R) -> Vec<u8> {
+        let mut nonce = [0u8; NONCE_BYTES];
+        rng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: MAGIC,
+                },
+            )
+            .expect("AES-GCM encrypts messages of any practical size");
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(nonce);
+        bytes.extend(ciphertext);
+        bytes
+    }
+
+    pub fn decrypt(&self, bytes: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let body = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(EncryptionError::NotEncrypted)?;
+        if body.len() < NONCE_BYTES {
+            return Err(EncryptionError::Decryption);
+        }
+        let (nonce, ciphertext) = body.split_at(NONCE_BYTES);
+        self.cipher()
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: MAGIC,
+                },
+            )
+            .map_err(|_| EncryptionError::Decryption)
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0))
+    }
+}
+
+impl fmt::Debug for InputsKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("InputsKey(..)")
+    }
+}
+
+/// Whether `bytes` are an encrypted inputs file
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptionError {
+    #[error("Failed to read inputs key: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Inputs key must be 32 hex-encoded bytes")]
+    InvalidKey,
+    #[error("Inputs are encrypted; set {KEY_ENV} or pass --inputs-key-file")]
+    MissingKey,
+    #[error("Not an encrypted inputs file")]
+    NotEncrypted,
+    #[error("Failed to decrypt inputs: wrong key or corrupted file")]
+    Decryption,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn encrypted_inputs_round_trip() {
+        let key = InputsKey::generate(&mut OsRng);
+        let sealed = key.encrypt(br#"{"a": 3}"#, &mut OsRng);
+        assert!(is_encrypted(&sealed));
+        assert_eq!(key.decrypt(&sealed).unwrap(), br#"{"a": 3}"#);
+        assert_eq!(InputsKey::from_hex(&key.to_hex()).unwrap(), key);
+
+        let other = InputsKey::generate(&mut OsRng);
+        assert!(matches!(
+            other.decrypt(&sealed),
+            Err(EncryptionError::Decryption)
+        ));
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            key.decrypt(&tampered),
+            Err(EncryptionError::Decryption)
+        ));
+    }
+}
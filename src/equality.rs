@@ -0,0 +1,265 @@
+use ark_bn254::{Fr, G1Projective};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use bulletproofs::circuit::types::{CRS as CircuitCRS, Statement as CircuitStatement, Witness};
+use rand::{CryptoRng, RngCore};
+
+/// Domain separator of the equality proof's Fiat-Shamir challenge
+pub const EQUALITY_LABEL: &str = "circom-bp/commitment-equality/v1";
+
+/// One statement commitment `V = v·G + γ·H` of a proof
+#[derive(Debug, Clone, Copy)]
+pub struct CommittedValue<'a> {
+    pub crs: &'a CircuitCRS<G1Projective>,
+    /// Size the CRS was derived for, `CrsParams::size`
+    pub crs_size: usize,
+    pub statement: &'a CircuitStatement<G1Projective>,
+    /// Statement position; see `conversion::statement_variables` for converted circuits
+    pub variable: usize,
+}
+
+impl CommittedValue<'_> {
+    fn commitment(&self) -> Result<G1Projective, EqualityError> {
+        self.statement
+            .v
+            .get(self.variable)
+            .copied()
+            .ok_or(EqualityError::NoCommitment(self.variable))
+    }
+
+    /// The generators `(G, H)` of this statement position
+    ///
+    /// Recovered by committing to unit openings through the statement, so they are
+    /// exactly those the prover used, whatever the CRS layout.
+    fn generators(&self) -> Result<(G1Projective, G1Projective), EqualityError> {
+        let commitments = self.statement.v.len();
+        if self.variable >= commitments {
+            return Err(EqualityError::NoCommitment(self.variable));
+        }
+        let unit = |blinding: bool| {
+            let mut v = vec![Fr::zero(); commitments];
+            let mut gamma = vec![Fr::zero(); commitments];
+            if blinding {
+                gamma[self.variable] = Fr::from(1u64);
+            } else {
+                v[self.variable] = Fr::from(1u64);
+            }
+            let witness = Witness {
+                a_l: vec![Fr::zero(); self.crs_size],
+                a_r: vec![Fr::zero(); self.crs_size],
+                a_o: vec![Fr::zero(); self.crs_size],
+                v,
+                gamma,
+            };
+            CircuitStatement::new(self.crs, &witness).v[self.variable]
+        };
+        Ok((unit(false), unit(true)))
+    }
+}
+
+/// Proof that two statement commitments, possibly of different proofs, hide the
+/// same value
+///
+/// A sigma protocol for knowledge of `v, γ_a, γ_b` with `V_a = v·G_a + γ_a·H_a`
+/// and `V_b = v·G_b + γ_b·H_b`, made non-interactive with Fiat-Shamir. It reveals
+/// nothing about `v` and takes two points and three scalars.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EqualityProof {
+    t_a: G1Projective,
+    t_b: G1Projective,
+    z: Fr,
+    z_a: Fr,
+    z_b: Fr,
+}
+
+impl EqualityProof {
+    /// Proves that `a` and `b` commit to the same value, opened by the witnesses
+    /// their proofs were made from
+    pub fn prove<R: RngCore + CryptoRng>(
+        a: &CommittedValue,
+        witness_a: &Witness<Fr>,
+        b: &CommittedValue,
+        witness_b: &Witness<Fr>,
+        rng: &mut R,
+    ) -> Result<Self, EqualityError> {
+        let (value, gamma_a) = opening(witness_a, a.variable)?;
+        let (value_b, gamma_b) = opening(witness_b, b.variable)?;
+        if value != value_b {
+            return Err(EqualityError::ValuesDiffer);
+        }
+        let (g_a, h_a) = a.generators()?;
+        let (g_b, h_b) = b.generators()?;
+
+        let (r, s_a, s_b) = (Fr::rand(rng), Fr::rand(rng), Fr::rand(rng));
+        let t_a = g_a * r + h_a * s_a;
+        let t_b = g_b * r + h_b * s_b;
+        let c = challenge(&[
+            a.commitment()?,
+            b.commitment()?,
+            g_a,
+            h_a,
+            g_b,
+            h_b,
+            t_a,
+            t_b,
+        ])?;
+        Ok(Self {
+            t_a,
+            t_b,
+            z: r + c * value,
+            z_a: s_a + c * gamma_a,
+            z_b: s_b + c * gamma_b,
+        })
+    }
+
+    /// Checks that `a` and `b` commit to the same value
+    pub fn verify(&self, a: &CommittedValue, b: &CommittedValue) -> Result<(), EqualityError> {
+        let (v_a, v_b) = (a.commitment()?, b.commitment()?);
+        let (g_a, h_a) = a.generators()?;
+        let (g_b, h_b) = b.generators()?;
+        let c = challenge(&[v_a, v_b, g_a, h_a, g_b, h_b, self.t_a, self.t_b])?;
+        if g_a * self.z + h_a * self.z_a != self.t_a + v_a * c
+            || g_b * self.z + h_b * self.z_b != self.t_b + v_b * c
+        {
+            return Err(EqualityError::Invalid);
+        }
+        Ok(())
+    }
+
+    /// Compressed encoding: both points, then the three scalars
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        let mut bytes = Vec::new();
+        self.t_a.serialize_compressed(&mut bytes)?;
+        self.t_b.serialize_compressed(&mut bytes)?;
+        self.z.serialize_compressed(&mut bytes)?;
+        self.z_a.serialize_compressed(&mut bytes)?;
+        self.z_b.serialize_compressed(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, SerializationError> {
+        let proof = Self {
+            t_a: G1Projective::deserialize_compressed(&mut bytes)?,
+            t_b: G1Projective::deserialize_compressed(&mut bytes)?,
+            z: Fr::deserialize_compressed(&mut bytes)?,
+            z_a: Fr::deserialize_compressed(&mut bytes)?,
+            z_b: Fr::deserialize_compressed(&mut bytes)?,
+        };
+        if !bytes.is_empty() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(proof)
+    }
+}
+
+fn opening(witness: &Witness<Fr>, variable: usize) -> Result<(Fr, Fr), EqualityError> {
+    match (witness.v.get(variable), witness.gamma.get(variable)) {
+        (Some(&value), Some(&blinding)) => Ok((value, blinding)),
+        _ => Err(EqualityError::NoCommitment(variable)),
+    }
+}
+
+/// Hashes the label and the points into a scalar
+fn challenge(points: &[G1Projective]) -> Result<Fr, SerializationError> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(EQUALITY_LABEL.as_bytes());
+    let mut bytes = Vec::new();
+    for point in points {
+        bytes.clear();
+        point.serialize_compressed(&mut bytes)?;
+        hasher.update(&bytes);
+    }
+    let mut wide = [0u8; 64];
+    hasher.finalize_xof().fill(&mut wide);
+    Ok(Fr::from_le_bytes_mod_order(&wide))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EqualityError {
+    #[error("Statement has no commitment at position {0}")]
+    NoCommitment(usize),
+    #[error("The committed values differ")]
+    ValuesDiffer,
+    #[error("Equality proof does not verify")]
+    Invalid,
+    #[error("Failed to encode points: {0}")]
+    Serialization(#[from] SerializationError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder::CircuitBuilder, crs::CrsParams};
+
+    /// Witnesses committing to `x` at position 0 and to `y` at position 1, under fresh
+    /// blindings, over two gates
+    fn witnesses(x: u64, y: u64) -> (Witness<Fr>, Witness<Fr>) {
+        let mut rng = rand::rngs::OsRng;
+        let mut first = CircuitBuilder::new();
+        first.commit_value(Fr::from(x), Fr::rand(&mut rng));
+        first.add_mul_gate(Fr::from(1u64), Fr::from(1u64));
+        first.add_mul_gate(Fr::from(1u64), Fr::from(1u64));
+        let mut second = CircuitBuilder::new();
+        second.commit_value(Fr::from(5u64), Fr::rand(&mut rng));
+        second.commit_value(Fr::from(y), Fr::rand(&mut rng));
+        second.add_mul_gate(Fr::from(1u64), Fr::from(1u64));
+        second.add_mul_gate(Fr::from(1u64), Fr::from(1u64));
+        (first.build().1, second.build().1)
+    }
+
+    #[test]
+    fn proves_equal_commitments_across_statements() {
+        let mut rng = rand::rngs::OsRng;
+        let crs_params = CrsParams::new(2);
+        let crs = crs_params.derive();
+        let (witness_a, witness_b) = witnesses(42, 42);
+        let statement_a = CircuitStatement::new(&crs, &witness_a);
+        let statement_b = CircuitStatement::new(&crs, &witness_b);
+        let a = CommittedValue {
+            crs: &crs,
+            crs_size: crs_params.size,
+            statement: &statement_a,
+            variable: 0,
+        };
+        let b = CommittedValue {
+            statement: &statement_b,
+            variable: 1,
+            ..a
+        };
+
+        let proof = EqualityProof::prove(&a, &witness_a, &b, &witness_b, &mut rng).unwrap();
+        let proof = EqualityProof::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+        proof.verify(&a, &b).unwrap();
+
+        // The proof is bound to the commitments it was made for
+        let other = CommittedValue { variable: 0, ..b };
+        assert!(matches!(
+            proof.verify(&a, &other),
+            Err(EqualityError::Invalid)
+        ));
+    }
+
+    #[test]
+    fn different_values_cannot_be_proved_equal() {
+        let crs_params = CrsParams::new(2);
+        let crs = crs_params.derive();
+        let (witness_a, witness_b) = witnesses(42, 43);
+        let statement_a = CircuitStatement::new(&crs, &witness_a);
+        let statement_b = CircuitStatement::new(&crs, &witness_b);
+        let a = CommittedValue {
+            crs: &crs,
+            crs_size: crs_params.size,
+            statement: &statement_a,
+            variable: 0,
+        };
+        let b = CommittedValue {
+            statement: &statement_b,
+            variable: 1,
+            ..a
+        };
+        assert!(matches!(
+            EqualityProof::prove(&a, &witness_a, &b, &witness_b, &mut rand::rngs::OsRng),
+            Err(EqualityError::ValuesDiffer)
+        ));
+    }
+}
@@ -0,0 +1,94 @@
+use ark_circom::circom::R1CS;
+use ark_ff::PrimeField;
+use serde::Serialize;
+
+use crate::r1cs;
+
+/// Bytes of a compressed BN254 G1 point or a scalar
+const COMPRESSED_BYTES: usize = 32;
+/// In-memory size of a projective G1 point (three base field coordinates)
+const PROJECTIVE_BYTES: usize = 96;
+/// In-memory size of a scalar field element
+const SCALAR_BYTES: usize = 32;
+
+/// Points of a circuit proof besides the inner-product argument:
+/// A_I, A_O, S and the five T commitments
+const PROOF_POINTS: usize = 8;
+/// Scalars of a circuit proof: τ_x, μ, t̂ and the two final inner-product scalars
+const PROOF_SCALARS: usize = 5;
+
+/// Rough resource needs of proving a circuit, derived from its shape alone
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResourceEstimate {
+    pub constraints: usize,
+    pub variables: usize,
+    /// Statement commitments, one per public signal; the constant wire is not
+    /// committed
+    pub commitments: usize,
+    /// Multiplication gates, one per multiplicative constraint and per two
+    /// private variables, padded to a power of 2
+    pub gates: usize,
+    /// Serialized CRS: two generator vectors of the gate count plus three extra generators
+    pub crs_bytes: usize,
+    /// Proof size; the inner-product argument adds two points per halving round
+    pub proof_bytes: usize,
+    /// Dense weight matrices, CRS and witness vectors held during proving
+    pub memory_bytes: usize,
+}
+
+/// Scalar multiplications in the verifier's final multi-scalar multiplication
+///
+/// One per generator of both vectors, per statement commitment and per proof point,
+/// plus the two base generators; the count operators track across versions.
+pub fn verification_group_ops(gates: usize, commitments: usize) -> usize {
+    let rounds = gates.trailing_zeros() as usize;
+    2 * gates + commitments + PROOF_POINTS + 2 * rounds + 2
+}
+
+impl ResourceEstimate {
+    pub fn from_r1cs<F: PrimeField>(r1cs: &R1CS<F>) -> Self {
+        let linear = r1cs
+            .constraints
+            .iter()
+            .filter(|(a, b, _)| r1cs::is_linear(a, b))
+            .count();
+        Self::from_dimensions(
+            r1cs.constraints.len(),
+            linear,
+            r1cs.num_variables,
+            r1cs.num_inputs.min(r1cs.num_variables).saturating_sub(1),
+        )
+    }
+
+    /// Estimate for `constraints` R1CS constraints of which `linear` have an empty
+    /// A or B side and take no gate
+    pub fn from_dimensions(
+        constraints: usize,
+        linear: usize,
+        variables: usize,
+        commitments: usize,
+    ) -> Self {
+        let multiplicative = constraints - linear;
+        // Every variable but the constant wire and the commitments is a gate wire
+        let private = variables.saturating_sub(commitments + 1);
+        let n = (multiplicative + private.div_ceil(2)).next_power_of_two();
+        let m = commitments;
+        let crs_points = 2 * n + 3;
+        let rounds = n.trailing_zeros() as usize;
+
+        // Three rows per multiplicative and one per linear constraint, padded to a
+        // power of 2, over the gate wires and commitments
+        let rows = (3 * multiplicative + linear).next_power_of_two();
+        let matrices = rows * (3 * n + m) * SCALAR_BYTES;
+        let witness = (3 * n + 2 * m) * SCALAR_BYTES;
+        Self {
+            constraints,
+            variables,
+            commitments,
+            gates: n,
+            crs_bytes: crs_points * COMPRESSED_BYTES,
+            proof_bytes: (PROOF_POINTS + 2 * rounds + PROOF_SCALARS) * COMPRESSED_BYTES,
+            memory_bytes: matrices + crs_points * PROJECTIVE_BYTES + witness,
+        }
+    }
+}
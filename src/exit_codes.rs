@@ -0,0 +1,21 @@
+pub const FAILURE: u8 = 1;
+pub const INPUT: u8 = 3;
+pub const WITNESS: u8 = 4;
+pub const UNSATISFIED: u8 = 5;
+pub const PROVING: u8 = 6;
+pub const VERIFICATION: u8 = 7;
+/// A `--max-memory` or `--max-duration` limit was hit
+pub const RESOURCE_LIMIT: u8 = 8;
+
+/// Maps a failure category (see `PipelineError::category`) to its exit code
+pub fn for_category(category: &str) -> u8 {
+    match category {
+        "input" | "load" => INPUT,
+        "witness" => WITNESS,
+        "unsatisfied" => UNSATISFIED,
+        "proving" => PROVING,
+        "verification" => VERIFICATION,
+        "resource" => RESOURCE_LIMIT,
+        _ => FAILURE,
+    }
+}
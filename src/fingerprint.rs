@@ -0,0 +1,105 @@
+use std::{fmt, path::Path, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+
+/// Hash function used to fingerprint artifacts (r1cs files, inputs, CRS parameters)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    #[default]
+    Blake3,
+    /// For deployments whose compliance rules only admit FIPS-approved hashes
+    Sha256,
+}
+
+impl HashAlgorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::Sha256 => "sha256",
+        }
+    }
+
+    pub fn digest(self, data: &[u8]) -> Fingerprint {
+        let digest = match self {
+            HashAlgorithm::Blake3 => blake3::hash(data).as_bytes().to_vec(),
+            HashAlgorithm::Sha256 => sha2::Sha256::digest(data).to_vec(),
+        };
+        Fingerprint {
+            algorithm: self,
+            digest,
+        }
+    }
+
+    pub fn digest_file(self, path: impl AsRef<Path>) -> std::io::Result<Fingerprint> {
+        Ok(self.digest(&std::fs::read(path)?))
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = FingerprintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blake3" => Ok(HashAlgorithm::Blake3),
+            "sha256" => Ok(HashAlgorithm::Sha256),
+            other => Err(FingerprintError::UnknownAlgorithm(other.to_string())),
+        }
+    }
+}
+
+/// A digest tagged with the algorithm that produced it, written as `<algorithm>:<hex>`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Fingerprint {
+    pub algorithm: HashAlgorithm,
+    pub digest: Vec<u8>,
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm, hex::encode(&self.digest))
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = FingerprintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (algorithm, digest) = s
+            .split_once(':')
+            .ok_or_else(|| FingerprintError::Malformed(s.to_string()))?;
+        Ok(Fingerprint {
+            algorithm: algorithm.parse()?,
+            digest: hex::decode(digest).map_err(|_| FingerprintError::Malformed(s.to_string()))?,
+        })
+    }
+}
+
+impl TryFrom<String> for Fingerprint {
+    type Error = FingerprintError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Fingerprint> for String {
+    fn from(fingerprint: Fingerprint) -> Self {
+        fingerprint.to_string()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FingerprintError {
+    #[error("Unknown hash algorithm '{0}', expected blake3 or sha256")]
+    UnknownAlgorithm(String),
+    #[error("Malformed fingerprint '{0}', expected <algorithm>:<hex digest>")]
+    Malformed(String),
+}
@@ -0,0 +1,1090 @@
+use std::{collections::BTreeMap, fmt, io::Read, path::Path, str::FromStr};
+
+use ark_bn254::Fr;
+use ark_ff::PrimeField;
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use num_bigint::BigUint;
+use serde_json::{Map, Value};
+use tracing::warn;
+
+use crate::{
+    encryption::{self, EncryptionError, InputsKey},
+    template::InputSignal,
+};
+
+/// Reads a circuit inputs file: a JSON object mapping signal names to values or
+/// (nested) arrays of values.
+///
+/// A value is a JSON integer of any size, a string holding a decimal or
+/// `0x`-prefixed hex number, or a boolean, which stands for 1 or 0. Values are
+/// reduced into the scalar field, a leading `-` negating modulo its order; with
+/// `strict`, values at or above the modulus are rejected instead. Byte strings are
+/// written as `{"bytes": "<base64>"}` and packed into field elements, see
+/// `pack_bytes`.
+pub fn load_inputs(path: impl AsRef<Path>, strict: bool) -> Result<Vec<(String, Fr)>, InputError> {
+    parse_inputs(&read_inputs_json(path)?, strict)
+}
+
+/// Reads the raw contents of an inputs file, or of standard input when `path` is
+/// `-`, so secret inputs can be piped in without touching the disk
+pub fn read_inputs_json(path: impl AsRef<Path>) -> std::io::Result<String> {
+    let path = path.as_ref();
+    if path == Path::new("-") {
+        let mut inputs_json = String::new();
+        std::io::stdin().read_to_string(&mut inputs_json)?;
+        return Ok(inputs_json);
+    }
+    std::fs::read_to_string(path)
+}
+
+/// Like `read_inputs_json`, decrypting files written by `encrypt-inputs` with `key`
+///
+/// Plaintext files are read as they are, so a key can be configured once for a
+/// mix of encrypted and plain inputs.
+pub fn read_inputs_decrypted(
+    path: impl AsRef<Path>,
+    key: Option<&InputsKey>,
+) -> Result<String, InputError> {
+    let path = path.as_ref();
+    let mut bytes = Vec::new();
+    if path == Path::new("-") {
+        std::io::stdin().read_to_end(&mut bytes)?;
+    } else {
+        bytes = std::fs::read(path)?;
+    }
+    if encryption::is_encrypted(&bytes) {
+        bytes = key.ok_or(EncryptionError::MissingKey)?.decrypt(&bytes)?;
+    }
+    String::from_utf8(bytes)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e).into())
+}
+
+/// Whether the inputs at `path` must stay off the disk: they are piped in on
+/// standard input, or come from a file written by `encrypt-inputs`
+///
+/// Caches holding anything derived from such inputs, like witnesses, are skipped.
+pub fn is_private_source(path: impl AsRef<Path>) -> bool {
+    let path = path.as_ref();
+    if path == Path::new("-") {
+        return true;
+    }
+    let mut header = Vec::new();
+    std::fs::File::open(path)
+        .and_then(|file| {
+            file.take(encryption::MAGIC.len() as u64)
+                .read_to_end(&mut header)
+        })
+        .is_ok_and(|_| encryption::is_encrypted(&header))
+}
+
+/// Splits a CSV of inputs into one inputs JSON object per row
+///
+/// The header names the signals. A cell is read like a `--set` value: as JSON,
+/// falling back to a string, so array signals are written as quoted JSON arrays.
+/// Empty cells are left out, so `--defaults` can fill them.
+pub fn csv_inputs(csv: &str) -> Result<Vec<String>, InputError> {
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let header = reader.headers()?.clone();
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let row: Map<String, Value> = header
+            .iter()
+            .zip(record.iter())
+            .filter(|(_, cell)| !cell.trim().is_empty())
+            .map(|(signal, cell)| (signal.trim().to_string(), parse_cell(cell.trim())))
+            .collect();
+        rows.push(serde_json::to_string(&row)?);
+    }
+    Ok(rows)
+}
+
+/// Reads a command-line or CSV value as JSON, or as a string if it is not valid JSON
+fn parse_cell(value: &str) -> Value {
+    serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))
+}
+
+/// Parses the contents of a circuit inputs file.
+///
+/// Array signals are flattened in row-major order into repeated entries for the same
+/// name, which is how the witness calculator expects them. Nested arrays may be of
+/// any depth but must be rectangular, so the order is unambiguous. Objects, as used for bus
+/// signals, are flattened into `name.field` entries, and arrays of them into
+/// `name[i].field`, following circom's witness calculator. Every signal is checked
+/// before failing, so all problems are reported at once.
+pub fn parse_inputs(inputs_json: &str, strict: bool) -> Result<Vec<(String, Fr)>, InputError> {
+    parse_layered_inputs(inputs_json, None, &[], strict)
+}
+
+/// Like `parse_inputs`, taking every signal missing from `inputs_json` from
+/// `defaults_json` when given, then applying `overrides` in order
+///
+/// Defaults are matched by top-level signal name, so a default array or bus is
+/// used whole or not at all. Overrides may replace a single element or field.
+pub fn parse_layered_inputs(
+    inputs_json: &str,
+    defaults_json: Option<&str>,
+    overrides: &[InputOverride],
+    strict: bool,
+) -> Result<Vec<(String, Fr)>, InputError> {
+    flatten_inputs(
+        &merge_inputs(inputs_json, defaults_json, overrides)?,
+        strict,
+    )
+}
+
+/// The inputs JSON object `parse_layered_inputs` flattens, before any value is parsed
+pub fn merge_inputs(
+    inputs_json: &str,
+    defaults_json: Option<&str>,
+    overrides: &[InputOverride],
+) -> Result<Map<String, Value>, InputError> {
+    let mut inputs: Map<String, Value> = serde_json::from_str(inputs_json)?;
+    if let Some(defaults_json) = defaults_json {
+        let defaults: Map<String, Value> = serde_json::from_str(defaults_json)?;
+        for (key, value) in defaults {
+            inputs.entry(key).or_insert(value);
+        }
+    }
+    let issues: Vec<InputIssue> = overrides
+        .iter()
+        .filter_map(|input| {
+            input.apply(&mut inputs).err().map(|reason| InputIssue {
+                signal: input.key.clone(),
+                reason,
+            })
+        })
+        .collect();
+    if !issues.is_empty() {
+        return Err(InputError::Invalid(issues));
+    }
+    Ok(inputs)
+}
+
+/// Flattens and parses a merged inputs object, see `parse_inputs`
+///
+/// Signals and bus fields come out sorted by name, whatever order the JSON map
+/// keeps, so the witness cache key and error reports are the same across builds.
+pub fn flatten_inputs(
+    inputs: &Map<String, Value>,
+    strict: bool,
+) -> Result<Vec<(String, Fr)>, InputError> {
+    let mut parsed = Vec::with_capacity(inputs.len());
+    let mut issues = Vec::new();
+    for (key, value) in sorted(inputs) {
+        let mut values = Vec::new();
+        match flatten_signal(key, value, strict, &mut values) {
+            Ok(()) => parsed.extend(values),
+            Err(reason) => issues.push(InputIssue {
+                signal: key.clone(),
+                reason,
+            }),
+        }
+    }
+
+    if !issues.is_empty() {
+        return Err(InputError::Invalid(issues));
+    }
+    Ok(parsed)
+}
+
+/// Parsed inputs grouped by signal name, each with its values in row-major order
+pub type InputMap = BTreeMap<String, Vec<Fr>>;
+
+/// Groups parsed `(name, value)` entries by signal
+pub fn group_inputs(inputs: &[(String, Fr)]) -> InputMap {
+    let mut map = InputMap::new();
+    for (name, value) in inputs {
+        map.entry(name.clone()).or_default().push(*value);
+    }
+    map
+}
+
+/// Flattens grouped inputs back into `(name, value)` entries, in name order
+pub fn ungroup_inputs(map: InputMap) -> Vec<(String, Fr)> {
+    map.into_iter()
+        .flat_map(|(name, values)| values.into_iter().map(move |value| (name.clone(), value)))
+        .collect()
+}
+
+/// Writes parsed inputs back as an inputs JSON object for other calculators
+///
+/// Values become decimal strings and repeated names flat arrays, which circom's
+/// calculators read in row-major order like nested ones.
+pub fn inputs_json(inputs: &[(String, Fr)]) -> String {
+    let mut object = Map::new();
+    for (name, value) in inputs {
+        let value = Value::String(BigUint::from(value.into_bigint()).to_string());
+        match object.get_mut(name) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(first) => *first = Value::Array(vec![first.take(), value]),
+            None => {
+                object.insert(name.clone(), value);
+            }
+        }
+    }
+    Value::Object(object).to_string()
+}
+
+/// Bytes packed into one field element unless an input declares its `width`; the
+/// most that always stays below the modulus
+pub const DEFAULT_PACKING_WIDTH: usize = 31;
+
+/// Packs a byte string into field elements of `width` bytes each
+///
+/// Every chunk is read as a big-endian number; the last one may be shorter. This
+/// is how hash-preimage circuits usually take their message, one element per chunk.
+pub fn pack_bytes(bytes: &[u8], width: usize) -> Vec<Fr> {
+    bytes
+        .chunks(width.max(1))
+        .map(Fr::from_be_bytes_mod_order)
+        .collect()
+}
+
+/// Reads `{"bytes": "<base64>", "width": n}`, returning `None` for other objects
+fn parse_byte_string(fields: &Map<String, Value>) -> Option<Result<Vec<Fr>, String>> {
+    let encoded = fields.get("bytes")?.as_str()?;
+    if fields.keys().any(|key| key != "bytes" && key != "width") {
+        return None;
+    }
+    let max_width = (Fr::MODULUS_BIT_SIZE as usize - 1) / 8;
+    let width = match fields.get("width") {
+        None => DEFAULT_PACKING_WIDTH,
+        Some(width) => match width.as_u64() {
+            Some(width @ 1..) if width as usize <= max_width => width as usize,
+            _ => {
+                return Some(Err(format!(
+                    "packing width must be between 1 and {max_width} bytes, got {width}"
+                )));
+            }
+        },
+    };
+    Some(
+        BASE64
+            .decode(encoded)
+            .map(|bytes| pack_bytes(&bytes, width))
+            .map_err(|e| format!("invalid base64 bytes: {e}")),
+    )
+}
+
+/// Entries of a JSON object by key
+///
+/// serde_json iterates in key order by default but in insertion order once any
+/// crate in the build enables its `preserve_order` feature.
+fn sorted(object: &Map<String, Value>) -> Vec<(&String, &Value)> {
+    let mut entries: Vec<_> = object.iter().collect();
+    entries.sort_unstable_by_key(|&(key, _)| key);
+    entries
+}
+
+/// Flattens one signal into `(name, value)` entries
+fn flatten_signal(
+    name: &str,
+    value: &Value,
+    strict: bool,
+    entries: &mut Vec<(String, Fr)>,
+) -> Result<(), String> {
+    match value {
+        Value::Object(fields) => {
+            if let Some(packed) = parse_byte_string(fields) {
+                entries.extend(packed?.into_iter().map(|value| (name.to_string(), value)));
+                return Ok(());
+            }
+            return sorted(fields).into_iter().try_for_each(|(field, value)| {
+                flatten_signal(&format!("{name}.{field}"), value, strict, entries)
+            });
+        }
+        Value::Array(_) => {
+            let mut leaves = Vec::new();
+            collect_leaves(value, &mut leaves);
+            let objects = leaves.iter().filter(|leaf| leaf.is_object()).count();
+            if objects > 0 && objects == leaves.len() {
+                return flatten_indexed(name, value, strict, entries);
+            }
+            if objects > 0 {
+                return Err("array mixes objects and values".to_string());
+            }
+            tensor_shape(value)?;
+        }
+        _ => {}
+    }
+
+    let mut values = Vec::new();
+    parse_value(name, value, strict, &mut values)?;
+    entries.extend(values.into_iter().map(|value| (name.to_string(), value)));
+    Ok(())
+}
+
+/// Names every object of a (nested) array by its indices, `name[i][j]`
+fn flatten_indexed(
+    name: &str,
+    value: &Value,
+    strict: bool,
+    entries: &mut Vec<(String, Fr)>,
+) -> Result<(), String> {
+    match value {
+        Value::Array(elements) => elements.iter().enumerate().try_for_each(|(i, element)| {
+            flatten_indexed(&format!("{name}[{i}]"), element, strict, entries)
+        }),
+        _ => flatten_signal(name, value, strict, entries),
+    }
+}
+
+/// Shape of a nested array, e.g. `[2, 3]` for `[[1, 2, 3], [4, 5, 6]]`, and empty
+/// for anything else
+///
+/// Fails on ragged arrays, naming the first element whose length differs from the
+/// first one along the same axis.
+pub fn tensor_shape(value: &Value) -> Result<Vec<usize>, String> {
+    shape_at(value, &mut Vec::new())
+}
+
+fn shape_at(value: &Value, index: &mut Vec<usize>) -> Result<Vec<usize>, String> {
+    let Value::Array(elements) = value else {
+        return Ok(Vec::new());
+    };
+    let mut inner: Option<Vec<usize>> = None;
+    for (i, element) in elements.iter().enumerate() {
+        index.push(i);
+        let shape = shape_at(element, index)?;
+        match &inner {
+            Some(expected) if *expected != shape => {
+                let at = format_index(index);
+                let reason = match expected.iter().zip(&shape).position(|(a, b)| a != b) {
+                    Some(k) => format!(
+                        "ragged array: axis {} has length {} at {at}, expected {}",
+                        index.len() + k,
+                        shape[k],
+                        expected[k]
+                    ),
+                    None => format!(
+                        "ragged array: {at} has {} axes, expected {}",
+                        shape.len(),
+                        expected.len()
+                    ),
+                };
+                return Err(reason);
+            }
+            Some(_) => {}
+            None => inner = Some(shape),
+        }
+        index.pop();
+    }
+    let mut shape = vec![elements.len()];
+    shape.extend(inner.unwrap_or_default());
+    Ok(shape)
+}
+
+fn format_index(index: &[usize]) -> String {
+    index.iter().map(|i| format!("[{i}]")).collect()
+}
+
+/// Explains how a nested array `value` differs from the `declared` shape
+///
+/// Flat arrays are taken in row-major order whatever the declared shape, as by
+/// circom's witness calculator, so only their length is checked, by `check_signals`.
+fn shape_mismatch(value: &Value, declared: &[usize]) -> Option<String> {
+    let shape = tensor_shape(value).ok()?;
+    if shape.len() < 2 && declared.len() != shape.len() {
+        return None;
+    }
+    if shape.len() != declared.len() {
+        return Some(format!(
+            "expected shape {declared:?} with {} axes, got {shape:?} with {}",
+            declared.len(),
+            shape.len()
+        ));
+    }
+    let axis = declared.iter().zip(&shape).position(|(a, b)| a != b)?;
+    Some(format!(
+        "expected shape {declared:?}, got {shape:?}: axis {axis} has length {}, expected {}",
+        shape[axis], declared[axis]
+    ))
+}
+
+fn collect_leaves<'a>(value: &'a Value, leaves: &mut Vec<&'a Value>) {
+    match value {
+        Value::Array(elements) => elements
+            .iter()
+            .for_each(|element| collect_leaves(element, leaves)),
+        leaf => leaves.push(leaf),
+    }
+}
+
+fn parse_value(
+    name: &str,
+    value: &Value,
+    strict: bool,
+    values: &mut Vec<Fr>,
+) -> Result<(), String> {
+    match value {
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                values.push(signed_to_field(i < 0, &BigUint::from(i.unsigned_abs())));
+            } else if let Some(u) = n.as_u64() {
+                values.push(Fr::from(u));
+            } else {
+                // Numbers keep their digits (serde_json's `arbitrary_precision`), so
+                // integers beyond 64 bits arrive intact; fractions and exponents do not parse
+                let digits = n.to_string();
+                if !digits.bytes().all(|b| b.is_ascii_digit() || b == b'-') {
+                    return Err(format!("invalid number format '{n}', expected an integer"));
+                }
+                values.push(parse_field_element(name, &digits, strict)?);
+            }
+            Ok(())
+        }
+        Value::String(s) => {
+            values.push(parse_field_element(name, s, strict)?);
+            Ok(())
+        }
+        Value::Bool(b) => {
+            values.push(Fr::from(*b));
+            Ok(())
+        }
+        Value::Array(elements) => elements
+            .iter()
+            .try_for_each(|element| parse_value(name, element, strict, values)),
+        _ => Err(format!(
+            "must be a number, numeric string or boolean, got {}",
+            json_type(value)
+        )),
+    }
+}
+
+/// Parses a decimal or `0x`-prefixed hex string, optionally negated, into a field element
+///
+/// Magnitudes at or above the modulus are rejected when `strict` and otherwise
+/// reduced with a warning naming the signal, never wrapped silently.
+fn parse_field_element(name: &str, s: &str, strict: bool) -> Result<Fr, String> {
+    let (negative, magnitude) = match s.trim().strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.trim()),
+    };
+    let (radix, digits) = match magnitude
+        .strip_prefix("0x")
+        .or_else(|| magnitude.strip_prefix("0X"))
+    {
+        Some(hex) => (16, hex),
+        None => (10, magnitude),
+    };
+    let value = BigUint::parse_bytes(digits.as_bytes(), radix)
+        .ok_or_else(|| format!("'{s}' is not a decimal or 0x-prefixed hex number"))?;
+    let modulus = BigUint::from(Fr::MODULUS);
+    if value >= modulus {
+        if strict {
+            return Err(format!(
+                "'{s}' is out of range: values must be below the bn254 scalar field modulus {modulus}"
+            ));
+        }
+        let reduced = signed_to_field(negative, &value);
+        warn!(
+            "Input '{name}' value {s} is not below the bn254 scalar field modulus {modulus}, reducing it to {reduced}"
+        );
+        return Ok(reduced);
+    }
+    Ok(signed_to_field(negative, &value))
+}
+
+/// Maps a signed integer into the field the way snarkjs does: `-x` becomes
+/// `p - (x mod p)`, so negative inputs and constants agree with circom tooling
+pub fn signed_to_field(negative: bool, magnitude: &BigUint) -> Fr {
+    let value = Fr::from_be_bytes_mod_order(&magnitude.to_bytes_be());
+    if negative { -value } else { value }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// A `signal=value` assignment layered over the inputs file
+///
+/// The signal may be followed by `[index]` elements and `.field` bus fields, as in
+/// `path[3]=7` or `p.x=1`. The value is read as JSON, falling back to a string, so
+/// `0x1f` works unquoted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputOverride {
+    pub key: String,
+    name: String,
+    path: Vec<Step>,
+    value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    Index(usize),
+    Field(String),
+}
+
+impl InputOverride {
+    /// Sets the value in `inputs`, adding missing signals and bus fields
+    fn apply(&self, inputs: &mut Map<String, Value>) -> Result<(), String> {
+        let mut slot = inputs.entry(self.name.clone()).or_insert(Value::Null);
+        for step in &self.path {
+            if slot.is_null() && matches!(step, Step::Field(_)) {
+                *slot = Value::Object(Map::new());
+            }
+            slot = match (step, slot) {
+                (Step::Index(i), Value::Array(elements)) => {
+                    let len = elements.len();
+                    elements.get_mut(*i).ok_or_else(|| {
+                        format!("index {i} is out of bounds for an array of {len} values")
+                    })?
+                }
+                (Step::Field(field), Value::Object(fields)) => {
+                    fields.entry(field.clone()).or_insert(Value::Null)
+                }
+                (Step::Index(_), Value::Null) => return Err("is not set".to_string()),
+                (Step::Index(i), other) => {
+                    return Err(format!("cannot index {} with [{i}]", json_type(other)));
+                }
+                (Step::Field(field), other) => {
+                    return Err(format!(
+                        "cannot take field .{field} of {}",
+                        json_type(other)
+                    ));
+                }
+            };
+        }
+        *slot = self.value.clone();
+        Ok(())
+    }
+}
+
+impl FromStr for InputOverride {
+    type Err = InvalidOverride;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidOverride(s.to_string());
+        let (key, value) = s.split_once('=').ok_or_else(invalid)?;
+        let end = key.find(['[', '.']).unwrap_or(key.len());
+        let (name, mut rest) = key.split_at(end);
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        let mut path = Vec::new();
+        while !rest.is_empty() {
+            if let Some(index) = rest.strip_prefix('[') {
+                let (index, tail) = index.split_once(']').ok_or_else(invalid)?;
+                path.push(Step::Index(index.parse().map_err(|_| invalid())?));
+                rest = tail;
+            } else {
+                let field = rest.strip_prefix('.').ok_or_else(invalid)?;
+                let end = field.find(['[', '.']).unwrap_or(field.len());
+                if end == 0 {
+                    return Err(invalid());
+                }
+                path.push(Step::Field(field[..end].to_string()));
+                rest = &field[end..];
+            }
+        }
+        Ok(Self {
+            key: key.to_string(),
+            name: name.to_string(),
+            path,
+            value: parse_cell(value),
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid input override '{0}', expected signal=value, e.g. nonce=42 or path[3]=7")]
+pub struct InvalidOverride(pub String);
+
+/// What to do with inputs that name no input signal of the circuit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownInputs {
+    /// Reject them, catching typos; suited to CI
+    #[default]
+    Error,
+    /// Drop them, so one shared inputs file can feed several circuits
+    Ignore,
+}
+
+impl UnknownInputs {
+    pub fn name(self) -> &'static str {
+        match self {
+            UnknownInputs::Error => "error",
+            UnknownInputs::Ignore => "ignore",
+        }
+    }
+}
+
+impl fmt::Display for UnknownInputs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for UnknownInputs {
+    type Err = UnknownInputsPolicy;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(UnknownInputs::Error),
+            "ignore" => Ok(UnknownInputs::Ignore),
+            other => Err(UnknownInputsPolicy(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown policy '{0}' for unknown inputs, expected error or ignore")]
+pub struct UnknownInputsPolicy(pub String);
+
+/// Checks parsed inputs against the input signals of the compiled circuit
+///
+/// Every signal must be given with as many values as its shape holds, and signals
+/// given as nested arrays in `json`, the merged inputs object, must match the
+/// declared shape axis by axis. Names that
+/// are not input signals are rejected or, with `UnknownInputs::Ignore`, removed
+/// from `inputs` and returned. The wasm calculator reports such mistakes as opaque
+/// errors, if at all, so they are caught here with the expected shape.
+pub fn check_signals(
+    inputs: &mut Vec<(String, Fr)>,
+    json: &Map<String, Value>,
+    signals: &[InputSignal],
+    unknown: UnknownInputs,
+) -> Result<Vec<String>, InputError> {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for (name, _) in inputs.iter() {
+        match counts.iter_mut().find(|(seen, _)| seen == name) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((name, 1)),
+        }
+    }
+
+    let mut issues = Vec::new();
+    let mut ignored = Vec::new();
+    for &(name, _) in &counts {
+        if signals.iter().any(|signal| signal.name == name) {
+            continue;
+        }
+        match unknown {
+            UnknownInputs::Error => issues.push(InputIssue {
+                signal: name.to_string(),
+                reason: "not an input signal of the circuit".to_string(),
+            }),
+            UnknownInputs::Ignore => ignored.push(name.to_string()),
+        }
+    }
+    for signal in signals {
+        let given = counts
+            .iter()
+            .find(|(name, _)| *name == signal.name)
+            .map(|&(_, count)| count);
+        let mismatch = json
+            .get(&signal.name)
+            .and_then(|value| shape_mismatch(value, &signal.shape));
+        let reason = match (mismatch, given) {
+            (Some(mismatch), _) => mismatch,
+            (None, None) => format!("missing, expected {}", signal.describe()),
+            (None, Some(count)) if count != signal.value_count() => format!(
+                "expected {} of {} values, got {count}",
+                signal.describe(),
+                signal.value_count()
+            ),
+            (None, Some(_)) => continue,
+        };
+        issues.push(InputIssue {
+            signal: signal.name.clone(),
+            reason,
+        });
+    }
+
+    if !issues.is_empty() {
+        return Err(InputError::Invalid(issues));
+    }
+    inputs.retain(|(name, _)| !ignored.contains(name));
+    Ok(ignored)
+}
+
+/// A problem with a single input signal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputIssue {
+    pub signal: String,
+    pub reason: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InputError {
+    #[error("Failed to read inputs: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse inputs: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to parse inputs CSV: {0}")]
+    Csv(#[from] csv::Error),
+    #[error(transparent)]
+    Encryption(#[from] EncryptionError),
+    #[error("Input hook failed: {0}")]
+    Hook(String),
+    #[error("{}", format_issues(.0))]
+    Invalid(Vec<InputIssue>),
+}
+
+fn format_issues(issues: &[InputIssue]) -> String {
+    let mut message = format!("{} invalid input signal(s):", issues.len());
+    for issue in issues {
+        message.push_str(&format!("\n  - '{}': {}", issue.signal, issue.reason));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(
+            parse_inputs(r#"{"a": 3, "b": -11}"#, false).unwrap(),
+            vec![
+                ("a".to_string(), Fr::from(3u64)),
+                ("b".to_string(), -Fr::from(11u64))
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_field_sized_strings() {
+        let inputs = parse_inputs(
+            r#"{"a": "1234567890123456789012345", "b": "0x1f", "c": "-7", "d": "1e3"}"#,
+            false,
+        );
+        let Err(InputError::Invalid(issues)) = inputs else {
+            panic!("expected invalid inputs");
+        };
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].signal, "d");
+
+        let parsed = parse_inputs(
+            r#"{"a": "1234567890123456789012345", "b": "0x1f", "c": "-7"}"#,
+            false,
+        )
+        .unwrap();
+        let big = Fr::from(1234567890123u64) * Fr::from(10u64.pow(12)) + Fr::from(456789012345u64);
+        assert_eq!(
+            parsed,
+            vec![
+                ("a".to_string(), big),
+                ("b".to_string(), Fr::from(31u64)),
+                ("c".to_string(), -Fr::from(7u64))
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_big_json_numbers() {
+        let parsed = parse_inputs(
+            r#"{"a": 1234567890123456789012345, "b": -1234567890123456789012345}"#,
+            false,
+        )
+        .unwrap();
+        let big = Fr::from(1234567890123u64) * Fr::from(10u64.pow(12)) + Fr::from(456789012345u64);
+        assert_eq!(
+            parsed,
+            vec![("a".to_string(), big), ("b".to_string(), -big)]
+        );
+    }
+
+    #[test]
+    fn reduces_values_beyond_the_field_unless_strict() {
+        let modulus = BigUint::from(Fr::MODULUS);
+        let json = format!(r#"{{"a": {modulus}, "b": "{}"}}"#, &modulus + 5u32);
+        assert_eq!(
+            parse_inputs(&json, false).unwrap(),
+            vec![
+                ("a".to_string(), Fr::from(0u64)),
+                ("b".to_string(), Fr::from(5u64))
+            ]
+        );
+
+        let Err(InputError::Invalid(issues)) = parse_inputs(&json, true) else {
+            panic!("expected invalid inputs");
+        };
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].signal, "a");
+        assert!(issues[0].reason.contains(&modulus.to_string()));
+    }
+
+    #[test]
+    fn negative_values_wrap_like_snarkjs() {
+        let modulus = BigUint::from(Fr::MODULUS);
+        let json = format!(
+            r#"{{"a": -1, "b": "-1", "c": {}, "d": "-{}"}}"#,
+            i64::MIN,
+            &modulus + 1u32
+        );
+        let parsed = parse_inputs(&json, false).unwrap();
+        let canonical: Vec<BigUint> = parsed
+            .iter()
+            .map(|(_, value)| value.into_bigint().into())
+            .collect();
+        let p_minus = |x: u64| &modulus - x;
+        assert_eq!(
+            canonical,
+            [p_minus(1), p_minus(1), p_minus(1 << 63), p_minus(1)]
+        );
+    }
+
+    #[test]
+    fn defaults_fill_missing_signals() {
+        let parsed = parse_layered_inputs(
+            r#"{"a": 3, "in": [1, 2]}"#,
+            Some(r#"{"a": 7, "in": [0, 0, 0], "config": [5, 6]}"#),
+            &[],
+            false,
+        )
+        .unwrap();
+        let expected = [
+            ("a", 3u64),
+            ("config", 5),
+            ("config", 6),
+            ("in", 1),
+            ("in", 2),
+        ]
+        .map(|(name, value)| (name.to_string(), Fr::from(value)));
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn overrides_replace_elements_and_fields() {
+        let overrides = ["nonce=42", "path[1][0]=0x1f", "p.y=9", "path[3]=1"]
+            .map(|s| s.parse::<InputOverride>().unwrap());
+        let parsed = parse_layered_inputs(
+            r#"{"path": [[1, 2], [3, 4]], "p": {"x": 1}}"#,
+            None,
+            &overrides[..3],
+            false,
+        )
+        .unwrap();
+        let expected = [
+            ("nonce", 42u64),
+            ("p.x", 1),
+            ("p.y", 9),
+            ("path", 1),
+            ("path", 2),
+            ("path", 31),
+            ("path", 4),
+        ]
+        .map(|(name, value)| (name.to_string(), Fr::from(value)));
+        assert_eq!(parsed, expected);
+
+        let Err(InputError::Invalid(issues)) =
+            parse_layered_inputs(r#"{"path": [1, 2]}"#, None, &overrides[3..], false)
+        else {
+            panic!("out of bounds override accepted");
+        };
+        assert_eq!(issues[0].signal, "path[3]");
+        assert!("path[x]=1".parse::<InputOverride>().is_err());
+        assert!("nonce".parse::<InputOverride>().is_err());
+    }
+
+    #[test]
+    fn csv_rows_become_inputs() {
+        let rows = csv_inputs("a,in,flag\n3,\"[1, 2]\",true\n0x1f,,false\n").unwrap();
+        assert_eq!(rows.len(), 2);
+        let parsed = parse_inputs(&rows[0], false).unwrap();
+        let expected = [("a", 3u64), ("flag", 1), ("in", 1), ("in", 2)]
+            .map(|(name, value)| (name.to_string(), Fr::from(value)));
+        assert_eq!(parsed, expected);
+        // The empty cell is left to the defaults
+        let parsed = parse_inputs(&rows[1], false).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0], ("a".to_string(), Fr::from(31u64)));
+    }
+
+    #[test]
+    fn inputs_round_trip_through_json() {
+        let parsed =
+            parse_inputs(r#"{"a": 3, "in": [[1, 2], [3, 4]], "p": {"x": -1}}"#, false).unwrap();
+        assert_eq!(parse_inputs(&inputs_json(&parsed), true).unwrap(), parsed);
+    }
+
+    #[test]
+    fn grouped_inputs_round_trip() {
+        let parsed = parse_inputs(r#"{"a": [1, 2], "b": 3}"#, false).unwrap();
+        let mut map = group_inputs(&parsed);
+        assert_eq!(map["a"], [Fr::from(1u64), Fr::from(2u64)]);
+        assert_eq!(ungroup_inputs(map.clone()), parsed);
+
+        map.insert("sum".to_string(), vec![map["a"].iter().sum()]);
+        assert_eq!(
+            ungroup_inputs(map).last(),
+            Some(&("sum".to_string(), Fr::from(3u64)))
+        );
+    }
+
+    #[test]
+    fn signals_are_ordered_by_name() {
+        let parsed =
+            parse_inputs(r#"{"b": 1, "a": [2, 3], "p": {"y": 4, "x": 5}}"#, false).unwrap();
+        let names: Vec<_> = parsed.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["a", "a", "b", "p.x", "p.y"]);
+        assert_eq!(parsed[1].1, Fr::from(3u64));
+    }
+
+    #[test]
+    fn booleans_are_bits() {
+        assert_eq!(
+            parse_inputs(r#"{"sel": [true, false]}"#, false).unwrap(),
+            [1u64, 0].map(|x| ("sel".to_string(), Fr::from(x)))
+        );
+    }
+
+    #[test]
+    fn packs_byte_strings() {
+        // "hello world" in chunks of 4 bytes
+        let parsed = parse_inputs(
+            r#"{"msg": {"bytes": "aGVsbG8gd29ybGQ=", "width": 4}}"#,
+            false,
+        )
+        .unwrap();
+        let chunks: Vec<_> = parsed.iter().map(|(_, value)| *value).collect();
+        assert_eq!(chunks, [0x68656c6cu64, 0x6f20776f, 0x726c64].map(Fr::from));
+        assert_eq!(parsed[0].0, "msg");
+        assert_eq!(pack_bytes(&[7; 40], DEFAULT_PACKING_WIDTH).len(), 2);
+
+        let Err(InputError::Invalid(issues)) = parse_inputs(
+            r#"{"a": {"bytes": "AA==", "width": 32}, "b": {"bytes": "!"}}"#,
+            false,
+        ) else {
+            panic!("expected invalid inputs");
+        };
+        assert_eq!(issues.len(), 2);
+    }
+
+    #[test]
+    fn flattens_arrays() {
+        assert_eq!(
+            parse_inputs(r#"{"in": [[1, 2], [3, 4]]}"#, false).unwrap(),
+            [1u64, 2, 3, 4].map(|x| ("in".to_string(), Fr::from(x)))
+        );
+    }
+
+    #[test]
+    fn flattens_bus_objects() {
+        let parsed = parse_inputs(
+            r#"{"p": {"x": 1, "y": [2, 3]}, "ps": [{"x": 4}, {"x": 5}], "bad": [1, {"x": 2}]}"#,
+            false,
+        );
+        let Err(InputError::Invalid(issues)) = parsed else {
+            panic!("expected invalid inputs");
+        };
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].signal, "bad");
+
+        let parsed = parse_inputs(
+            r#"{"p": {"x": 1, "y": [2, 3]}, "ps": [{"x": 4}, {"x": 5}]}"#,
+            false,
+        )
+        .unwrap();
+        let names: Vec<_> = parsed.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["p.x", "p.y", "p.y", "ps[0].x", "ps[1].x"]);
+        assert_eq!(parsed[4].1, Fr::from(5u64));
+    }
+
+    #[test]
+    fn checks_inputs_against_circuit_signals() {
+        let signal = |name: &str, shape: &[usize]| InputSignal {
+            name: name.to_string(),
+            shape: shape.to_vec(),
+            public: false,
+        };
+        let signals = [signal("a", &[]), signal("in", &[2, 2])];
+
+        let check = |json: &str| {
+            let json = merge_inputs(json, None, &[]).unwrap();
+            let mut inputs = flatten_inputs(&json, false).unwrap();
+            check_signals(&mut inputs, &json, &signals, UnknownInputs::Error)
+        };
+        check(r#"{"a": 1, "in": [[1, 2], [3, 4]]}"#).unwrap();
+        // Flat arrays are taken in row-major order
+        check(r#"{"a": 1, "in": [1, 2, 3, 4]}"#).unwrap();
+
+        let Err(InputError::Invalid(issues)) = check(r#"{"in": [1, 2, 3], "b": 1}"#) else {
+            panic!("expected invalid inputs");
+        };
+        let reasons: Vec<_> = issues
+            .iter()
+            .map(|issue| format!("{}: {}", issue.signal, issue.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                "b: not an input signal of the circuit",
+                "a: missing, expected a scalar",
+                "in: expected an array of shape [2, 2] of 4 values, got 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_tensor_shapes_axis_by_axis() {
+        let signals = [InputSignal {
+            name: "t".to_string(),
+            shape: vec![2, 3, 2],
+            public: false,
+        }];
+        let check = |json: &str| {
+            let json = merge_inputs(json, None, &[]).unwrap();
+            let mut inputs = flatten_inputs(&json, false)?;
+            check_signals(&mut inputs, &json, &signals, UnknownInputs::Error)
+        };
+        let reason = |json: &str| match check(json) {
+            Err(InputError::Invalid(issues)) => issues[0].reason.clone(),
+            other => panic!("expected invalid inputs, got {other:?}"),
+        };
+
+        check(r#"{"t": [[[1, 2], [3, 4], [5, 6]], [[7, 8], [9, 10], [11, 12]]]}"#).unwrap();
+        assert_eq!(
+            reason(r#"{"t": [[[1, 2, 3], [4, 5, 6]], [[7, 8, 9], [10, 11, 12]]]}"#),
+            "expected shape [2, 3, 2], got [2, 2, 3]: axis 1 has length 2, expected 3"
+        );
+        assert_eq!(
+            reason(r#"{"t": [[1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12]]}"#),
+            "expected shape [2, 3, 2] with 3 axes, got [2, 6] with 2"
+        );
+        assert_eq!(
+            reason(r#"{"t": [[[1, 2], [3, 4], [5, 6]], [[7, 8], [9], [11, 12]]]}"#),
+            "ragged array: axis 2 has length 1 at [1][1], expected 2"
+        );
+        assert_eq!(
+            tensor_shape(&serde_json::json!([[1, 2], 3])).unwrap_err(),
+            "ragged array: [1] has 0 axes, expected 1"
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_inputs_when_lenient() {
+        let signals = [InputSignal {
+            name: "a".to_string(),
+            shape: vec![],
+            public: false,
+        }];
+        let json = merge_inputs(r#"{"a": 1, "b": 2, "c": [3, 4]}"#, None, &[]).unwrap();
+        let mut inputs = flatten_inputs(&json, false).unwrap();
+        let ignored = check_signals(&mut inputs, &json, &signals, UnknownInputs::Ignore).unwrap();
+        assert_eq!(ignored, ["b", "c"]);
+        assert_eq!(inputs, [("a".to_string(), Fr::from(1u64))]);
+    }
+
+    #[test]
+    fn reports_every_invalid_signal() {
+        let Err(InputError::Invalid(issues)) = parse_inputs(
+            r#"{"a": "x", "b": 1, "c": [1, null], "d": 1.5, "e": 1e30}"#,
+            false,
+        ) else {
+            panic!("expected invalid inputs");
+        };
+        let signals: Vec<_> = issues.iter().map(|issue| issue.signal.as_str()).collect();
+        assert_eq!(signals, ["a", "c", "d", "e"]);
+    }
+}
@@ -0,0 +1,40 @@
+//! Bridges Circom circuits to the Bulletproofs arithmetic-circuit proof system.
+
+#[cfg(feature = "archive")]
+pub mod archive;
+pub mod atomic;
+pub mod audit;
+pub mod builder;
+pub mod bundle;
+pub mod commitment;
+pub mod compliance;
+pub mod compile;
+pub mod conversion;
+pub mod conversion_cache;
+pub mod crs;
+pub mod curve;
+pub mod encryption;
+pub mod equality;
+pub mod estimate;
+pub mod exit_codes;
+pub mod fingerprint;
+pub mod inputs;
+pub mod limits;
+pub mod locate;
+pub mod manifest;
+pub mod oracle;
+pub mod pipeline;
+pub mod profile;
+pub mod r1cs;
+pub mod range;
+#[cfg(feature = "registry")]
+pub mod registry;
+pub mod reorder;
+pub mod simplify;
+pub mod sink;
+pub mod sparse;
+pub mod statement;
+pub mod sym;
+pub mod template;
+pub mod witness_cache;
+pub mod wtns;
@@ -0,0 +1,183 @@
+use std::{sync::OnceLock, thread, time::Duration};
+
+use tracing::error;
+
+use crate::exit_codes;
+
+static INSTALLED: OnceLock<ResourceLimits> = OnceLock::new();
+
+/// Self-imposed resource limits of one invocation, so multi-tenant hosts can bound
+/// what a single proving job may take
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Most worker threads, whatever `--threads` asks for
+    pub max_threads: Option<usize>,
+    /// Most bytes of heap memory
+    pub max_memory: Option<u64>,
+    /// Wall-clock time after which the process exits
+    pub max_duration: Option<Duration>,
+}
+
+impl ResourceLimits {
+    /// The limits installed for this process, none if `install` was not called
+    pub fn current() -> Self {
+        INSTALLED.get().copied().unwrap_or_default()
+    }
+
+    /// Enforces the limits for the rest of the process; only the first call counts
+    ///
+    /// Memory is capped with `RLIMIT_DATA` where available, so allocations beyond it
+    /// fail. `RLIMIT_AS` would also count the address space the wasm runtime
+    /// reserves without using it. The duration is enforced by a watchdog thread that
+    /// exits with `exit_codes::RESOURCE_LIMIT`.
+    pub fn install(self) -> Result<(), LimitError> {
+        if INSTALLED.set(self).is_err() {
+            return Ok(());
+        }
+        if let Some(bytes) = self.max_memory {
+            set_memory_limit(bytes)?;
+        }
+        if let Some(duration) = self.max_duration {
+            thread::Builder::new()
+                .name("deadline".to_string())
+                .spawn(move || {
+                    thread::sleep(duration);
+                    error!("Exceeded the time limit of {duration:?}, aborting");
+                    std::process::exit(exit_codes::RESOURCE_LIMIT.into());
+                })
+                .map_err(LimitError::System)?;
+        }
+        Ok(())
+    }
+
+    /// Worker threads to configure given the requested count, where `None` and 0
+    /// mean one per core
+    pub fn thread_count(&self, requested: Option<usize>) -> Option<usize> {
+        let Some(max) = self.max_threads else {
+            return requested;
+        };
+        let requested = requested.filter(|&n| n > 0).unwrap_or_else(|| {
+            thread::available_parallelism().map_or(max, std::num::NonZeroUsize::get)
+        });
+        Some(requested.min(max).max(1))
+    }
+
+    /// Fails up front when a job is estimated to need more memory than allowed
+    pub fn check_memory(&self, estimated_bytes: usize) -> Result<(), LimitError> {
+        match self.max_memory {
+            Some(limit) if estimated_bytes as u64 > limit => Err(LimitError::Memory {
+                needed: estimated_bytes as u64,
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn set_memory_limit(bytes: u64) -> Result<(), LimitError> {
+    let limit = libc::rlimit {
+        rlim_cur: bytes as libc::rlim_t,
+        rlim_max: bytes as libc::rlim_t,
+    };
+    // SAFETY: `limit` is a valid rlimit that outlives the call
+    if unsafe { libc::setrlimit(libc::RLIMIT_DATA, &limit) } != 0 {
+        return Err(LimitError::System(std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_memory_limit(_bytes: u64) -> Result<(), LimitError> {
+    tracing::warn!("Memory limits are only enforced by setrlimit, which this platform lacks");
+    Ok(())
+}
+
+/// Parses a byte count such as `1048576`, `512K`, `64M` or `4G`, in powers of 1024
+pub fn parse_bytes(s: &str) -> Result<u64, LimitError> {
+    let s = s.trim();
+    let digits = s.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let shift = match s[digits.len()..].to_ascii_uppercase().as_str() {
+        "" | "B" => 0,
+        "K" | "KB" | "KIB" => 10,
+        "M" | "MB" | "MIB" => 20,
+        "G" | "GB" | "GIB" => 30,
+        "T" | "TB" | "TIB" => 40,
+        _ => return Err(LimitError::InvalidSize(s.to_string())),
+    };
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(1 << shift))
+        .ok_or_else(|| LimitError::InvalidSize(s.to_string()))
+}
+
+/// Parses a duration such as `90`, `90s`, `500ms`, `15m` or `2h`; bare numbers are
+/// seconds
+pub fn parse_duration(s: &str) -> Result<Duration, LimitError> {
+    let s = s.trim();
+    let digits = s.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let value: u64 = digits
+        .trim()
+        .parse()
+        .map_err(|_| LimitError::InvalidDuration(s.to_string()))?;
+    let duration = match &s[digits.len()..] {
+        "ms" => Some(Duration::from_millis(value)),
+        "" | "s" => Some(Duration::from_secs(value)),
+        "m" => value.checked_mul(60).map(Duration::from_secs),
+        "h" => value.checked_mul(3600).map(Duration::from_secs),
+        _ => None,
+    };
+    duration.ok_or_else(|| LimitError::InvalidDuration(s.to_string()))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LimitError {
+    #[error("Invalid size '{0}', expected bytes or a K, M, G or T suffix")]
+    InvalidSize(String),
+    #[error("Invalid duration '{0}', expected seconds or an ms, s, m or h suffix")]
+    InvalidDuration(String),
+    #[error("Proving needs about {needed} bytes of memory, above the limit of {limit}")]
+    Memory { needed: u64, limit: u64 },
+    #[error("Failed to apply resource limits: {0}")]
+    System(std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sizes_and_durations() {
+        assert_eq!(parse_bytes("1024").unwrap(), 1024);
+        assert_eq!(parse_bytes("512K").unwrap(), 512 << 10);
+        assert_eq!(parse_bytes("4GiB").unwrap(), 4 << 30);
+        assert!(parse_bytes("4X").is_err());
+        assert!(parse_bytes("G").is_err());
+
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+        assert!(parse_duration("1d").is_err());
+    }
+
+    #[test]
+    fn caps_threads_and_memory() {
+        let limits = ResourceLimits {
+            max_threads: Some(2),
+            max_memory: Some(1 << 20),
+            max_duration: None,
+        };
+        assert_eq!(limits.thread_count(Some(8)), Some(2));
+        assert_eq!(limits.thread_count(Some(1)), Some(1));
+        assert!(limits.thread_count(None).is_some_and(|n| n <= 2));
+        assert_eq!(ResourceLimits::default().thread_count(None), None);
+
+        limits.check_memory(1 << 20).unwrap();
+        assert!(matches!(
+            limits.check_memory((1 << 20) + 1),
+            Err(LimitError::Memory { .. })
+        ));
+    }
+}
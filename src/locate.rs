@@ -0,0 +1,207 @@
+use std::{collections::HashMap, fmt};
+
+use ark_circom::circom::R1CS;
+use ark_ff::PrimeField;
+use bulletproofs::circuit::types::Witness;
+
+use crate::conversion::constraint_placement;
+use crate::r1cs::ONE_WIRE;
+use crate::sparse::SparseCircuit;
+
+/// Part of a converted circuit a witness can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    /// A linear constraint `W_L·a_L + W_R·a_R + W_O·a_O = W_V·v + c`
+    Row(usize),
+    /// A multiplication gate `a_L ⊙ a_R = a_O`
+    Gate(usize),
+}
+
+/// A failed row or gate of a converted circuit, traced back to the R1CS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub location: Location,
+    /// The R1CS constraint converted into it; `None` for padding and for the
+    /// gates carrying private variables
+    pub constraint: Option<usize>,
+    /// Variables of that constraint, without the constant wire
+    pub variables: Vec<usize>,
+}
+
+impl Failure {
+    /// Names the failure's signals by `names`, keyed by R1CS variable, e.g. from
+    /// `SymbolTable::names_by_variable`; unnamed ones read `variable i`
+    pub fn describe(&self, names: &HashMap<usize, &str>) -> String {
+        let Some(constraint) = self.constraint else {
+            return format!("{}, owned by no constraint", self.location);
+        };
+        let signals: Vec<_> = self
+            .variables
+            .iter()
+            .map(|variable| {
+                names
+                    .get(variable)
+                    .map_or_else(|| format!("variable {variable}"), |name| name.to_string())
+            })
+            .collect();
+        format!(
+            "{} of constraint {constraint} ({})",
+            self.location,
+            signals.join(", ")
+        )
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Location::Row(row) => write!(f, "row {row}"),
+            Location::Gate(gate) => write!(f, "gate {gate}"),
+        }
+    }
+}
+
+/// The gates and rows of `circuit` that `witness` fails, gates first, each with
+/// the R1CS constraint of `r1cs` it was converted from
+///
+/// `circuit` must be the conversion of `r1cs`. A witness of the wrong shape fails
+/// as a whole rather than at any row, and yields no failures.
+pub fn failures<F: PrimeField>(
+    r1cs: &R1CS<F>,
+    circuit: &SparseCircuit<F>,
+    witness: &Witness<F>,
+) -> Vec<Failure> {
+    if !circuit.fits(witness) {
+        return Vec::new();
+    }
+    let mut row_owners = vec![None; circuit.size()];
+    let mut gate_owners = vec![None; circuit.dim()];
+    for (constraint, (rows, gate)) in constraint_placement(r1cs).into_iter().enumerate() {
+        for row in rows {
+            row_owners[row] = Some(constraint);
+        }
+        if let Some(gate) = gate {
+            gate_owners[gate] = Some(constraint);
+        }
+    }
+
+    let failure = |location, constraint: Option<usize>| {
+        let mut variables: Vec<usize> = constraint
+            .map(|i| {
+                let (a, b, c) = &r1cs.constraints[i];
+                a.iter()
+                    .chain(b)
+                    .chain(c)
+                    .map(|&(variable, _)| variable)
+                    .collect()
+            })
+            .unwrap_or_default();
+        variables.retain(|&variable| variable != ONE_WIRE);
+        variables.sort_unstable();
+        variables.dedup();
+        Failure {
+            location,
+            constraint,
+            variables,
+        }
+    };
+    let gates = (0..circuit.dim())
+        .filter(|&gate| !circuit.gate_holds(gate, witness))
+        .map(|gate| failure(Location::Gate(gate), gate_owners[gate]));
+    let rows = (0..circuit.size())
+        .filter(|&row| !circuit.row_holds(row, witness))
+        .map(|row| failure(Location::Row(row), row_owners[row]));
+    gates.chain(rows).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::circom_to_sparse;
+    use ark_bn254::Fr;
+    use ark_circom::CircomCircuit;
+
+    /// `c <== a * b` and `a + 2 === s` over wires [1, c, s, a, b]
+    fn circom() -> CircomCircuit<Fr> {
+        let one = Fr::from(1u64);
+        CircomCircuit {
+            r1cs: R1CS {
+                num_inputs: 3,
+                num_aux: 2,
+                num_variables: 5,
+                constraints: vec![
+                    (vec![(3, one)], vec![(4, one)], vec![(1, one)]),
+                    (
+                        vec![],
+                        vec![],
+                        vec![(3, one), (ONE_WIRE, Fr::from(2u64)), (2, -one)],
+                    ),
+                ],
+                wire_mapping: None,
+            },
+            witness: Some([1u64, 33, 5, 3, 11].map(Fr::from).to_vec()),
+        }
+    }
+
+    #[test]
+    fn satisfied_witness_has_no_failures() {
+        let circom = circom();
+        let (circuit, witness, _) = circom_to_sparse(&circom, &[]).unwrap();
+        assert!(failures(&circom.r1cs, &circuit, &witness).is_empty());
+    }
+
+    #[test]
+    fn failed_row_names_the_signals_of_its_constraint() {
+        let circom = circom();
+        let (circuit, mut witness, _) = circom_to_sparse(&circom, &[]).unwrap();
+        // s is committed; claiming s = 6 breaks only the linear constraint's row
+        witness.v[1] += Fr::from(1u64);
+        let failures = failures(&circom.r1cs, &circuit, &witness);
+        assert_eq!(
+            failures,
+            [Failure {
+                location: Location::Row(3),
+                constraint: Some(1),
+                variables: vec![2, 3],
+            }]
+        );
+        let names = HashMap::from([(2, "main.s"), (3, "main.a")]);
+        assert_eq!(
+            failures[0].describe(&names),
+            "row 3 of constraint 1 (main.s, main.a)"
+        );
+        assert_eq!(
+            failures[0].describe(&HashMap::new()),
+            "row 3 of constraint 1 (variable 2, variable 3)"
+        );
+    }
+
+    #[test]
+    fn failed_gate_traces_to_its_constraint() {
+        let circom = circom();
+        let (circuit, mut witness, _) = circom_to_sparse(&circom, &[]).unwrap();
+        witness.a_o[0] += Fr::from(1u64);
+        let failures = failures(&circom.r1cs, &circuit, &witness);
+        assert_eq!(failures[0].location, Location::Gate(0));
+        assert_eq!(failures[0].constraint, Some(0));
+        assert_eq!(failures[0].variables, [1, 3, 4]);
+        // Gate 0's output row reads a_O[0] = c as well
+        assert_eq!(failures[1].location, Location::Row(2));
+    }
+
+    #[test]
+    fn private_variable_gates_are_owned_by_no_constraint() {
+        let circom = circom();
+        let (circuit, mut witness, _) = circom_to_sparse(&circom, &[]).unwrap();
+        // Gate 1 carries a and b, and no row reads its output
+        witness.a_o[1] += Fr::from(1u64);
+        let failures = failures(&circom.r1cs, &circuit, &witness);
+        assert_eq!(
+            failures
+                .iter()
+                .map(|failure| failure.describe(&HashMap::new()))
+                .collect::<Vec<_>>(),
+            ["gate 1, owned by no constraint"]
+        );
+    }
+}
@@ -1,99 +1,179 @@
-use ark_circom::{CircomBuilder, CircomConfig};
-use ark_bn254::{Fr, G1Projective};
-use bulletproofs::circuit::{
-    CircuitProofDomainSeparator, prove as circuit_prove, verify as circuit_verify,
-    types::{CRS as CircuitCRS, Statement as CircuitStatement}
-};
-use rand::rngs::OsRng;
-use serde_json::{Map, Value};
-use spongefish::{DomainSeparator, codecs::arkworks_algebra::CommonGroupToUnit};
-mod conversion;
-use conversion::circom_to_bulletproofs;
+use std::{process::ExitCode, time::Duration};
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Get circuit name from command line arguments
-    let args: Vec<String> = std::env::args().collect();
-    let circuit_name = if args.len() > 1 {
-        &args[1]
-    } else {
-        return Err("Usage: cargo run <circuit_name>".into());
-    };
-    
-    // Load circuit files
-    let wasm_path = format!("./circuits/{}_js/{}.wasm", circuit_name, circuit_name);
-    let r1cs_path = format!("./circuits/{}.r1cs", circuit_name);
-    let inputs_path = format!("./circuits/{}_inputs.json", circuit_name);
-    
-    let config = CircomConfig::<Fr>::new(&wasm_path, &r1cs_path)?;
-    let mut builder = CircomBuilder::new(config);
-    
-    // Load inputs from JSON file
-    let inputs_json = std::fs::read_to_string(&inputs_path)?;
-    let inputs: Map<String, Value> = serde_json::from_str(&inputs_json)?;
-    
-    // Add all inputs to the circuit builder
-    for (key, value) in inputs {
-        let input_value = match value {
-            Value::Number(n) => {
-                if let Some(i) = n.as_i64() {
-                    i
-                } else if let Some(u) = n.as_u64() {
-                    u as i64
-                } else {
-                    return Err(format!("Invalid number format for input '{}'", key).into());
-                }
-            }
-            _ => return Err(format!("Input '{}' must be a number", key).into()),
-        };
-        builder.push_input(&key, input_value);
+use circom_bp::limits::{self, ResourceLimits};
+use clap::{Parser, Subcommand};
+use tracing_subscriber::EnvFilter;
+
+mod commands;
+
+#[derive(Parser)]
+#[command(
+    name = "circom-bp",
+    version,
+    about = "Prove Circom circuits with Bulletproofs"
+)]
+struct Cli {
+    /// Only log warnings and errors, overriding `RUST_LOG`
+    #[arg(long, short, global = true, env = "CIRCOM_BP_QUIET")]
+    quiet: bool,
+    /// Worker threads for proving and conversion; 0 uses one per core
+    #[arg(long, global = true, env = "CIRCOM_BP_THREADS")]
+    threads: Option<usize>,
+    /// Most worker threads, capping `--threads` and the per-core default
+    #[arg(long, global = true, env = "CIRCOM_BP_MAX_THREADS")]
+    max_threads: Option<usize>,
+    /// Most heap memory, e.g. `512M` or `8G`; jobs estimated to need more fail up
+    /// front and allocations beyond it fail
+    #[arg(
+        long,
+        global = true,
+        value_parser = limits::parse_bytes,
+        env = "CIRCOM_BP_MAX_MEMORY"
+    )]
+    max_memory: Option<u64>,
+    /// Wall-clock time after which the process exits, e.g. `90s` or `15m`
+    #[arg(
+        long,
+        global = true,
+        value_parser = limits::parse_duration,
+        env = "CIRCOM_BP_MAX_DURATION"
+    )]
+    max_duration: Option<Duration>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a witness, prove the circuit and verify the proof
+    Prove(commands::prove::ProveArgs),
+    /// Time every phase of the pipeline over repeated runs
+    Bench(commands::bench::BenchArgs),
+    /// Prove every inputs file in a directory, reusing one CRS
+    BatchProve(commands::batch_prove::BatchProveArgs),
+    /// Verify many proof bundles of one circuit
+    VerifyBatch(commands::verify_batch::VerifyBatchArgs),
+    /// Re-run the pipeline whenever the inputs or circuit change; accepts a `.circom` source
+    Watch(commands::watch::WatchArgs),
+    /// Check that this host is ready to prove and verify
+    Doctor(commands::doctor::DoctorArgs),
+    /// Compile a circom source into the r1cs, wasm and sym files used here
+    Build(commands::build::BuildArgs),
+    /// Compile, prove and verify a circom source in one step
+    Run(commands::run::RunArgs),
+    /// Report the signals that differ between two .wtns witnesses
+    WitnessDiff(commands::witness_diff::WitnessDiffArgs),
+    /// Print a skeleton inputs file listing every input signal of a circuit
+    InputsTemplate(commands::inputs_template::InputsTemplateArgs),
+    /// Encrypt an inputs file so provers need not keep it on disk in plaintext
+    EncryptInputs(commands::encrypt_inputs::EncryptInputsArgs),
+    /// Publish Pedersen commitments to input signals ahead of a proof, writing the
+    /// secret blindings that open them to an owner-only file
+    Commit(commands::commit::CommitArgs),
+    /// Generate an ed25519 key for signing params manifests
+    Keygen(commands::sign_params::KeygenArgs),
+    /// Sign a circuit's fingerprint and CRS parameters for distribution
+    SignParams(commands::sign_params::SignParamsArgs),
+    /// Print a shell completion script
+    Completions(commands::completions::CompletionsArgs),
+    /// List, inspect and prune the local proof registry
+    Proofs(commands::proofs::ProofsArgs),
+    /// Warn when proofs of a witness would not hide its private values
+    Audit(commands::audit::AuditArgs),
+    /// Check proof bundles against the published schema, without verifying them
+    ValidateBundle(commands::validate_bundle::ValidateBundleArgs),
+}
+
+impl Command {
+    /// The circuit selection of commands that work on a compiled circuit
+    fn circuit_args(&mut self) -> Option<&mut commands::CircuitArgs> {
+        match self {
+            Command::Prove(args) => Some(&mut args.circuit),
+            Command::Bench(args) => Some(&mut args.circuit),
+            Command::BatchProve(args) => Some(&mut args.circuit),
+            Command::VerifyBatch(args) => Some(&mut args.circuit),
+            Command::Watch(args) => Some(&mut args.circuit),
+            Command::InputsTemplate(args) => Some(&mut args.circuit),
+            Command::Commit(args) => Some(&mut args.circuit),
+            Command::SignParams(args) => Some(&mut args.circuit),
+            Command::Audit(args) => Some(&mut args.circuit),
+            _ => None,
+        }
     }
-    
-    let circom = builder.build()?;
-    if circom.witness.is_none() {
-        return Err("Witness generation failed".into());
+}
+
+fn main() -> ExitCode {
+    let mut cli = Cli::parse();
+    init_logging(cli.quiet);
+    let limits = ResourceLimits {
+        max_threads: cli.max_threads,
+        max_memory: cli.max_memory,
+        max_duration: cli.max_duration,
+    };
+    if let Err(e) = limits.install() {
+        tracing::error!("{e}");
+        return ExitCode::FAILURE;
     }
-    
-    println!("Generated witness with {} values", circom.witness.as_ref().unwrap().len());
-    
-    // Convert to bulletproofs format with power-of-2 padding
-    let (circuit, witness) = circom_to_bulletproofs(&circom)?;
-    println!("Bulletproof circuit: {} constraints, {} variables", circuit.size(), circuit.dim());
-    
-    if !circuit.is_satisfied_by(&witness) {
-        return Err("Circuit not satisfied by witness".into());
+    // Sizes the global pool arkworks uses for MSMs and FFTs
+    if let Some(threads) = limits.thread_count(cli.threads)
+        && let Err(e) = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+    {
+        tracing::error!("Failed to configure {threads} threads: {e}");
+        return ExitCode::FAILURE;
     }
-    
-    // Generate CRS (circuit dimension is already power-of-2)
-    let mut rng = OsRng;
-    let crs_size = circuit.dim();
-    println!("Generating CRS with size: {}", crs_size);
-    let crs: CircuitCRS<G1Projective> = CircuitCRS::rand(crs_size, &mut rng);
-    
-    // Create public statement
-    let statement = CircuitStatement::new(&crs, &witness);
-    
-    // Set up Fiat-Shamir domain separator
-    let domain_separator = {
-        let ds = DomainSeparator::new("circom-to-bulletproofs");
-        let ds = CircuitProofDomainSeparator::<G1Projective>::circuit_proof_statement(ds, statement.v.len()).ratchet();
-        CircuitProofDomainSeparator::<G1Projective>::add_circuit_proof(ds, crs_size)
+    // Removes the build directory of a `--source` once the command is done
+    let _build_dir = match cli.command.circuit_args().map(|circuit| circuit.resolve()) {
+        Some(Err(e)) => {
+            tracing::error!("{e}");
+            return ExitCode::from(commands::exit_code(e.as_ref()));
+        }
+        Some(Ok(build_dir)) => build_dir,
+        None => None,
+    };
+
+    let result = match cli.command {
+        Command::Prove(args) => commands::prove::run(args),
+        Command::Bench(args) => commands::bench::run(args),
+        Command::BatchProve(args) => commands::batch_prove::run(args),
+        Command::VerifyBatch(args) => commands::verify_batch::run(args),
+        Command::Watch(args) => commands::watch::run(args),
+        Command::Doctor(args) => commands::doctor::run(args),
+        Command::Build(args) => commands::build::run(args),
+        Command::Run(args) => commands::run::run(args),
+        Command::WitnessDiff(args) => commands::witness_diff::run(args),
+        Command::InputsTemplate(args) => commands::inputs_template::run(args),
+        Command::EncryptInputs(args) => commands::encrypt_inputs::run(args),
+        Command::Commit(args) => commands::commit::run(args),
+        Command::Keygen(args) => commands::sign_params::keygen(args),
+        Command::SignParams(args) => commands::sign_params::run(args),
+        Command::Completions(args) => commands::completions::run(args),
+        Command::Proofs(args) => commands::proofs::run(args),
+        Command::Audit(args) => commands::audit::run(args),
+        Command::ValidateBundle(args) => commands::validate_bundle::run(args),
     };
-    
-    // Generate bulletproof
-    println!("Generating proof...");
-    let mut prover_state = domain_separator.to_prover_state();
-    prover_state.public_points(&statement.v)?;
-    prover_state.ratchet()?;
-    let proof = circuit_prove(&mut prover_state, &crs, &circuit, &witness, &mut rng)?;
-    
-    // Verify bulletproof
-    println!("Verifying proof...");
-    let mut verifier_state = domain_separator.to_verifier_state(&proof);
-    verifier_state.public_points(&statement.v)?;
-    verifier_state.ratchet()?;
-    circuit_verify(&mut verifier_state, &crs, &circuit, &statement, &mut rng)?;
-    
-    println!("✅ Proof verified successfully!");
 
-    Ok(())
-}
\ No newline at end of file
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            tracing::error!("{e}");
+            ExitCode::from(commands::exit_code(e.as_ref()))
+        }
+    }
+}
+
+/// Logs go to stderr so stdout stays reserved for requested output such as `--json`
+fn init_logging(quiet: bool) {
+    let filter = if quiet {
+        EnvFilter::new("warn")
+    } else {
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .without_time()
+        .init();
+}
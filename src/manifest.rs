@@ -0,0 +1,150 @@
+use std::path::Path;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::{crs::CrsParams, fingerprint::Fingerprint};
+
+/// The public parameters a verifier needs: which circuit, and which CRS
+///
+/// The CRS is fully determined by its parameters, so signing them (together with
+/// the fingerprint of the `.r1cs` the verifier rebuilds the circuit from) pins
+/// everything a verifier would otherwise have to take on trust.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParamsManifest {
+    pub circuit: String,
+    pub circuit_fingerprint: Fingerprint,
+    pub crs: CrsParams,
+}
+
+/// A manifest signed with ed25519 by the party who ran setup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedManifest {
+    pub manifest: ParamsManifest,
+    #[serde(with = "hex::serde")]
+    pub public_key: [u8; 32],
+    #[serde(with = "hex::serde")]
+    pub signature: Vec<u8>,
+}
+
+impl ParamsManifest {
+    /// Bytes covered by the signature: the manifest's JSON, in field order
+    fn signed_bytes(&self) -> Result<Vec<u8>, ManifestError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn sign(self, key: &SigningKey) -> Result<SignedManifest, ManifestError> {
+        let signature = key.sign(&self.signed_bytes()?);
+        Ok(SignedManifest {
+            manifest: self,
+            public_key: key.verifying_key().to_bytes(),
+            signature: signature.to_bytes().to_vec(),
+        })
+    }
+}
+
+impl SignedManifest {
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), ManifestError> {
+        crate::atomic::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    /// Returns the manifest once its signature checks out under `trusted`
+    ///
+    /// The embedded public key is only a hint; the caller must supply the key it
+    /// trusts, otherwise anyone could re-sign substituted parameters.
+    pub fn verify(&self, trusted: &VerifyingKey) -> Result<&ParamsManifest, ManifestError> {
+        if self.public_key != trusted.to_bytes() {
+            return Err(ManifestError::UntrustedKey(hex::encode(self.public_key)));
+        }
+        let signature =
+            Signature::from_slice(&self.signature).map_err(|_| ManifestError::BadSignature)?;
+        trusted
+            .verify(&self.manifest.signed_bytes()?, &signature)
+            .map_err(|_| ManifestError::BadSignature)?;
+        Ok(&self.manifest)
+    }
+}
+
+/// Generates a fresh signing key for `sign-params`
+pub fn generate_key<R: RngCore + CryptoRng>(rng: &mut R) -> SigningKey {
+    SigningKey::generate(rng)
+}
+
+/// Parses a hex-encoded 32-byte ed25519 secret key, as written by `keygen`
+pub fn parse_signing_key(hex_key: &str) -> Result<SigningKey, ManifestError> {
+    Ok(SigningKey::from_bytes(&decode_key(hex_key)?))
+}
+
+/// Parses a hex-encoded 32-byte ed25519 public key
+pub fn parse_verifying_key(hex_key: &str) -> Result<VerifyingKey, ManifestError> {
+    VerifyingKey::from_bytes(&decode_key(hex_key)?)
+        .map_err(|e| ManifestError::InvalidKey(e.to_string()))
+}
+
+fn decode_key(hex_key: &str) -> Result<[u8; 32], ManifestError> {
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(hex_key.trim().trim_start_matches("0x"), &mut bytes)
+        .map_err(|e| ManifestError::InvalidKey(e.to_string()))?;
+    Ok(bytes)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ManifestError {
+    #[error("Failed to access params manifest: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Malformed params manifest: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid ed25519 key, expected 32 hex-encoded bytes: {0}")]
+    InvalidKey(String),
+    #[error("Params manifest is signed by untrusted key {0}")]
+    UntrustedKey(String),
+    #[error("Params manifest signature is invalid")]
+    BadSignature,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::HashAlgorithm;
+
+    fn manifest() -> ParamsManifest {
+        ParamsManifest {
+            circuit: "multiplier2".into(),
+            circuit_fingerprint: HashAlgorithm::Blake3.digest(b"r1cs"),
+            crs: CrsParams::new(4),
+        }
+    }
+
+    #[test]
+    fn signed_manifest_round_trips() {
+        let key = generate_key(&mut rand::rngs::OsRng);
+        let signed = manifest().sign(&key).unwrap();
+        let json = serde_json::to_string(&signed).unwrap();
+        let parsed: SignedManifest = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.verify(&key.verifying_key()).unwrap(), &manifest());
+    }
+
+    #[test]
+    fn substituted_params_are_rejected() {
+        let key = generate_key(&mut rand::rngs::OsRng);
+        let mut signed = manifest().sign(&key).unwrap();
+        signed.manifest.crs = CrsParams::with_seed(4, [7; 32]);
+        assert!(matches!(
+            signed.verify(&key.verifying_key()),
+            Err(ManifestError::BadSignature)
+        ));
+
+        let other = generate_key(&mut rand::rngs::OsRng);
+        let resigned = manifest().sign(&other).unwrap();
+        assert!(matches!(
+            resigned.verify(&key.verifying_key()),
+            Err(ManifestError::UntrustedKey(_))
+        ));
+    }
+}
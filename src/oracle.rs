@@ -0,0 +1,166 @@
+use std::fmt;
+
+use ark_bn254::Fr;
+use ark_circom::CircomCircuit;
+use bulletproofs::circuit::types::Witness;
+
+use crate::conversion::constraint_placement;
+use crate::r1cs;
+use crate::sparse::SparseCircuit;
+
+/// Whether the conversion oracle runs: always in debug builds, including tests,
+/// and in release builds when `requested`, e.g. by `--check-conversion`
+pub fn enabled(requested: bool) -> bool {
+    cfg!(debug_assertions) || requested
+}
+
+/// Checks that the converted circuit and the R1CS it was converted from agree on
+/// the witness, constraint by constraint
+///
+/// The R1CS is evaluated directly, and each constraint's verdict is compared with
+/// that of the rows and gate converted from it (`conversion::constraint_placement`).
+/// The circuit as a whole, padding and variable gates included, must then be
+/// satisfied exactly when the R1CS is. Disagreement means the conversion does not
+/// preserve the constraint system: a satisfied R1CS yielding an unprovable circuit,
+/// or worse, a violated constraint whose rows still hold. Checking per constraint
+/// also catches bugs an unsatisfied witness would otherwise hide, since both sides
+/// failing somewhere is not both failing at the same place.
+pub fn check_conversion(
+    circom: &CircomCircuit<Fr>,
+    circuit: &SparseCircuit<Fr>,
+    witness: &Witness<Fr>,
+) -> Result<(), ConversionMismatch> {
+    // The conversion has already rejected witnesses that don't fit the wire mapping
+    let values = r1cs::variable_values(&circom.r1cs, circom.witness.as_deref().unwrap_or_default())
+        .unwrap_or_default();
+    let failed = r1cs::unsatisfied_constraints(&circom.r1cs, &values);
+    let r1cs_satisfied = failed.is_empty();
+    let converted_satisfied = circuit.is_satisfied_by(witness);
+    let mismatch = |constraint| ConversionMismatch {
+        r1cs_satisfied,
+        converted_satisfied,
+        failed_constraints: failed.len(),
+        constraint,
+    };
+    // A witness of the wrong shape fails `is_satisfied_by` without indexing into it
+    if circuit.fits(witness) {
+        for (i, (rows, gate)) in constraint_placement(&circom.r1cs).into_iter().enumerate() {
+            let holds = rows.clone().all(|row| circuit.row_holds(row, witness))
+                && gate.is_none_or(|gate| circuit.gate_holds(gate, witness));
+            if holds == failed.binary_search(&i).is_ok() {
+                return Err(mismatch(Some(i)));
+            }
+        }
+    }
+    if r1cs_satisfied != converted_satisfied {
+        return Err(mismatch(None));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub struct ConversionMismatch {
+    pub r1cs_satisfied: bool,
+    pub converted_satisfied: bool,
+    pub failed_constraints: usize,
+    /// The first constraint whose converted rows and gate disagree with it, if the
+    /// disagreement is not only in gates no constraint owns
+    pub constraint: Option<usize>,
+}
+
+impl fmt::Display for ConversionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Conversion changed satisfiability: the R1CS is {} ({} constraints violated) but the converted circuit is {}",
+            satisfied(self.r1cs_satisfied),
+            self.failed_constraints,
+            satisfied(self.converted_satisfied)
+        )?;
+        if let Some(constraint) = self.constraint {
+            write!(
+                f,
+                "; constraint {constraint} disagrees with the rows converted from it"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn satisfied(yes: bool) -> &'static str {
+    if yes { "satisfied" } else { "not satisfied" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::circom_to_sparse;
+    use ark_circom::circom::R1CS;
+
+    /// `c <== a * b` over wires [1, c, a, b]
+    fn multiplier(a: u64, b: u64, c: u64) -> CircomCircuit<Fr> {
+        let one = Fr::from(1u64);
+        CircomCircuit {
+            r1cs: R1CS {
+                num_inputs: 2,
+                num_aux: 2,
+                num_variables: 4,
+                constraints: vec![(vec![(2, one)], vec![(3, one)], vec![(1, one)])],
+                wire_mapping: None,
+            },
+            witness: Some([1, c, a, b].map(Fr::from).to_vec()),
+        }
+    }
+
+    #[test]
+    fn agrees_with_the_r1cs() {
+        for circom in [multiplier(3, 11, 33), multiplier(3, 11, 34)] {
+            let (circuit, witness, _) = circom_to_sparse(&circom, &[]).unwrap();
+            check_conversion(&circom, &circuit, &witness).unwrap();
+        }
+    }
+
+    #[test]
+    fn reports_a_converted_circuit_that_disagrees() {
+        let circom = multiplier(3, 11, 33);
+        let (circuit, mut witness, _) = circom_to_sparse(&circom, &[]).unwrap();
+        let last = witness.a_o.len() - 1;
+        witness.a_o[last] += Fr::from(1u64);
+        assert_eq!(
+            check_conversion(&circom, &circuit, &witness),
+            Err(ConversionMismatch {
+                r1cs_satisfied: true,
+                converted_satisfied: false,
+                failed_constraints: 0,
+                constraint: None,
+            })
+        );
+    }
+
+    /// Both sides unsatisfied, but at different constraints
+    #[test]
+    fn reports_the_constraint_that_disagrees() {
+        let one = Fr::from(1u64);
+        // `c <== a * b` and `d <== a * a` over wires [1, c, d, a, b]
+        let circom = CircomCircuit {
+            r1cs: R1CS {
+                num_inputs: 3,
+                num_aux: 2,
+                num_variables: 5,
+                constraints: vec![
+                    (vec![(3, one)], vec![(4, one)], vec![(1, one)]),
+                    (vec![(3, one)], vec![(3, one)], vec![(2, one)]),
+                ],
+                wire_mapping: None,
+            },
+            witness: Some([1u64, 34, 9, 3, 11].map(Fr::from).to_vec()),
+        };
+        let (mut circuit, witness, _) = circom_to_sparse(&circom, &[]).unwrap();
+        // Constraint 1's output row now expects d + 1
+        circuit.c[5] += one;
+        let mismatch = check_conversion(&circom, &circuit, &witness).unwrap_err();
+        assert_eq!(mismatch.constraint, Some(1));
+        assert!(!mismatch.r1cs_satisfied && !mismatch.converted_satisfied);
+        assert!(mismatch.to_string().contains("constraint 1 disagrees"));
+    }
+}
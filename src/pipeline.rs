@@ -0,0 +1,981 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use ark_bn254::{Fr, G1Projective};
+use ark_circom::{
+    CircomBuilder, CircomCircuit, CircomConfig,
+    circom::{R1CS, R1CSFile},
+};
+use ark_ff::PrimeField;
+use bulletproofs::circuit::{
+    CircuitProofDomainSeparator, prove as circuit_prove,
+    types::{CRS as CircuitCRS, Circuit, Statement as CircuitStatement, Witness},
+    verify as circuit_verify,
+};
+use num_bigint::BigUint;
+use rand::{CryptoRng, RngCore};
+use serde_json::{Map, Value};
+use spongefish::{DomainSeparator, codecs::arkworks_algebra::CommonGroupToUnit};
+use tracing::{debug, error, warn};
+
+use crate::conversion::{self, ConversionError, ConversionReport};
+use crate::conversion_cache::ConversionCache;
+use crate::curve::{Curve, CurveError};
+use crate::inputs::{self, InputError, InputMap, UnknownInputs};
+use crate::locate;
+use crate::r1cs::{self, FailedConstraint, WireMappingError};
+use crate::simplify::Elimination;
+use crate::sparse::SparseCircuit;
+use crate::sym::SymbolTable;
+use crate::template;
+use crate::witness_cache::WitnessCache;
+use crate::wtns;
+
+/// Label absorbed first into every Fiat-Shamir transcript produced by this crate
+pub const DOMAIN_LABEL: &str = "circom-to-bulletproofs";
+
+/// Locations of the compiled artifacts of a single circuit
+#[derive(Debug, Clone)]
+pub struct CircuitPaths {
+    pub wasm: PathBuf,
+    pub r1cs: PathBuf,
+    pub sym: PathBuf,
+    pub inputs: PathBuf,
+    /// Native witness calculator, built from `circom --c` output with `make`
+    pub cpp: PathBuf,
+}
+
+impl CircuitPaths {
+    /// Resolves the layout produced by `npm run compile-circuits`:
+    /// `<dir>/<name>_js/<name>.wasm`, `<dir>/<name>.r1cs`, `<dir>/<name>.sym` and
+    /// `<dir>/<name>_inputs.json`, plus `<dir>/<name>_cpp/<name>` for `circom --c`.
+    pub fn new(dir: impl AsRef<Path>, name: &str) -> Self {
+        let dir = dir.as_ref();
+        Self {
+            wasm: dir.join(format!("{name}_js")).join(format!("{name}.wasm")),
+            r1cs: dir.join(format!("{name}.r1cs")),
+            sym: dir.join(format!("{name}.sym")),
+            inputs: dir.join(format!("{name}_inputs.json")),
+            cpp: dir.join(format!("{name}_cpp")).join(name),
+        }
+    }
+}
+
+/// Which witness calculator computes the witness, as selected with `--witness-source`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WitnessSource {
+    /// The wasm calculator, run in process
+    #[default]
+    Wasm,
+    /// The native calculator of `circom --c`, run as a subprocess; much faster on
+    /// large circuits
+    Cpp,
+}
+
+impl WitnessSource {
+    pub fn name(self) -> &'static str {
+        match self {
+            WitnessSource::Wasm => "wasm",
+            WitnessSource::Cpp => "cpp",
+        }
+    }
+}
+
+impl fmt::Display for WitnessSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for WitnessSource {
+    type Err = UnknownWitnessSource;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wasm" => Ok(WitnessSource::Wasm),
+            "cpp" => Ok(WitnessSource::Cpp),
+            other => Err(UnknownWitnessSource(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown witness source '{0}', expected wasm or cpp")]
+pub struct UnknownWitnessSource(pub String);
+
+/// Computes a witness with the given calculator; only the wasm one uses `cache`
+pub fn generate_witness_from(
+    source: WitnessSource,
+    paths: &CircuitPaths,
+    inputs: &[(String, Fr)],
+    cache: Option<&WitnessCache>,
+) -> Result<CircomCircuit<Fr>, PipelineError> {
+    match source {
+        WitnessSource::Wasm => generate_witness_cached(paths, inputs, cache),
+        WitnessSource::Cpp => generate_witness_cpp(paths, inputs),
+    }
+}
+
+/// Runs the native witness calculator `circom --c` generates for a circuit
+///
+/// The executable is built by running `make` in `<name>_cpp/` and called as
+/// `<name> <inputs.json> <witness.wtns>`. Inputs are handed over in a private
+/// temporary directory, removed afterwards, and the `.wtns` it writes is loaded and
+/// checked like any precomputed witness.
+pub fn generate_witness_cpp(
+    paths: &CircuitPaths,
+    inputs: &[(String, Fr)],
+) -> Result<CircomCircuit<Fr>, PipelineError> {
+    let witness = calculate_cpp(paths, inputs)?;
+    let r1cs = load_r1cs(&paths.r1cs)?;
+    check_loaded_witness(&r1cs, &witness, &paths.cpp)?;
+    Ok(CircomCircuit {
+        r1cs,
+        witness: Some(witness),
+    })
+}
+
+/// The unchecked witness values the native calculator writes for `inputs`
+fn calculate_cpp(paths: &CircuitPaths, inputs: &[(String, Fr)]) -> Result<Vec<Fr>, PipelineError> {
+    if !paths.cpp.is_file() {
+        return Err(PipelineError::Load(format!(
+            "no C++ witness calculator at {}; compile with `circom --c` and run `make` in its directory",
+            paths.cpp.display()
+        )));
+    }
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "circom-bp-witness-{}-{}",
+        std::process::id(),
+        RUNS.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder
+        .recursive(true)
+        .create(&dir)
+        .map_err(|e| PipelineError::Witness(format!("{}: {e}", dir.display())))?;
+
+    let result = run_cpp_calculator(paths, inputs, &dir);
+    if let Err(e) = std::fs::remove_dir_all(&dir) {
+        warn!("Failed to remove {}: {e}", dir.display());
+    }
+    result
+}
+
+fn run_cpp_calculator(
+    paths: &CircuitPaths,
+    inputs: &[(String, Fr)],
+    dir: &Path,
+) -> Result<Vec<Fr>, PipelineError> {
+    let input = dir.join("input.json");
+    let output = dir.join("witness.wtns");
+    std::fs::write(&input, inputs::inputs_json(inputs))
+        .map_err(|e| PipelineError::Witness(format!("{}: {e}", input.display())))?;
+    let run = Command::new(&paths.cpp)
+        .arg(&input)
+        .arg(&output)
+        .output()
+        .map_err(|e| PipelineError::Witness(format!("{}: {e}", paths.cpp.display())))?;
+    if !run.status.success() {
+        let mut message = String::from_utf8_lossy(&run.stderr).into_owned();
+        message.push_str(&String::from_utf8_lossy(&run.stdout));
+        return Err(PipelineError::Witness(format!(
+            "{} failed ({}): {}",
+            paths.cpp.display(),
+            run.status,
+            message.trim()
+        )));
+    }
+    debug!("native witness calculator finished");
+    wtns::read_wtns::<Fr>(&output)
+        .map_err(|e| PipelineError::Load(format!("{}: {e}", output.display())))
+}
+
+/// Loads the witness calculator and R1CS of a circuit
+pub fn load_config(paths: &CircuitPaths) -> Result<CircomConfig<Fr>, PipelineError> {
+    Curve::Bn254.check_r1cs(&paths.r1cs)?;
+    CircomConfig::<Fr>::new(&paths.wasm, &paths.r1cs)
+        .map_err(|e| PipelineError::Load(e.to_string()))
+}
+
+/// Parses only the R1CS of a circuit, which is all a verifier needs
+///
+/// The header prime is checked first, so a circuit compiled for another field fails
+/// with both moduli rather than being read as wrapped bn254 elements.
+pub fn load_r1cs(path: impl AsRef<Path>) -> Result<R1CS<Fr>, PipelineError> {
+    let path = path.as_ref();
+    Curve::Bn254.check_r1cs(path)?;
+    let reader = std::io::BufReader::new(
+        std::fs::File::open(path).map_err(|e| PipelineError::Load(e.to_string()))?,
+    );
+    let file = R1CSFile::<Fr>::new(reader).map_err(|e| PipelineError::Load(e.to_string()))?;
+    Ok(R1CS::from(file))
+}
+
+/// Runs the circom witness calculator over the given inputs
+pub fn generate_witness(
+    config: CircomConfig<Fr>,
+    inputs: &[(String, Fr)],
+) -> Result<CircomCircuit<Fr>, PipelineError> {
+    let mut builder = CircomBuilder::new(config);
+    for (key, value) in inputs {
+        builder.push_input(key, BigUint::from(value.into_bigint()));
+    }
+
+    let circom = builder
+        .build()
+        .map_err(|e| PipelineError::Witness(e.to_string()))?;
+    if circom.witness.is_none() {
+        return Err(PipelineError::Witness("no witness was produced".into()));
+    }
+    debug!(
+        constraints = circom.r1cs.constraints.len(),
+        variables = circom.r1cs.num_variables,
+        "generated witness"
+    );
+    check_witness(&circom)?;
+    Ok(circom)
+}
+
+/// Like `generate_witness`, letting `hook` adjust the inputs, grouped by signal,
+/// right before they are pushed to the calculator
+///
+/// Embedders can derive auxiliary signals this way, e.g. insert the digest of
+/// another input, without touching inputs files. An error from the hook fails
+/// witness generation as an input error.
+pub fn generate_witness_with_hook<E: fmt::Display>(
+    config: CircomConfig<Fr>,
+    inputs: &[(String, Fr)],
+    hook: impl FnOnce(&mut InputMap) -> Result<(), E>,
+) -> Result<CircomCircuit<Fr>, PipelineError> {
+    let mut map = inputs::group_inputs(inputs);
+    hook(&mut map).map_err(|e| InputError::Hook(e.to_string()))?;
+    generate_witness(config, &inputs::ungroup_inputs(map))
+}
+
+/// Loads a precomputed `.wtns` witness, e.g. from snarkjs or a custom calculator,
+/// instead of running the wasm witness calculator
+///
+/// Only the R1CS is loaded. The witness is checked against it like a generated one.
+pub fn load_witness(
+    paths: &CircuitPaths,
+    wtns: impl AsRef<Path>,
+) -> Result<CircomCircuit<Fr>, PipelineError> {
+    let wtns = wtns.as_ref();
+    let witness = wtns::read_wtns::<Fr>(wtns)
+        .map_err(|e| PipelineError::Load(format!("{}: {e}", wtns.display())))?;
+    let r1cs = load_r1cs(&paths.r1cs)?;
+    check_loaded_witness(&r1cs, &witness, wtns)?;
+    Ok(CircomCircuit {
+        r1cs,
+        witness: Some(witness),
+    })
+}
+
+/// Checks a witness read from `source` against `r1cs`: it must hold a value for
+/// every variable and satisfy every constraint
+fn check_loaded_witness(
+    r1cs: &R1CS<Fr>,
+    witness: &[Fr],
+    source: &Path,
+) -> Result<(), PipelineError> {
+    let needed = match &r1cs.wire_mapping {
+        Some(wire_mapping) => wire_mapping
+            .iter()
+            .take(r1cs.num_variables)
+            .max()
+            .map_or(0, |&wire| wire + 1),
+        None => r1cs.num_variables,
+    };
+    if witness.len() < needed {
+        return Err(PipelineError::Witness(format!(
+            "{} holds {} values, the circuit needs {needed}",
+            source.display(),
+            witness.len()
+        )));
+    }
+    check_witness_values(r1cs, witness)
+}
+
+/// Checks input names, arities and tensor shapes against the circuit's `.sym` file,
+/// before witness generation; `json` is the inputs object `inputs` was parsed from
+///
+/// Inputs naming no signal are rejected or, under `UnknownInputs::Ignore`, dropped
+/// with a warning. Skipped for circuits compiled without `--sym`, where the
+/// calculator remains the only check.
+pub fn check_inputs(
+    paths: &CircuitPaths,
+    json: &Map<String, Value>,
+    inputs: &mut Vec<(String, Fr)>,
+    unknown: UnknownInputs,
+) -> Result<(), PipelineError> {
+    if !paths.sym.exists() {
+        debug!("no symbol file, skipping the input check");
+        return Ok(());
+    }
+    let signals =
+        template::read_input_signals(paths).map_err(|e| PipelineError::Load(e.to_string()))?;
+    let ignored = inputs::check_signals(inputs, json, &signals, unknown)?;
+    if !ignored.is_empty() {
+        warn!(
+            "Ignoring inputs that are not input signals of the circuit: {}",
+            ignored.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Like `generate_witness`, but reuses a witness cached for the same wasm and inputs
+///
+/// On a hit only the R1CS is loaded and the witness is checked against it as usual.
+/// Failures to read or fill the cache fall back to running the calculator.
+pub fn generate_witness_cached(
+    paths: &CircuitPaths,
+    inputs: &[(String, Fr)],
+    cache: Option<&WitnessCache>,
+) -> Result<CircomCircuit<Fr>, PipelineError> {
+    let Some(cache) = cache else {
+        return generate_witness(load_config(paths)?, inputs);
+    };
+    let key = match WitnessCache::key(&paths.wasm, inputs) {
+        Ok(key) => key,
+        Err(e) => {
+            warn!("Witness cache disabled: {e}");
+            return generate_witness(load_config(paths)?, inputs);
+        }
+    };
+
+    if let Some(witness) = cache.get(&key) {
+        debug!(key, "reusing cached witness");
+        let circom = CircomCircuit {
+            r1cs: load_r1cs(&paths.r1cs)?,
+            witness: Some(witness),
+        };
+        check_witness(&circom)?;
+        return Ok(circom);
+    }
+
+    let circom = generate_witness(load_config(paths)?, inputs)?;
+    if let Some(witness) = &circom.witness
+        && let Err(e) = cache.put(&key, witness)
+    {
+        warn!("Failed to cache witness: {e}");
+    }
+    Ok(circom)
+}
+
+/// Computes witnesses of one circuit for many inputs, as `batch-prove` does
+///
+/// The R1CS is parsed, and the wasm calculator instantiated, once up front. Each
+/// witness then only runs the calculator, or comes from the witness cache, and is
+/// checked against the R1CS like any other.
+pub struct WitnessGenerator {
+    source: WitnessSource,
+    paths: CircuitPaths,
+    r1cs: R1CS<Fr>,
+    /// The wasm calculator, its R1CS stripped of the constraints so that the copy
+    /// each witness consumes stays cheap
+    calculator: Option<CircomConfig<Fr>>,
+    cache: Option<WitnessCache>,
+}
+
+impl WitnessGenerator {
+    /// Loads the circuit for `source`; only the wasm calculator uses `cache`
+    pub fn new(
+        source: WitnessSource,
+        paths: &CircuitPaths,
+        cache: Option<WitnessCache>,
+    ) -> Result<Self, PipelineError> {
+        let (r1cs, calculator) = match source {
+            WitnessSource::Wasm => {
+                let mut config = load_config(paths)?;
+                let r1cs = R1CS {
+                    num_inputs: config.r1cs.num_inputs,
+                    num_aux: config.r1cs.num_aux,
+                    num_variables: config.r1cs.num_variables,
+                    constraints: std::mem::take(&mut config.r1cs.constraints),
+                    wire_mapping: config.r1cs.wire_mapping.clone(),
+                };
+                (r1cs, Some(config))
+            }
+            WitnessSource::Cpp => (load_r1cs(&paths.r1cs)?, None),
+        };
+        Ok(Self {
+            source,
+            paths: paths.clone(),
+            r1cs,
+            calculator,
+            cache,
+        })
+    }
+
+    /// The R1CS every witness is for
+    pub fn r1cs(&self) -> &R1CS<Fr> {
+        &self.r1cs
+    }
+
+    /// The witness for `inputs`, checked against the R1CS; unless `cached`, the
+    /// witness cache is neither read nor written, as for secret inputs
+    pub fn witness(&self, inputs: &[(String, Fr)], cached: bool) -> Result<Vec<Fr>, PipelineError> {
+        let witness = match &self.calculator {
+            Some(config) => self.calculate_wasm(config, inputs, cached)?,
+            None => calculate_cpp(&self.paths, inputs)?,
+        };
+        let source = match self.source {
+            WitnessSource::Wasm => &self.paths.wasm,
+            WitnessSource::Cpp => &self.paths.cpp,
+        };
+        check_loaded_witness(&self.r1cs, &witness, source)?;
+        Ok(witness)
+    }
+
+    fn calculate_wasm(
+        &self,
+        config: &CircomConfig<Fr>,
+        inputs: &[(String, Fr)],
+        cached: bool,
+    ) -> Result<Vec<Fr>, PipelineError> {
+        let calculate = || {
+            let mut builder = CircomBuilder::new(config.clone());
+            for (key, value) in inputs {
+                builder.push_input(key, BigUint::from(value.into_bigint()));
+            }
+            builder
+                .build()
+                .map_err(|e| PipelineError::Witness(e.to_string()))?
+                .witness
+                .ok_or_else(|| PipelineError::Witness("no witness was produced".into()))
+        };
+        let Some(cache) = self.cache.as_ref().filter(|_| cached) else {
+            return calculate();
+        };
+        let key = match WitnessCache::key(&self.paths.wasm, inputs) {
+            Ok(key) => key,
+            Err(e) => {
+                warn!("Witness cache disabled: {e}");
+                return calculate();
+            }
+        };
+        if let Some(witness) = cache.get(&key) {
+            debug!(key, "reusing cached witness");
+            return Ok(witness);
+        }
+        let witness = calculate()?;
+        if let Err(e) = cache.put(&key, &witness) {
+            warn!("Failed to cache witness: {e}");
+        }
+        Ok(witness)
+    }
+}
+
+/// Like `conversion::circom_to_sparse`, but reuses a circuit cached for the same
+/// `.r1cs` file, simplification `passes` and committed variables
+///
+/// On a hit only the witness is placed into the cached circuit. Failures to read or
+/// fill the cache fall back to converting, as the witness cache does.
+pub fn convert_cached(
+    circom: &CircomCircuit<Fr>,
+    committed: &[usize],
+    paths: &CircuitPaths,
+    passes: &[&str],
+    cache: Option<&ConversionCache>,
+) -> Result<(SparseCircuit<Fr>, Witness<Fr>, ConversionReport), PipelineError> {
+    if circom.witness.is_none() {
+        return Err(ConversionError::MissingWitness.into());
+    }
+    let (circuit, report) = convert_r1cs_cached(&circom.r1cs, committed, paths, passes, cache)?;
+    let witness = conversion::convert_witness(circom, committed)?;
+    Ok((circuit, witness, report))
+}
+
+/// The circuit half of `convert_cached`, for placing many witnesses into one
+/// circuit with `conversion::convert_witness`
+pub fn convert_r1cs_cached(
+    r1cs: &R1CS<Fr>,
+    committed: &[usize],
+    paths: &CircuitPaths,
+    passes: &[&str],
+    cache: Option<&ConversionCache>,
+) -> Result<(SparseCircuit<Fr>, ConversionReport), PipelineError> {
+    let convert = || -> Result<_, PipelineError> {
+        let circuit = conversion::r1cs_to_sparse(r1cs, committed)?;
+        Ok((circuit, ConversionReport::committing(r1cs, committed)))
+    };
+    let Some(cache) = cache else {
+        return convert();
+    };
+    let key = match ConversionCache::key(&paths.r1cs, passes, committed) {
+        Ok(key) => key,
+        Err(e) => {
+            warn!("Conversion cache disabled: {e}");
+            return convert();
+        }
+    };
+
+    if let Some(cached) = cache.get(&key) {
+        debug!(key, "reusing cached circuit");
+        return Ok(cached);
+    }
+
+    let (circuit, report) = convert()?;
+    if let Err(e) = cache.put(&key, &circuit, &report) {
+        warn!("Failed to cache converted circuit: {e}");
+    }
+    Ok((circuit, report))
+}
+
+/// How many violated constraints `check_witness` reports with their values
+pub const REPORTED_FAILURES: usize = 5;
+
+/// Checks the witness against the original R1CS, before any conversion
+///
+/// The wasm calculator happily produces witnesses violating `===` constraints, so
+/// this separates bad inputs or circuit bugs from failures to compute a witness.
+/// The error carries the first `REPORTED_FAILURES` violations with `A·w`, `B·w`
+/// and `C·w` evaluated, so they can be traced back to the circuit source.
+pub fn check_witness(circom: &CircomCircuit<Fr>) -> Result<(), PipelineError> {
+    check_witness_values(&circom.r1cs, circom.witness.as_deref().unwrap_or_default())
+}
+
+/// `check_witness` for a witness held apart from its R1CS
+pub fn check_witness_values(r1cs: &R1CS<Fr>, witness: &[Fr]) -> Result<(), PipelineError> {
+    let values = r1cs::variable_values(r1cs, witness)?;
+    // Every constant term is scaled by this wire, so anything but 1 shifts them all
+    if values
+        .get(r1cs::ONE_WIRE)
+        .is_some_and(|one| *one != Fr::from(1u64))
+    {
+        return Err(PipelineError::ConstantWire);
+    }
+    let mut failures = r1cs::failed_constraints(r1cs, &values);
+    let first: Vec<_> = failures.by_ref().take(REPORTED_FAILURES).collect();
+    if !first.is_empty() {
+        return Err(PipelineError::UnsatisfiedConstraints {
+            failed: first.len() + failures.count(),
+            total: r1cs.constraints.len(),
+            first,
+        });
+    }
+    Ok(())
+}
+
+/// Checks a converted circuit against its witness, logging each failed row and gate
+/// with the R1CS constraint it came from
+///
+/// The constraint's signals are named from the circuit's `.sym` file when there is
+/// one, through `elimination` when that pass renumbered the variables, so a failure
+/// can be found in the circom source. Only the first `REPORTED_FAILURES` are logged.
+pub fn check_converted(
+    circom: &CircomCircuit<Fr>,
+    circuit: &SparseCircuit<Fr>,
+    witness: &Witness<Fr>,
+    paths: &CircuitPaths,
+    elimination: Option<&Elimination>,
+) -> Result<(), PipelineError> {
+    if circuit.is_satisfied_by(witness) {
+        return Ok(());
+    }
+    let symbols = match paths.sym.exists().then(|| SymbolTable::read(&paths.sym)) {
+        Some(Ok(symbols)) => symbols,
+        Some(Err(e)) => {
+            warn!("Failed to read {}: {e}", paths.sym.display());
+            SymbolTable::default()
+        }
+        None => SymbolTable::default(),
+    };
+    let names = symbols.names_by_variable();
+    let names = match elimination {
+        Some(elimination) => elimination.rename(&names),
+        None => names,
+    };
+    let failures = locate::failures(&circom.r1cs, circuit, witness);
+    for failure in failures.iter().take(REPORTED_FAILURES) {
+        error!("Converted circuit fails {}", failure.describe(&names));
+    }
+    if failures.len() > REPORTED_FAILURES {
+        error!(
+            "... and {} more failed rows and gates",
+            failures.len() - REPORTED_FAILURES
+        );
+    }
+    Err(PipelineError::Unsatisfied)
+}
+
+/// Public signals (outputs, then public inputs) as decimal strings
+///
+/// Circom lays these out right after the constant-one wire.
+pub fn public_signals(circom: &CircomCircuit<Fr>) -> Vec<String> {
+    let num_public = circom.r1cs.num_inputs.saturating_sub(1);
+    circom
+        .witness
+        .iter()
+        .flat_map(|witness| witness.iter().skip(r1cs::ONE_WIRE + 1).take(num_public))
+        .map(|value| value.into_bigint().to_string())
+        .collect()
+}
+
+/// Samples a CRS large enough for the (power-of-2 padded) circuit
+pub fn setup<R: RngCore + CryptoRng>(
+    circuit: &Circuit<Fr>,
+    rng: &mut R,
+) -> CircuitCRS<G1Projective> {
+    CircuitCRS::rand(circuit.dim(), rng)
+}
+
+/// Fiat-Shamir domain separator for a statement of `num_commitments` points
+pub fn domain_separator(num_commitments: usize, crs_size: usize) -> DomainSeparator {
+    let ds = DomainSeparator::new(DOMAIN_LABEL);
+    let ds =
+        CircuitProofDomainSeparator::<G1Projective>::circuit_proof_statement(ds, num_commitments)
+            .ratchet();
+    CircuitProofDomainSeparator::<G1Projective>::add_circuit_proof(ds, crs_size)
+}
+
+/// Commits to the witness and produces a proof of circuit satisfaction
+pub fn prove<R: RngCore + CryptoRng>(
+    crs: &CircuitCRS<G1Projective>,
+    circuit: &Circuit<Fr>,
+    witness: &Witness<Fr>,
+    rng: &mut R,
+) -> Result<(CircuitStatement<G1Projective>, Vec<u8>), PipelineError> {
+    if !circuit.is_satisfied_by(witness) {
+        return Err(PipelineError::Unsatisfied);
+    }
+
+    let statement = CircuitStatement::new(crs, witness);
+    let domain_separator = domain_separator(statement.v.len(), circuit.dim());
+
+    let mut prover_state = domain_separator.to_prover_state();
+    prover_state
+        .public_points(&statement.v)
+        .map_err(|e| PipelineError::Proving(e.to_string()))?;
+    prover_state
+        .ratchet()
+        .map_err(|e| PipelineError::Proving(e.to_string()))?;
+    let proof = circuit_prove(&mut prover_state, crs, circuit, witness, rng)
+        .map_err(|e| PipelineError::Proving(e.to_string()))?;
+
+    debug!(
+        commitments = statement.v.len(),
+        proof_size = proof.len(),
+        "generated proof"
+    );
+    Ok((statement, proof.to_vec()))
+}
+
+/// How often proving is attempted before a transient failure is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first; at least 1
+    pub max_attempts: usize,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 3 }
+    }
+}
+
+impl RetryPolicy {
+    /// Runs `attempt` until it succeeds, fails permanently or the attempts run out,
+    /// returning its result and the number of attempts made
+    ///
+    /// Only errors for which `PipelineError::is_transient` holds are retried.
+    pub fn run<T>(
+        &self,
+        mut attempt: impl FnMut() -> Result<T, PipelineError>,
+    ) -> (Result<T, PipelineError>, usize) {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match attempt() {
+                Err(e) if e.is_transient() && attempts < self.max_attempts => {
+                    warn!(
+                        "Attempt {attempts} of {} failed, retrying: {e}",
+                        self.max_attempts
+                    );
+                }
+                result => return (result, attempts),
+            }
+        }
+    }
+}
+
+/// `prove` under a retry policy; every attempt draws fresh randomness from `rng`
+pub fn prove_with_retry<R: RngCore + CryptoRng>(
+    crs: &CircuitCRS<G1Projective>,
+    circuit: &Circuit<Fr>,
+    witness: &Witness<Fr>,
+    rng: &mut R,
+    policy: RetryPolicy,
+) -> (
+    Result<(CircuitStatement<G1Projective>, Vec<u8>), PipelineError>,
+    usize,
+) {
+    policy.run(|| prove(crs, circuit, witness, rng))
+}
+
+/// Checks a proof against the public statement
+pub fn verify<R: RngCore + CryptoRng>(
+    crs: &CircuitCRS<G1Projective>,
+    circuit: &Circuit<Fr>,
+    statement: &CircuitStatement<G1Projective>,
+    proof: &[u8],
+    rng: &mut R,
+) -> Result<(), PipelineError> {
+    verify_timed(crs, circuit, statement, proof, rng).map(|_| ())
+}
+
+/// Time spent in the two halves of `verify`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VerifyTimings {
+    /// Replaying the transcript up to the proof: domain separator and statement points
+    pub transcript: Duration,
+    /// Checking the proof, dominated by the verifier's multi-scalar multiplication
+    pub check: Duration,
+}
+
+/// `verify`, also reporting where the time went
+pub fn verify_timed<R: RngCore + CryptoRng>(
+    crs: &CircuitCRS<G1Projective>,
+    circuit: &Circuit<Fr>,
+    statement: &CircuitStatement<G1Projective>,
+    proof: &[u8],
+    rng: &mut R,
+) -> Result<VerifyTimings, PipelineError> {
+    let start = Instant::now();
+    let domain_separator = domain_separator(statement.v.len(), circuit.dim());
+
+    let mut verifier_state = domain_separator.to_verifier_state(proof);
+    verifier_state
+        .public_points(&statement.v)
+        .map_err(|e| PipelineError::Verification(e.to_string()))?;
+    verifier_state
+        .ratchet()
+        .map_err(|e| PipelineError::Verification(e.to_string()))?;
+    let transcript = start.elapsed();
+
+    let start = Instant::now();
+    circuit_verify(&mut verifier_state, crs, circuit, statement, rng)
+        .map_err(|e| PipelineError::Verification(e.to_string()))?;
+    Ok(VerifyTimings {
+        transcript,
+        check: start.elapsed(),
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PipelineError {
+    #[error(transparent)]
+    Input(#[from] InputError),
+    #[error("Failed to load circuit: {0}")]
+    Load(String),
+    #[error(transparent)]
+    Curve(#[from] CurveError),
+    #[error("Witness generation failed: {0}")]
+    Witness(String),
+    #[error(transparent)]
+    Conversion(#[from] ConversionError),
+    #[error("Witness does not assign 1 to the constant wire {}", r1cs::ONE_WIRE)]
+    ConstantWire,
+    #[error(transparent)]
+    WireMapping(#[from] WireMappingError),
+    #[error(
+        "Witness violates {failed} of {total} R1CS constraints: {}{}",
+        first.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "),
+        if *failed > first.len() { "; ..." } else { "" }
+    )]
+    UnsatisfiedConstraints {
+        failed: usize,
+        total: usize,
+        /// The first violated constraints, in constraint order
+        first: Vec<FailedConstraint<Fr>>,
+    },
+    #[error("Converted circuit not satisfied by witness")]
+    Unsatisfied,
+    #[error("Proving failed: {0}")]
+    Proving(String),
+    #[error("Verification failed: {0}")]
+    Verification(String),
+}
+
+impl PipelineError {
+    /// Short machine-readable name of the stage that failed
+    pub fn category(&self) -> &'static str {
+        match self {
+            PipelineError::Input(_) => "input",
+            PipelineError::Load(_) | PipelineError::Curve(_) => "load",
+            PipelineError::Witness(_)
+            | PipelineError::ConstantWire
+            | PipelineError::WireMapping(_) => "witness",
+            PipelineError::Conversion(_) => "conversion",
+            PipelineError::UnsatisfiedConstraints { .. } | PipelineError::Unsatisfied => {
+                "unsatisfied"
+            }
+            PipelineError::Proving(_) => "proving",
+            PipelineError::Verification(_) => "verification",
+        }
+    }
+
+    /// Whether the failure may not recur with fresh randomness: errors raised while
+    /// running the prover and its transcript. Bad circuits, witnesses and proofs are
+    /// never transient.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, PipelineError::Proving(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crs::CrsParams;
+    use ark_ff::One;
+    use rand::rngs::OsRng;
+
+    /// circuits/constants.circom: `x * (x - 3) === 0`, `out <== x * x` over wires [1, out, x]
+    #[test]
+    fn circuit_without_inputs_proves() {
+        let mut inputs = inputs::parse_inputs("{}", true).unwrap();
+        assert!(inputs.is_empty());
+        inputs::check_signals(&mut inputs, &Default::default(), &[], UnknownInputs::Error).unwrap();
+
+        let one = Fr::one();
+        let circom = CircomCircuit {
+            r1cs: R1CS {
+                num_inputs: 2,
+                num_aux: 1,
+                num_variables: 3,
+                constraints: vec![
+                    (vec![(2, one)], vec![(2, one), (0, -Fr::from(3u64))], vec![]),
+                    (vec![(2, one)], vec![(2, one)], vec![(1, one)]),
+                ],
+                wire_mapping: None,
+            },
+            witness: Some([1u64, 9, 3].map(Fr::from).to_vec()),
+        };
+        check_witness(&circom).unwrap();
+        assert_eq!(public_signals(&circom), ["9"]);
+
+        let (circuit, witness, _) = crate::conversion::circom_to_bulletproofs(&circom).unwrap();
+        let crs = CrsParams::new(circuit.dim()).derive();
+        let (statement, proof) = prove(&crs, &circuit, &witness, &mut OsRng).unwrap();
+        verify(&crs, &circuit, &statement, &proof, &mut OsRng).unwrap();
+    }
+
+    /// `out <-- 5` without constraints, and a template without signals
+    #[test]
+    fn degenerate_circuits_prove() {
+        for (num_inputs, witness) in [(2, vec![1u64, 5]), (1, vec![1])] {
+            let circom = CircomCircuit {
+                r1cs: R1CS {
+                    num_inputs,
+                    num_aux: 0,
+                    num_variables: witness.len(),
+                    constraints: vec![],
+                    wire_mapping: None,
+                },
+                witness: Some(witness.into_iter().map(Fr::from).collect()),
+            };
+            check_witness(&circom).unwrap();
+
+            let (circuit, witness, _) = crate::conversion::circom_to_bulletproofs(&circom).unwrap();
+            assert_eq!(circuit.dim(), 1);
+            let crs = CrsParams::new(circuit.dim()).derive();
+            let (statement, proof) = prove(&crs, &circuit, &witness, &mut OsRng).unwrap();
+            assert_eq!(statement.v.len(), num_inputs - 1);
+            verify(&crs, &circuit, &statement, &proof, &mut OsRng).unwrap();
+        }
+    }
+
+    /// One conversion of `out <== x * x` holds every witness placed into it
+    #[test]
+    fn converted_circuits_take_many_witnesses() {
+        let one = Fr::one();
+        let r1cs = R1CS {
+            num_inputs: 2,
+            num_aux: 1,
+            num_variables: 3,
+            constraints: vec![(vec![(2, one)], vec![(2, one)], vec![(1, one)])],
+            wire_mapping: None,
+        };
+        let paths = CircuitPaths::new(std::env::temp_dir(), "square");
+        let (circuit, report) = convert_r1cs_cached(&r1cs, &[], &paths, &[], None).unwrap();
+        let mut circom = CircomCircuit {
+            r1cs,
+            witness: None,
+        };
+        for x in [3u64, 5] {
+            circom.witness = Some([1, x * x, x].map(Fr::from).to_vec());
+            let expected = crate::conversion::circom_to_sparse(&circom, &[]).unwrap();
+            let witness = crate::conversion::convert_witness(&circom, &[]).unwrap();
+            assert!(circuit.is_satisfied_by(&witness));
+            assert_eq!((&circuit, &report), (&expected.0, &expected.2));
+        }
+    }
+
+    /// `out <== x * x` over wires [1, out, x], with the witness claiming 4 * 4 = 9
+    #[test]
+    fn unsatisfied_witness_reports_failing_values() {
+        let one = Fr::one();
+        let circom = CircomCircuit {
+            r1cs: R1CS {
+                num_inputs: 2,
+                num_aux: 1,
+                num_variables: 3,
+                constraints: vec![(vec![(2, one)], vec![(2, one)], vec![(1, one)]); 7],
+                wire_mapping: None,
+            },
+            witness: Some([1u64, 9, 4].map(Fr::from).to_vec()),
+        };
+        let Err(PipelineError::UnsatisfiedConstraints {
+            failed,
+            total,
+            first,
+        }) = check_witness(&circom)
+        else {
+            panic!("expected unsatisfied constraints");
+        };
+        assert_eq!((failed, total, first.len()), (7, 7, REPORTED_FAILURES));
+        assert_eq!(
+            first.iter().map(|failed| failed.index).collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4]
+        );
+        let message = check_witness(&circom).unwrap_err().to_string();
+        assert!(
+            message.contains("constraint 0: 4 * 4 = 16, expected 9; constraint 1:"),
+            "{message}"
+        );
+        assert!(message.ends_with("; ..."), "{message}");
+    }
+
+    #[test]
+    fn retries_only_transient_failures() {
+        let policy = RetryPolicy { max_attempts: 3 };
+        let mut failures = 2;
+        let (result, attempts) = policy.run(|| {
+            if failures > 0 {
+                failures -= 1;
+                return Err(PipelineError::Proving("transcript I/O".into()));
+            }
+            Ok(())
+        });
+        assert!(result.is_ok());
+        assert_eq!(attempts, 3);
+
+        let (result, attempts) = policy.run(|| Err::<(), _>(PipelineError::Proving("rng".into())));
+        assert!(matches!(result, Err(PipelineError::Proving(_))));
+        assert_eq!(attempts, 3);
+
+        let (result, attempts) = policy.run(|| Err::<(), _>(PipelineError::Unsatisfied));
+        assert!(matches!(result, Err(PipelineError::Unsatisfied)));
+        assert_eq!(attempts, 1);
+    }
+}
@@ -0,0 +1,78 @@
+use std::{fmt, str::FromStr};
+
+/// Verifier strictness, bundling the optional checks behind one choice
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum VerifierProfile {
+    /// Checks every bundle against the circuit and whatever the caller supplies
+    #[default]
+    Standard,
+    /// Also insists on fingerprinted bundles, well-formed statements and the
+    /// expected CRS seed
+    Strict,
+    /// Also requires signed params and re-derives the CRS for every bundle
+    Paranoid,
+}
+
+/// The optional checks a profile enables
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifierChecks {
+    /// Reject bundles that carry no circuit fingerprint
+    pub require_fingerprint: bool,
+    /// Reject statements whose number of commitments does not match the circuit
+    pub check_statement_shape: bool,
+    /// Only accept CRS parameters derived from the expected seed, not one picked by the prover
+    pub require_expected_seed: bool,
+    /// Refuse to verify without a signed params manifest
+    pub require_signed_params: bool,
+    /// Derive the CRS again for every bundle instead of sharing a cached one
+    pub rederive_crs: bool,
+}
+
+impl VerifierProfile {
+    pub const ALL: [VerifierProfile; 3] = [
+        VerifierProfile::Standard,
+        VerifierProfile::Strict,
+        VerifierProfile::Paranoid,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            VerifierProfile::Standard => "standard",
+            VerifierProfile::Strict => "strict",
+            VerifierProfile::Paranoid => "paranoid",
+        }
+    }
+
+    pub fn checks(self) -> VerifierChecks {
+        let strict = self != VerifierProfile::Standard;
+        let paranoid = self == VerifierProfile::Paranoid;
+        VerifierChecks {
+            require_fingerprint: strict,
+            check_statement_shape: strict,
+            require_expected_seed: strict,
+            require_signed_params: paranoid,
+            rederive_crs: paranoid,
+        }
+    }
+}
+
+impl fmt::Display for VerifierProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for VerifierProfile {
+    type Err = UnknownProfile;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        VerifierProfile::ALL
+            .into_iter()
+            .find(|profile| profile.name() == s)
+            .ok_or_else(|| UnknownProfile(s.to_string()))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown verifier profile '{0}', expected standard, strict or paranoid")]
+pub struct UnknownProfile(pub String);
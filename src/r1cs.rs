@@ -0,0 +1,323 @@
+use std::{collections::HashMap, fmt};
+
+use ark_circom::circom::R1CS;
+use ark_ff::PrimeField;
+
+/// Variable of the constant `1`, which circom reserves in every circuit
+///
+/// Constraints use it for constant terms, so `a + 5 === b` reads
+/// `0 = a + 5·w[ONE_WIRE] - b`. It is public, fixed, and never blinded.
+pub const ONE_WIRE: usize = 0;
+
+/// Witness values in R1CS variable order
+///
+/// Circuits compiled with simplification carry a `wire_mapping` from variables to
+/// witness positions; unoptimized circuits use the witness as-is. The mapping is
+/// checked with `check_wire_mapping`, and a witness too short for it, or for the
+/// variables of an unmapped circuit, is an error rather than read as zeros.
+pub fn variable_values<F: PrimeField>(
+    r1cs: &R1CS<F>,
+    witness: &[F],
+) -> Result<Vec<F>, WireMappingError> {
+    let position = |variable: usize, wire: usize| {
+        witness
+            .get(wire)
+            .copied()
+            .ok_or(WireMappingError::OutOfRange {
+                variable,
+                wire,
+                len: witness.len(),
+            })
+    };
+    match &r1cs.wire_mapping {
+        Some(wire_mapping) => {
+            check_wire_mapping(r1cs)?;
+            wire_mapping
+                .iter()
+                .enumerate()
+                .map(|(variable, &wire)| position(variable, wire))
+                .collect()
+        }
+        None => (0..r1cs.num_variables)
+            .map(|variable| position(variable, variable))
+            .collect(),
+    }
+}
+
+/// Checks that a `wire_mapping` sends every variable to its own witness position,
+/// the constant wire to position 0
+///
+/// Circuits compiled with `-O1` or `-O2` drop signals and renumber the rest, so
+/// the mapping is all that ties the constraints to the witness; a mapping that
+/// disagrees with the R1CS would silently prove the wrong values.
+pub fn check_wire_mapping<F: PrimeField>(r1cs: &R1CS<F>) -> Result<(), WireMappingError> {
+    let Some(wire_mapping) = &r1cs.wire_mapping else {
+        return Ok(());
+    };
+    if wire_mapping.len() != r1cs.num_variables {
+        return Err(WireMappingError::Length {
+            mapped: wire_mapping.len(),
+            variables: r1cs.num_variables,
+        });
+    }
+    if let Some(&wire) = wire_mapping.get(ONE_WIRE)
+        && wire != ONE_WIRE
+    {
+        return Err(WireMappingError::ConstantWire(wire));
+    }
+    let mut variable_of = HashMap::with_capacity(wire_mapping.len());
+    for (variable, &wire) in wire_mapping.iter().enumerate() {
+        if let Some(first) = variable_of.insert(wire, variable) {
+            return Err(WireMappingError::Duplicate {
+                first,
+                second: variable,
+                wire,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// A `wire_mapping` or witness that doesn't fit the R1CS
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum WireMappingError {
+    #[error("Wire mapping covers {mapped} variables but the R1CS has {variables}")]
+    Length { mapped: usize, variables: usize },
+    #[error("Wire mapping sends the constant wire to witness position {0} instead of 0")]
+    ConstantWire(usize),
+    #[error(
+        "Wire mapping sends variables {first} and {second} to the same witness position {wire}"
+    )]
+    Duplicate {
+        first: usize,
+        second: usize,
+        wire: usize,
+    },
+    #[error("Variable {variable} reads witness position {wire}, past the {len} witness values")]
+    OutOfRange {
+        variable: usize,
+        wire: usize,
+        len: usize,
+    },
+}
+
+/// Evaluates a linear combination over the variable values
+pub fn evaluate<F: PrimeField>(lc: &[(usize, F)], values: &[F]) -> F {
+    lc.iter()
+        .map(|&(var_idx, coeff)| coeff * values.get(var_idx).copied().unwrap_or(F::zero()))
+        .sum()
+}
+
+/// Whether a constraint with sides `a` and `b` is purely linear
+///
+/// An empty (or all-zero) A or B side makes the product vanish, so the
+/// constraint reads `0 = C·w`; circom's -O0 output emits every linear
+/// constraint this way.
+pub fn is_linear<F: PrimeField>(a: &[(usize, F)], b: &[(usize, F)]) -> bool {
+    [a, b]
+        .iter()
+        .any(|lc| lc.iter().all(|(_, coeff)| coeff.is_zero()))
+}
+
+/// Indices of the constraints `A·w ⊙ B·w = C·w` violated by the variable values
+pub fn unsatisfied_constraints<F: PrimeField>(r1cs: &R1CS<F>, values: &[F]) -> Vec<usize> {
+    failed_constraints(r1cs, values)
+        .map(|failed| failed.index)
+        .collect()
+}
+
+/// The constraints violated by the variable values, with their sides evaluated
+pub fn failed_constraints<'a, F: PrimeField>(
+    r1cs: &'a R1CS<F>,
+    values: &'a [F],
+) -> impl Iterator<Item = FailedConstraint<F>> + 'a {
+    r1cs.constraints
+        .iter()
+        .enumerate()
+        .filter_map(|(index, (a, b, c))| {
+            let (a, b, c) = (
+                evaluate(a, values),
+                evaluate(b, values),
+                evaluate(c, values),
+            );
+            (a * b != c).then_some(FailedConstraint { index, a, b, c })
+        })
+}
+
+/// A violated constraint and the values of `A·w`, `B·w` and `C·w`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailedConstraint<F> {
+    pub index: usize,
+    pub a: F,
+    pub b: F,
+    pub c: F,
+}
+
+impl<F: PrimeField> fmt::Display for FailedConstraint<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "constraint {}: {} * {} = {}, expected {}",
+            self.index,
+            signed(self.a),
+            signed(self.b),
+            signed(self.a * self.b),
+            signed(self.c)
+        )
+    }
+}
+
+/// Renders a field element as the signed integer closest to zero, as circom does for negatives
+pub fn signed<F: PrimeField>(x: F) -> String {
+    if x.is_zero() || x.into_bigint() <= F::MODULUS_MINUS_ONE_DIV_TWO {
+        x.to_string()
+    } else {
+        format!("-{}", -x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+
+    /// `c <== a * b` and `a + b === s` over wires [1, c, a, b, s]
+    fn r1cs() -> R1CS<Fr> {
+        let one = Fr::from(1u64);
+        R1CS {
+            num_inputs: 2,
+            num_aux: 3,
+            num_variables: 5,
+            constraints: vec![
+                (vec![(2, one)], vec![(3, one)], vec![(1, one)]),
+                (vec![], vec![], vec![(2, one), (3, one), (4, -one)]),
+            ],
+            wire_mapping: None,
+        }
+    }
+
+    fn values(xs: &[u64]) -> Vec<Fr> {
+        xs.iter().copied().map(Fr::from).collect()
+    }
+
+    #[test]
+    fn satisfied_witness() {
+        assert!(unsatisfied_constraints(&r1cs(), &values(&[1, 33, 3, 11, 14])).is_empty());
+    }
+
+    #[test]
+    fn failed_constraints_carry_evaluated_sides() {
+        let failed: Vec<_> = failed_constraints(&r1cs(), &values(&[1, 34, 3, 11, 15])).collect();
+        assert_eq!(
+            failed[1],
+            FailedConstraint {
+                index: 1,
+                a: Fr::from(0u64),
+                b: Fr::from(0u64),
+                c: -Fr::from(1u64),
+            }
+        );
+        assert_eq!(
+            failed[0].to_string(),
+            "constraint 0: 3 * 11 = 33, expected 34"
+        );
+        assert_eq!(
+            failed[1].to_string(),
+            "constraint 1: 0 * 0 = 0, expected -1"
+        );
+    }
+
+    #[test]
+    fn reports_each_violated_constraint() {
+        assert_eq!(
+            unsatisfied_constraints(&r1cs(), &values(&[1, 34, 3, 11, 14])),
+            vec![0]
+        );
+        assert_eq!(
+            unsatisfied_constraints(&r1cs(), &values(&[1, 34, 3, 11, 15])),
+            vec![0, 1]
+        );
+    }
+
+    /// `(a + 2) * (b - 1) === c + 7` over wires [1, c, a, b], all offsets on wire 0
+    #[test]
+    fn constant_terms_use_the_one_wire() {
+        let one = Fr::from(1u64);
+        let r1cs = R1CS {
+            num_inputs: 2,
+            num_aux: 2,
+            num_variables: 4,
+            constraints: vec![(
+                vec![(2, one), (ONE_WIRE, Fr::from(2u64))],
+                vec![(3, one), (ONE_WIRE, -one)],
+                vec![(1, one), (ONE_WIRE, Fr::from(7u64))],
+            )],
+            wire_mapping: None,
+        };
+        // (3 + 2) * (11 - 1) = 43 + 7
+        assert!(unsatisfied_constraints(&r1cs, &values(&[1, 43, 3, 11])).is_empty());
+        // The constant is only a constant while wire 0 holds 1
+        assert_eq!(
+            unsatisfied_constraints(&r1cs, &values(&[2, 43, 3, 11])),
+            vec![0]
+        );
+    }
+
+    #[test]
+    fn linear_constraints_have_an_empty_side() {
+        let [multiplication, linear] = r1cs().constraints.try_into().unwrap();
+        assert!(!is_linear(&multiplication.0, &multiplication.1));
+        assert!(is_linear(&linear.0, &linear.1));
+        assert!(is_linear(&[(2, Fr::from(0u64))], &multiplication.1));
+    }
+
+    #[test]
+    fn wire_mapping_reorders_witness() {
+        let mut r1cs = r1cs();
+        r1cs.wire_mapping = Some(vec![0, 1, 3, 4, 5]);
+        let values = variable_values(&r1cs, &values(&[1, 33, 0, 3, 11, 14])).unwrap();
+        assert_eq!(values, self::values(&[1, 33, 3, 11, 14]));
+    }
+
+    #[test]
+    fn inconsistent_wire_mappings_are_rejected() {
+        let witness = values(&[1, 33, 0, 3, 11, 14]);
+        let mut r1cs = r1cs();
+        for (mapping, error) in [
+            (
+                vec![0, 1, 3, 4],
+                WireMappingError::Length {
+                    mapped: 4,
+                    variables: 5,
+                },
+            ),
+            (vec![2, 1, 3, 4, 5], WireMappingError::ConstantWire(2)),
+            (
+                vec![0, 1, 3, 3, 5],
+                WireMappingError::Duplicate {
+                    first: 2,
+                    second: 3,
+                    wire: 3,
+                },
+            ),
+            (
+                vec![0, 1, 3, 4, 6],
+                WireMappingError::OutOfRange {
+                    variable: 4,
+                    wire: 6,
+                    len: 6,
+                },
+            ),
+        ] {
+            r1cs.wire_mapping = Some(mapping);
+            assert_eq!(variable_values(&r1cs, &witness), Err(error));
+        }
+
+        // Without a mapping the witness must still cover every variable
+        r1cs.wire_mapping = None;
+        assert!(matches!(
+            variable_values(&r1cs, &witness[..4]),
+            Err(WireMappingError::OutOfRange { variable: 4, .. })
+        ));
+    }
+}
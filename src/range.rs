@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use ark_circom::circom::R1CS;
+use ark_ff::PrimeField;
+use serde::Serialize;
+
+use crate::r1cs::{self, ONE_WIRE};
+use crate::simplify::combine;
+
+/// A bit decomposition as circomlib's `Num2Bits` emits it: a boolean constraint
+/// `b·(b - 1) = 0` per bit and one linear constraint summing the bits with
+/// weights `2^i` against the value
+///
+/// It proves the value lies in `[0, 2^n)` with `n + 1` constraints and `n` bit
+/// variables, the work a native range proof does in logarithmic size.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BitDecomposition {
+    /// The linear constraint `Σ 2^i·b_i = value`, up to a common factor
+    pub sum: usize,
+    /// Bit variables, least significant first
+    pub bits: Vec<usize>,
+    /// The boolean constraint of each bit, in the same order
+    pub booleans: Vec<usize>,
+}
+
+impl BitDecomposition {
+    /// Constraints spent on the range check
+    pub fn constraints(&self) -> usize {
+        self.booleans.len() + 1
+    }
+}
+
+/// Finds the bit decompositions of `r1cs`, in the order of their sum constraints
+///
+/// Boolean constraints are recognized in any scaling or side order, and sums whose
+/// bit terms share a common factor, such as `value - Σ 2^i·b_i`. A sum must weigh
+/// every boolean variable it mentions, at least two, by consecutive powers of two;
+/// each bit belongs to the first sum using it.
+pub fn bit_decompositions<F: PrimeField>(r1cs: &R1CS<F>) -> Vec<BitDecomposition> {
+    let mut booleans = HashMap::new();
+    for (i, (a, b, c)) in r1cs.constraints.iter().enumerate() {
+        if let Some(bit) = boolean_variable(a, b, c) {
+            booleans.entry(bit).or_insert(i);
+        }
+    }
+
+    let mut decompositions = Vec::new();
+    for (i, (a, b, c)) in r1cs.constraints.iter().enumerate() {
+        if !r1cs::is_linear(a, b) {
+            continue;
+        }
+        let terms: Vec<_> = combine(c)
+            .into_iter()
+            .filter(|(v, _)| booleans.contains_key(v))
+            .collect();
+        let weights: HashMap<F, usize> = terms.iter().map(|&(v, coeff)| (coeff, v)).collect();
+        // Bits sharing a weight are no binary expansion
+        if weights.len() < 2 || weights.len() != terms.len() {
+            continue;
+        }
+        // The least significant bit's weight doubles into all the others
+        let Some(bits) = weights.keys().find_map(|&unit| {
+            (0..weights.len())
+                .scan(unit, |weight, _| {
+                    let bit = weights.get(&*weight).copied();
+                    *weight = weight.double();
+                    Some(bit)
+                })
+                .collect::<Option<Vec<_>>>()
+        }) else {
+            continue;
+        };
+        let booleans = bits
+            .iter()
+            .map(|bit| {
+                booleans
+                    .remove(bit)
+                    .expect("weights only hold unclaimed bits")
+            })
+            .collect();
+        decompositions.push(BitDecomposition {
+            sum: i,
+            bits,
+            booleans,
+        });
+    }
+    decompositions
+}
+
+/// The variable `x` of a constraint `α·x ⊙ β·(x - 1) = 0`, sides in either order
+fn boolean_variable<F: PrimeField>(
+    a: &[(usize, F)],
+    b: &[(usize, F)],
+    c: &[(usize, F)],
+) -> Option<usize> {
+    if !combine(c).is_empty() {
+        return None;
+    }
+    match (combine(a).as_slice(), combine(b).as_slice()) {
+        (&[(x, _)], &[(one, offset), (y, scale)]) | (&[(one, offset), (y, scale)], &[(x, _)]) => {
+            (x != ONE_WIRE && one == ONE_WIRE && y == x && offset == -scale).then_some(x)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+
+    fn lc(terms: &[(usize, i64)]) -> Vec<(usize, Fr)> {
+        terms
+            .iter()
+            .map(|&(v, coeff)| (v, Fr::from(coeff)))
+            .collect()
+    }
+
+    /// `Num2Bits(4)` over wires [1, in, b0, b1, b2, b3]
+    fn num2bits() -> R1CS<Fr> {
+        let mut constraints: Vec<_> = (2..6)
+            .map(|b| (lc(&[(b, 1)]), lc(&[(b, 1), (ONE_WIRE, -1)]), lc(&[])))
+            .collect();
+        constraints.push((
+            lc(&[]),
+            lc(&[]),
+            lc(&[(2, 1), (3, 2), (4, 4), (5, 8), (1, -1)]),
+        ));
+        R1CS {
+            num_inputs: 2,
+            num_aux: 4,
+            num_variables: 6,
+            constraints,
+            wire_mapping: None,
+        }
+    }
+
+    #[test]
+    fn finds_num2bits() {
+        let decomposition = BitDecomposition {
+            sum: 4,
+            bits: vec![2, 3, 4, 5],
+            booleans: vec![0, 1, 2, 3],
+        };
+        assert_eq!(decomposition.constraints(), 5);
+        assert_eq!(bit_decompositions(&num2bits()), [decomposition]);
+    }
+
+    #[test]
+    fn finds_scaled_and_reordered_decompositions() {
+        let mut r1cs = num2bits();
+        // (2 - 2·b1) · 3·b1 = 0, and 3·in - Σ 3·2^i·b_i = 0 with the terms shuffled
+        r1cs.constraints[1] = (lc(&[(ONE_WIRE, 2), (3, -2)]), lc(&[(3, 3)]), lc(&[]));
+        r1cs.constraints[4] = (
+            lc(&[]),
+            lc(&[]),
+            lc(&[(5, -24), (1, 3), (3, -6), (2, -3), (4, -12)]),
+        );
+        let decompositions = bit_decompositions(&r1cs);
+        assert_eq!(decompositions.len(), 1);
+        assert_eq!(decompositions[0].bits, [2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn other_weights_are_no_decomposition() {
+        let mut r1cs = num2bits();
+        r1cs.constraints[4].2 = lc(&[(2, 1), (3, 3), (4, 4), (5, 8), (1, -1)]);
+        assert!(bit_decompositions(&r1cs).is_empty());
+        r1cs.constraints[4].2 = lc(&[(2, 1), (3, 1), (4, 2), (5, 4), (1, -1)]);
+        assert!(bit_decompositions(&r1cs).is_empty());
+        // A bit whose boolean constraint is missing breaks the decomposition
+        let mut r1cs = num2bits();
+        r1cs.constraints[2].2 = lc(&[(1, 1)]);
+        assert!(bit_decompositions(&r1cs).is_empty());
+    }
+}
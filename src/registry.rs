@@ -0,0 +1,296 @@
+use std::{
+    fmt,
+    path::Path,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rusqlite::{Connection, OptionalExtension, Row, params};
+
+/// Whether a recorded proof has been checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationStatus {
+    Unverified,
+    Verified,
+    Failed,
+}
+
+impl VerificationStatus {
+    pub fn name(self) -> &'static str {
+        match self {
+            VerificationStatus::Unverified => "unverified",
+            VerificationStatus::Verified => "verified",
+            VerificationStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for VerificationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for VerificationStatus {
+    type Err = RegistryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unverified" => Ok(VerificationStatus::Unverified),
+            "verified" => Ok(VerificationStatus::Verified),
+            "failed" => Ok(VerificationStatus::Failed),
+            other => Err(RegistryError::Corrupt(format!("unknown status '{other}'"))),
+        }
+    }
+}
+
+/// A proof as recorded in the registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofRecord {
+    pub id: i64,
+    pub circuit: String,
+    pub circuit_fingerprint: String,
+    /// Fingerprint of the inputs file the witness was generated from
+    pub input_hash: String,
+    /// File path or URL of the proof artifact
+    pub proof_path: String,
+    /// Seconds since the Unix epoch
+    pub created_at: u64,
+    pub verified_at: Option<u64>,
+    pub status: VerificationStatus,
+}
+
+/// What a prover knows about a proof it just produced
+#[derive(Debug, Clone)]
+pub struct NewProof<'a> {
+    pub circuit: &'a str,
+    pub circuit_fingerprint: &'a str,
+    pub input_hash: &'a str,
+    pub proof_path: &'a str,
+    pub status: VerificationStatus,
+}
+
+/// Local bookkeeping of generated proofs, kept in a sqlite database
+pub struct Registry {
+    conn: Connection,
+}
+
+impl Registry {
+    /// Opens the database at `path`, creating it and its directory on first use
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, RegistryError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// An empty registry that lives only as long as the value
+    pub fn in_memory() -> Result<Self, RegistryError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self, RegistryError> {
+        // Concurrent provers recording at once wait for each other instead of failing
+        conn.busy_timeout(Duration::from_secs(30))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS proofs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                circuit TEXT NOT NULL,
+                circuit_fingerprint TEXT NOT NULL,
+                input_hash TEXT NOT NULL,
+                proof_path TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                verified_at INTEGER,
+                status TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS proofs_by_path ON proofs (proof_path);",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Records a proof; an existing record for the same location is replaced
+    pub fn record(&self, proof: &NewProof) -> Result<i64, RegistryError> {
+        let proof_path = normalize_location(proof.proof_path);
+        let now = unix_now();
+        let verified_at = (proof.status != VerificationStatus::Unverified).then_some(now);
+        self.conn.execute(
+            "DELETE FROM proofs WHERE proof_path = ?1",
+            params![proof_path],
+        )?;
+        self.conn.execute(
+            "INSERT INTO proofs
+                (circuit, circuit_fingerprint, input_hash, proof_path, created_at, verified_at, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                proof.circuit,
+                proof.circuit_fingerprint,
+                proof.input_hash,
+                proof_path,
+                now as i64,
+                verified_at.map(|at| at as i64),
+                proof.status.name(),
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Stores the outcome of verifying the proof at `proof_path`; returns whether it was known
+    pub fn set_status(
+        &self,
+        proof_path: &str,
+        status: VerificationStatus,
+    ) -> Result<bool, RegistryError> {
+        let updated = self.conn.execute(
+            "UPDATE proofs SET status = ?1, verified_at = ?2 WHERE proof_path = ?3",
+            params![
+                status.name(),
+                unix_now() as i64,
+                normalize_location(proof_path)
+            ],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// All records, oldest first, optionally only those of one circuit
+    pub fn list(&self, circuit: Option<&str>) -> Result<Vec<ProofRecord>, RegistryError> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, circuit, circuit_fingerprint, input_hash, proof_path, created_at,
+                    verified_at, status
+             FROM proofs WHERE ?1 IS NULL OR circuit = ?1 ORDER BY id",
+        )?;
+        let records = statement
+            .query_map(params![circuit], record_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        records.into_iter().collect()
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<ProofRecord>, RegistryError> {
+        self.conn
+            .query_row(
+                "SELECT id, circuit, circuit_fingerprint, input_hash, proof_path, created_at,
+                        verified_at, status
+                 FROM proofs WHERE id = ?1",
+                params![id],
+                record_from_row,
+            )
+            .optional()?
+            .transpose()
+    }
+
+    /// Removes records whose proof file no longer exists, and, given `older_than`,
+    /// records created longer ago than that; returns how many were removed
+    ///
+    /// Only records are removed; proof files are left alone.
+    pub fn prune(&self, older_than: Option<Duration>) -> Result<usize, RegistryError> {
+        let cutoff = older_than.map(|age| unix_now().saturating_sub(age.as_secs()));
+        let mut removed = 0;
+        for record in self.list(None)? {
+            let missing = !is_url(&record.proof_path) && !Path::new(&record.proof_path).exists();
+            let expired = cutoff.is_some_and(|cutoff| record.created_at < cutoff);
+            if missing || expired {
+                removed += self
+                    .conn
+                    .execute("DELETE FROM proofs WHERE id = ?1", params![record.id])?;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Reads a row, deferring status parsing so a corrupt value is reported rather than panicking
+fn record_from_row(row: &Row) -> rusqlite::Result<Result<ProofRecord, RegistryError>> {
+    let status: String = row.get(7)?;
+    let record = ProofRecord {
+        id: row.get(0)?,
+        circuit: row.get(1)?,
+        circuit_fingerprint: row.get(2)?,
+        input_hash: row.get(3)?,
+        proof_path: row.get(4)?,
+        created_at: row.get::<_, i64>(5)? as u64,
+        verified_at: row.get::<_, Option<i64>>(6)?.map(|at| at as u64),
+        status: VerificationStatus::Unverified,
+    };
+    Ok(status
+        .parse()
+        .map(|status| ProofRecord { status, ..record }))
+}
+
+/// Canonical form of local paths, so a proof is found whichever way it is named
+fn normalize_location(location: &str) -> String {
+    if is_url(location) {
+        return location.to_string();
+    }
+    std::fs::canonicalize(location)
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|_| location.to_string())
+}
+
+fn is_url(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    #[error("Failed to create registry directory: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Registry database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Corrupt registry: {0}")]
+    Corrupt(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof(path: &str) -> NewProof<'_> {
+        NewProof {
+            circuit: "multiplier2",
+            circuit_fingerprint: "blake3:00",
+            input_hash: "blake3:11",
+            proof_path: path,
+            status: VerificationStatus::Unverified,
+        }
+    }
+
+    #[test]
+    fn records_and_updates_status() {
+        let registry = Registry::in_memory().unwrap();
+        let id = registry.record(&proof("https://proofs.example/a")).unwrap();
+        assert!(
+            registry
+                .set_status("https://proofs.example/a", VerificationStatus::Verified)
+                .unwrap()
+        );
+
+        let record = registry.get(id).unwrap().unwrap();
+        assert_eq!(record.status, VerificationStatus::Verified);
+        assert!(record.verified_at.is_some());
+        assert_eq!(registry.list(Some("other")).unwrap(), vec![]);
+        assert_eq!(registry.list(Some("multiplier2")).unwrap(), vec![record]);
+    }
+
+    #[test]
+    fn recording_a_location_again_replaces_it() {
+        let registry = Registry::in_memory().unwrap();
+        registry.record(&proof("https://proofs.example/a")).unwrap();
+        registry.record(&proof("https://proofs.example/a")).unwrap();
+        assert_eq!(registry.list(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn prune_drops_records_of_missing_files() {
+        let registry = Registry::in_memory().unwrap();
+        registry.record(&proof("/nonexistent/proof.json")).unwrap();
+        registry.record(&proof("https://proofs.example/a")).unwrap();
+        assert_eq!(registry.prune(None).unwrap(), 1);
+        assert_eq!(registry.prune(Some(Duration::from_secs(3600))).unwrap(), 0);
+    }
+}
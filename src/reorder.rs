@@ -0,0 +1,306 @@
+use std::collections::VecDeque;
+
+use ark_circom::{CircomCircuit, circom::R1CS};
+use ark_ff::PrimeField;
+
+use crate::r1cs::{self, WireMappingError};
+
+/// An order of the variables and constraints of an R1CS
+///
+/// The converted circuit lays its weight-matrix columns and witness vectors out in
+/// variable order and its rows in constraint order, so an order that keeps the
+/// variables of each constraint close together improves the memory locality of the
+/// prover's vector operations. Variables below `num_inputs`, the constant wire and
+/// the public signals, keep their positions so statements and public signals are
+/// unaffected; only private variables move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitOrder {
+    /// Old index of the variable at each new position
+    variables: Vec<usize>,
+    /// Old index of the constraint at each new position
+    constraints: Vec<usize>,
+}
+
+impl CircuitOrder {
+    /// Validates a custom order, given as the old index at each new position
+    pub fn new<F: PrimeField>(
+        r1cs: &R1CS<F>,
+        variables: Vec<usize>,
+        constraints: Vec<usize>,
+    ) -> Result<Self, OrderError> {
+        check_permutation("variables", &variables, r1cs.num_variables)?;
+        check_permutation("constraints", &constraints, r1cs.constraints.len())?;
+        if let Some(position) =
+            (0..r1cs.num_inputs.min(variables.len())).find(|&i| variables[i] != i)
+        {
+            return Err(OrderError::PublicVariableMoved(position));
+        }
+        Ok(Self {
+            variables,
+            constraints,
+        })
+    }
+
+    /// The order the circuit was compiled in
+    pub fn identity<F: PrimeField>(r1cs: &R1CS<F>) -> Self {
+        Self {
+            variables: (0..r1cs.num_variables).collect(),
+            constraints: (0..r1cs.constraints.len()).collect(),
+        }
+    }
+
+    /// Reverse Cuthill–McKee over the graph linking variables that share a constraint
+    ///
+    /// Each connected component is traversed breadth-first from a vertex of minimum
+    /// degree, visiting neighbours by increasing degree, and the result is reversed,
+    /// which narrows the band around the diagonal that constraints touch. Constraints
+    /// are then sorted by the first private variable they reference.
+    pub fn reverse_cuthill_mckee<F: PrimeField>(r1cs: &R1CS<F>) -> Self {
+        let fixed = r1cs.num_inputs.min(r1cs.num_variables);
+        let neighbours = private_adjacency(r1cs, fixed);
+        let degree = |v: usize| neighbours[v - fixed].len();
+
+        let mut by_degree: Vec<usize> = (fixed..r1cs.num_variables).collect();
+        by_degree.sort_by_key(|&v| degree(v));
+        let mut visited = vec![false; r1cs.num_variables - fixed];
+        let mut order = Vec::with_capacity(visited.len());
+        let mut queue = VecDeque::new();
+        for start in by_degree {
+            if visited[start - fixed] {
+                continue;
+            }
+            visited[start - fixed] = true;
+            queue.push_back(start);
+            while let Some(v) = queue.pop_front() {
+                order.push(v);
+                let mut next: Vec<usize> = neighbours[v - fixed]
+                    .iter()
+                    .copied()
+                    .filter(|&u| !visited[u - fixed])
+                    .collect();
+                next.sort_by_key(|&u| degree(u));
+                for u in next {
+                    visited[u - fixed] = true;
+                    queue.push_back(u);
+                }
+            }
+        }
+        order.reverse();
+
+        let variables: Vec<usize> = (0..fixed).chain(order).collect();
+        let position = inverse(&variables);
+        let mut constraints: Vec<usize> = (0..r1cs.constraints.len()).collect();
+        constraints.sort_by_key(|&i| {
+            let (a, b, c) = &r1cs.constraints[i];
+            a.iter()
+                .chain(b)
+                .chain(c)
+                .map(|&(v, _)| position[v])
+                .filter(|&p| p >= fixed)
+                .min()
+                .unwrap_or(0)
+        });
+        Self {
+            variables,
+            constraints,
+        }
+    }
+
+    /// Rewrites `circom` in this order, resolving any wire mapping into the witness
+    pub fn apply<F: PrimeField>(
+        &self,
+        circom: &CircomCircuit<F>,
+    ) -> Result<CircomCircuit<F>, OrderError> {
+        let r1cs = &circom.r1cs;
+        if self.variables.len() != r1cs.num_variables
+            || self.constraints.len() != r1cs.constraints.len()
+        {
+            return Err(OrderError::ShapeMismatch);
+        }
+        let position = inverse(&self.variables);
+        let relabel = |lc: &[(usize, F)]| -> Vec<(usize, F)> {
+            lc.iter().map(|&(v, coeff)| (position[v], coeff)).collect()
+        };
+        let constraints = self
+            .constraints
+            .iter()
+            .map(|&i| {
+                let (a, b, c) = &r1cs.constraints[i];
+                (relabel(a), relabel(b), relabel(c))
+            })
+            .collect();
+        let witness = circom
+            .witness
+            .as_ref()
+            .map(|witness| {
+                let values = r1cs::variable_values(r1cs, witness)?;
+                Ok::<_, WireMappingError>(self.variables.iter().map(|&v| values[v]).collect())
+            })
+            .transpose()?;
+        Ok(CircomCircuit {
+            r1cs: R1CS {
+                num_inputs: r1cs.num_inputs,
+                num_aux: r1cs.num_aux,
+                num_variables: r1cs.num_variables,
+                constraints,
+                wire_mapping: None,
+            },
+            witness,
+        })
+    }
+}
+
+/// Widest spread of private variable indices within one constraint
+///
+/// A rough measure of locality: the prover touches about this many consecutive
+/// entries of each witness vector per constraint. Public variables are left out
+/// since the constant wire alone would otherwise span every constraint.
+pub fn bandwidth<F: PrimeField>(r1cs: &R1CS<F>) -> usize {
+    r1cs.constraints
+        .iter()
+        .filter_map(|(a, b, c)| {
+            let indices = a
+                .iter()
+                .chain(b)
+                .chain(c)
+                .map(|&(v, _)| v)
+                .filter(|&v| v >= r1cs.num_inputs);
+            Some(indices.clone().max()? - indices.min()?)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Neighbours of each private variable, offset by `fixed`
+fn private_adjacency<F: PrimeField>(r1cs: &R1CS<F>, fixed: usize) -> Vec<Vec<usize>> {
+    let mut neighbours = vec![Vec::new(); r1cs.num_variables - fixed];
+    for (a, b, c) in &r1cs.constraints {
+        let mut members: Vec<usize> = a
+            .iter()
+            .chain(b)
+            .chain(c)
+            .map(|&(v, _)| v)
+            .filter(|&v| v >= fixed && v < r1cs.num_variables)
+            .collect();
+        members.sort_unstable();
+        members.dedup();
+        for &v in &members {
+            neighbours[v - fixed].extend(members.iter().filter(|&&u| u != v));
+        }
+    }
+    for list in &mut neighbours {
+        list.sort_unstable();
+        list.dedup();
+    }
+    neighbours
+}
+
+fn inverse(order: &[usize]) -> Vec<usize> {
+    let mut position = vec![0; order.len()];
+    for (new, &old) in order.iter().enumerate() {
+        position[old] = new;
+    }
+    position
+}
+
+fn check_permutation(what: &'static str, order: &[usize], len: usize) -> Result<(), OrderError> {
+    let mut seen = vec![false; len];
+    let valid = order.len() == len
+        && order
+            .iter()
+            .all(|&i| i < len && !std::mem::replace(&mut seen[i], true));
+    if valid {
+        Ok(())
+    } else {
+        Err(OrderError::NotAPermutation { what, len })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OrderError {
+    #[error("Order of {what} is not a permutation of 0..{len}")]
+    NotAPermutation { what: &'static str, len: usize },
+    #[error("Order moves public variable {0}")]
+    PublicVariableMoved(usize),
+    #[error("Order does not match the circuit's shape")]
+    ShapeMismatch,
+    #[error(transparent)]
+    WireMapping(#[from] WireMappingError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::circom_to_bulletproofs;
+    use ark_bn254::Fr;
+
+    /// A chain `x_{i+1} <== x_i * x_i` over public [1, out] with the private
+    /// variables shuffled, so neighbouring links sit far apart
+    fn shuffled_chain(links: usize) -> CircomCircuit<Fr> {
+        let one = Fr::from(1u64);
+        let slot = |i: usize| 2 + (i * 7) % links;
+        let mut values = vec![Fr::from(0u64); links + 2];
+        values[0] = one;
+        let mut x = Fr::from(2u64);
+        values[slot(0)] = x;
+        let mut constraints = Vec::new();
+        for i in 0..links - 1 {
+            constraints.push((
+                vec![(slot(i), one)],
+                vec![(slot(i), one)],
+                vec![(slot(i + 1), one)],
+            ));
+            x *= x;
+            values[slot(i + 1)] = x;
+        }
+        constraints.push((vec![(slot(links - 1), one)], vec![(0, one)], vec![(1, one)]));
+        values[1] = x;
+        CircomCircuit {
+            r1cs: R1CS {
+                num_inputs: 2,
+                num_aux: links,
+                num_variables: links + 2,
+                constraints,
+                wire_mapping: None,
+            },
+            witness: Some(values),
+        }
+    }
+
+    #[test]
+    fn reverse_cuthill_mckee_narrows_the_band() {
+        let circom = shuffled_chain(16);
+        let order = CircuitOrder::reverse_cuthill_mckee(&circom.r1cs);
+        let reordered = order.apply(&circom).unwrap();
+
+        assert!(bandwidth(&reordered.r1cs) < bandwidth(&circom.r1cs));
+        assert_eq!(bandwidth(&reordered.r1cs), 1);
+        // Public signals stay put and the reordered circuit still holds
+        assert_eq!(
+            reordered.witness.as_ref().unwrap()[..2],
+            circom.witness.as_ref().unwrap()[..2]
+        );
+        let values = reordered.witness.as_ref().unwrap();
+        assert!(r1cs::unsatisfied_constraints(&reordered.r1cs, values).is_empty());
+        let (circuit, witness, _) = circom_to_bulletproofs(&reordered).unwrap();
+        assert!(circuit.is_satisfied_by(&witness));
+    }
+
+    #[test]
+    fn custom_orders_are_validated() {
+        let r1cs = shuffled_chain(4).r1cs;
+        let constraints: Vec<usize> = (0..4).collect();
+        assert!(CircuitOrder::new(&r1cs, vec![0, 1, 5, 4, 3, 2], constraints.clone()).is_ok());
+        assert!(matches!(
+            CircuitOrder::new(&r1cs, vec![1, 0, 2, 3, 4, 5], constraints.clone()),
+            Err(OrderError::PublicVariableMoved(0))
+        ));
+        assert!(matches!(
+            CircuitOrder::new(&r1cs, vec![0, 1, 2, 2, 3, 4], constraints),
+            Err(OrderError::NotAPermutation {
+                what: "variables",
+                ..
+            })
+        ));
+    }
+}
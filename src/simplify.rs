@@ -0,0 +1,615 @@
+use std::collections::{HashMap, HashSet};
+
+use ark_circom::{CircomCircuit, circom::R1CS};
+use ark_ff::PrimeField;
+use serde::Serialize;
+
+use crate::r1cs::{self, WireMappingError};
+
+/// Linear combination `(variable, coefficient)` of one constraint side
+type Lc<F> = Vec<(usize, F)>;
+/// Constraint `A·w ⊙ B·w = C·w` as its three sides
+type Constraint<F> = (Lc<F>, Lc<F>, Lc<F>);
+
+/// What `deduplicate` removed from an R1CS
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Deduplication {
+    pub constraints_before: usize,
+    pub constraints_after: usize,
+    /// Constraints equal to an earlier one up to a scalar multiple
+    pub duplicates: usize,
+    /// Constraints that read `0 = 0` once canonicalized
+    pub trivial: usize,
+}
+
+impl Deduplication {
+    pub fn removed(&self) -> usize {
+        self.constraints_before - self.constraints_after
+    }
+}
+
+/// Canonicalizes the constraints of `r1cs` and drops repeated and trivial ones
+///
+/// Circom often emits the same constraint several times, sometimes scaled or
+/// with A and B swapped. Every constraint is brought into a canonical form (see
+/// `canonicalize`), and only the first occurrence of each form is kept, in the
+/// original order. The variables are untouched, so the same witness satisfies
+/// the result exactly when it satisfies `r1cs`; fewer constraints mean fewer
+/// gates and rows, and so a smaller padded dimension and CRS.
+///
+/// Provers and verifiers must agree on whether the pass runs, as it changes the
+/// converted circuit.
+pub fn deduplicate<F: PrimeField>(r1cs: &R1CS<F>) -> (R1CS<F>, Deduplication) {
+    let mut report = Deduplication {
+        constraints_before: r1cs.constraints.len(),
+        ..Default::default()
+    };
+    let mut seen = HashSet::new();
+    let mut constraints = Vec::with_capacity(r1cs.constraints.len());
+    for (a, b, c) in &r1cs.constraints {
+        match canonicalize(a, b, c) {
+            None => report.trivial += 1,
+            Some(constraint) if !seen.insert(constraint.clone()) => report.duplicates += 1,
+            Some(constraint) => constraints.push(constraint),
+        }
+    }
+    report.constraints_after = constraints.len();
+    let r1cs = R1CS {
+        num_inputs: r1cs.num_inputs,
+        num_aux: r1cs.num_aux,
+        num_variables: r1cs.num_variables,
+        constraints,
+        wire_mapping: r1cs.wire_mapping.clone(),
+    };
+    (r1cs, report)
+}
+
+/// The canonical form of the constraint `A·w ⊙ B·w = C·w`, `None` if it holds
+/// for every witness
+///
+/// Each side has its terms sorted by variable, repeated variables summed and
+/// zero coefficients dropped. A linear constraint becomes `0 = C·w` with an
+/// empty A and B and C scaled to a leading coefficient of 1. Otherwise A and B
+/// are scaled to a leading coefficient of 1, C by the inverse of both factors,
+/// and the smaller of A and B is put first.
+pub fn canonicalize<F: PrimeField>(
+    a: &[(usize, F)],
+    b: &[(usize, F)],
+    c: &[(usize, F)],
+) -> Option<Constraint<F>> {
+    let (a, b, c) = (combine(a), combine(b), combine(c));
+    if r1cs::is_linear(&a, &b) {
+        let (c, _) = normalize(c)?;
+        return Some((Vec::new(), Vec::new(), c));
+    }
+    let (a, a_lead) = normalize(a)?;
+    let (b, b_lead) = normalize(b)?;
+    let scale = (a_lead * b_lead).inverse()?;
+    let c = c.into_iter().map(|(v, coeff)| (v, coeff * scale)).collect();
+    Some(if b < a { (b, a, c) } else { (a, b, c) })
+}
+
+/// What `share_products` rewrote in an R1CS
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Sharing {
+    /// Multiplicative constraints before the pass, each taking a gate
+    pub products_before: usize,
+    /// Constraints repeating an earlier product, rewritten as linear ones
+    pub shared: usize,
+}
+
+impl Sharing {
+    /// Multiplicative constraints left, and so the gates they take
+    pub fn products_after(&self) -> usize {
+        self.products_before - self.shared
+    }
+}
+
+/// Rewrites constraints repeating the product of an earlier constraint as linear
+/// ones, so both share its gate
+///
+/// Hash circuits often multiply the same pair of linear combinations more than
+/// once. When `A·w ⊙ B·w = C·w` comes first and a later constraint's sides are
+/// `α·A` and `β·B`, in either order, the later one holds, given the first, exactly
+/// when `C'·w = αβ·C·w` does, and it is replaced by that linear constraint.
+/// Linear constraints take no gate in the conversion, so every repetition of a
+/// product saves a gate and two rows. The variables and the constraint count are
+/// untouched, and the same witnesses satisfy the result. A repetition that only
+/// restates the first becomes `0 = 0`; run `deduplicate` first to drop those.
+///
+/// Provers and verifiers must agree on whether the pass runs, as it changes the
+/// converted circuit.
+pub fn share_products<F: PrimeField>(r1cs: &R1CS<F>) -> (R1CS<F>, Sharing) {
+    let mut report = Sharing::default();
+    // The first constraint of each canonical product, as `lead · Â·B̂ = C`
+    let mut products: HashMap<(Lc<F>, Lc<F>), (F, Lc<F>)> = HashMap::new();
+    let mut constraints = Vec::with_capacity(r1cs.constraints.len());
+    for (a, b, c) in &r1cs.constraints {
+        let (Some((a_hat, a_lead)), Some((b_hat, b_lead))) =
+            (normalize(combine(a)), normalize(combine(b)))
+        else {
+            constraints.push((a.clone(), b.clone(), c.clone()));
+            continue;
+        };
+        report.products_before += 1;
+        let lead = a_lead * b_lead;
+        let key = if b_hat < a_hat {
+            (b_hat, a_hat)
+        } else {
+            (a_hat, b_hat)
+        };
+        match products.get(&key) {
+            // lead · Â·B̂ = C' and first_lead · Â·B̂ = C give C' = lead / first_lead · C
+            Some((first_lead, first_c)) => {
+                let factor = lead
+                    * first_lead
+                        .inverse()
+                        .expect("leading coefficients are nonzero");
+                let terms: Lc<F> = c
+                    .iter()
+                    .copied()
+                    .chain(first_c.iter().map(|&(v, coeff)| (v, -coeff * factor)))
+                    .collect();
+                constraints.push((Vec::new(), Vec::new(), combine(&terms)));
+                report.shared += 1;
+            }
+            None => {
+                products.insert(key, (lead, c.clone()));
+                constraints.push((a.clone(), b.clone(), c.clone()));
+            }
+        }
+    }
+    let r1cs = R1CS {
+        num_inputs: r1cs.num_inputs,
+        num_aux: r1cs.num_aux,
+        num_variables: r1cs.num_variables,
+        constraints,
+        wire_mapping: r1cs.wire_mapping.clone(),
+    };
+    (r1cs, report)
+}
+
+/// What `eliminate_intermediates` removed from an R1CS
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Elimination {
+    pub variables_before: usize,
+    pub variables_after: usize,
+    pub constraints_before: usize,
+    pub constraints_after: usize,
+    /// Old index of the variable at each new position
+    #[serde(skip)]
+    kept: Vec<usize>,
+}
+
+impl Elimination {
+    /// Variables substituted away, each with the linear constraint defining it
+    pub fn eliminated(&self) -> usize {
+        self.variables_before - self.variables_after
+    }
+
+    /// The witness of the reduced R1CS, from a witness of the original `r1cs`
+    ///
+    /// Any wire mapping of `r1cs` is resolved, so the result is in variable order.
+    pub fn witness<F: PrimeField>(
+        &self,
+        r1cs: &R1CS<F>,
+        witness: &[F],
+    ) -> Result<Vec<F>, WireMappingError> {
+        let values = r1cs::variable_values(r1cs, witness)?;
+        Ok(self.kept.iter().map(|&v| values[v]).collect())
+    }
+
+    /// Names keyed by variable of the original R1CS, re-keyed to the reduced one;
+    /// eliminated variables lose theirs
+    pub fn rename<'a>(&self, names: &HashMap<usize, &'a str>) -> HashMap<usize, &'a str> {
+        self.kept
+            .iter()
+            .enumerate()
+            .filter_map(|(new, old)| Some((new, *names.get(old)?)))
+            .collect()
+    }
+}
+
+/// Substitutes away private variables defined by a linear constraint and used at
+/// most once more
+///
+/// A private variable `x` appearing in exactly one linear constraint
+/// `0 = k·x + L·w` and in at most one other constraint is replaced there by
+/// `-L·w / k`, and both `x` and its defining constraint are dropped. Limiting
+/// candidates to a single use keeps the substituted linear combinations from
+/// growing. Eliminations that make further variables eligible are repeated until
+/// none is left. Public variables keep their indices; the remaining private
+/// variables are renumbered in order.
+///
+/// Every witness of `r1cs` yields one of the result through
+/// `Elimination::witness`, and every witness of the result extends to one of
+/// `r1cs` by evaluating the eliminated definitions, so proofs about either
+/// circuit prove the same statement. As variables move, requested commitments to
+/// private variables can't be combined with this pass, and provers and verifiers
+/// must agree on whether it runs.
+pub fn eliminate_intermediates<F: PrimeField>(r1cs: &R1CS<F>) -> (R1CS<F>, Elimination) {
+    let fixed = r1cs.num_inputs.min(r1cs.num_variables);
+    let mut constraints: Vec<Option<Constraint<F>>> = r1cs
+        .constraints
+        .iter()
+        .map(|(a, b, c)| Some((combine(a), combine(b), combine(c))))
+        .collect();
+    let mut eliminated = vec![false; r1cs.num_variables];
+
+    loop {
+        // Constraints of each variable, and how many of them are linear with the
+        // variable in C; a linear constraint with an empty A may still hold it in B,
+        // where the product vanishes and no definition can be read off
+        let mut uses = vec![Vec::new(); r1cs.num_variables];
+        let mut linear_uses = vec![0usize; r1cs.num_variables];
+        for (i, (a, b, c)) in constraints
+            .iter()
+            .enumerate()
+            .filter_map(|(i, constraint)| Some((i, constraint.as_ref()?)))
+        {
+            let linear = r1cs::is_linear(a, b);
+            let mut members: Vec<usize> = a.iter().chain(b).chain(c).map(|&(v, _)| v).collect();
+            members.sort_unstable();
+            members.dedup();
+            for v in members.into_iter().filter(|&v| v < r1cs.num_variables) {
+                uses[v].push(i);
+                linear_uses[v] += usize::from(linear && defines(c, v));
+            }
+        }
+
+        // Eliminations touching disjoint constraints can run in the same round
+        let mut touched = vec![false; constraints.len()];
+        let mut progress = false;
+        for x in fixed..r1cs.num_variables {
+            if eliminated[x] || linear_uses[x] != 1 || uses[x].len() > 2 {
+                continue;
+            }
+            let Some(&definition) = uses[x].iter().find(|&&i| {
+                let (a, b, c) = constraints[i]
+                    .as_ref()
+                    .expect("only live constraints are used");
+                r1cs::is_linear(a, b) && defines(c, x)
+            }) else {
+                continue;
+            };
+            let target = uses[x].iter().copied().find(|&i| i != definition);
+            if touched[definition] || target.is_some_and(|i| touched[i]) {
+                continue;
+            }
+
+            // 0 = k·x + L·w gives x = -L·w / k
+            let (_, _, c) = constraints[definition].take().expect("definition is live");
+            let k = c
+                .iter()
+                .find(|&&(v, _)| v == x)
+                .map(|&(_, k)| k)
+                .expect("x is in the definition's C");
+            let factor = -k.inverse().expect("combined coefficients are nonzero");
+            let substitute: Lc<F> = c
+                .into_iter()
+                .filter(|&(v, _)| v != x)
+                .map(|(v, coeff)| (v, coeff * factor))
+                .collect();
+            if let Some(target) = target {
+                let (a, b, c) = constraints[target].as_mut().expect("target is live");
+                for side in [a, b, c] {
+                    *side = substitute_into(side, x, &substitute);
+                }
+                touched[target] = true;
+            }
+            touched[definition] = true;
+            eliminated[x] = true;
+            progress = true;
+        }
+        if !progress {
+            break;
+        }
+    }
+
+    let kept: Vec<usize> = (0..r1cs.num_variables)
+        .filter(|&v| !eliminated[v])
+        .collect();
+    let mut position = vec![0; r1cs.num_variables];
+    for (new, &old) in kept.iter().enumerate() {
+        position[old] = new;
+    }
+    let relabel = |lc: Lc<F>| -> Lc<F> {
+        lc.into_iter()
+            .map(|(v, coeff)| (position.get(v).copied().unwrap_or(v), coeff))
+            .collect()
+    };
+    let constraints: Vec<Constraint<F>> = constraints
+        .into_iter()
+        .flatten()
+        .map(|(a, b, c)| (relabel(a), relabel(b), relabel(c)))
+        .collect();
+    let report = Elimination {
+        variables_before: r1cs.num_variables,
+        variables_after: kept.len(),
+        constraints_before: r1cs.constraints.len(),
+        constraints_after: constraints.len(),
+        kept,
+    };
+    let reduced = R1CS {
+        num_inputs: r1cs.num_inputs,
+        num_aux: report.variables_after - fixed,
+        num_variables: report.variables_after,
+        constraints,
+        wire_mapping: None,
+    };
+    (reduced, report)
+}
+
+/// Whether `v` has a term in the combined side `c`
+fn defines<F: PrimeField>(c: &[(usize, F)], v: usize) -> bool {
+    c.iter().any(|&(u, _)| u == v)
+}
+
+/// `eliminate_intermediates` over a circuit and its witness
+pub fn eliminate_intermediates_in<F: PrimeField>(
+    circom: &CircomCircuit<F>,
+) -> Result<(CircomCircuit<F>, Elimination), WireMappingError> {
+    let (r1cs, elimination) = eliminate_intermediates(&circom.r1cs);
+    let witness = circom
+        .witness
+        .as_ref()
+        .map(|witness| elimination.witness(&circom.r1cs, witness))
+        .transpose()?;
+    Ok((CircomCircuit { r1cs, witness }, elimination))
+}
+
+/// `lc` with the variable `x` replaced by the linear combination `substitute`
+fn substitute_into<F: PrimeField>(lc: &[(usize, F)], x: usize, substitute: &[(usize, F)]) -> Lc<F> {
+    let Some(&(_, coeff)) = lc.iter().find(|&&(v, _)| v == x) else {
+        return lc.to_vec();
+    };
+    let terms: Lc<F> = lc
+        .iter()
+        .copied()
+        .filter(|&(v, _)| v != x)
+        .chain(substitute.iter().map(|&(v, s)| (v, s * coeff)))
+        .collect();
+    combine(&terms)
+}
+
+/// Sorts the terms by variable, summing repeated variables and dropping zeros
+pub(crate) fn combine<F: PrimeField>(lc: &[(usize, F)]) -> Lc<F> {
+    let mut terms = lc.to_vec();
+    terms.sort_by_key(|&(v, _)| v);
+    let mut combined: Lc<F> = Vec::with_capacity(terms.len());
+    for (v, coeff) in terms {
+        match combined.last_mut() {
+            Some((last, sum)) if *last == v => *sum += coeff,
+            _ => combined.push((v, coeff)),
+        }
+    }
+    combined.retain(|(_, coeff)| !coeff.is_zero());
+    combined
+}
+
+/// Scales a combined linear combination to a leading coefficient of 1, returning
+/// the factor it was divided by; `None` for the empty combination
+fn normalize<F: PrimeField>(lc: Lc<F>) -> Option<(Lc<F>, F)> {
+    let lead = lc.first()?.1;
+    let inverse = lead.inverse()?;
+    let lc = lc
+        .into_iter()
+        .map(|(v, coeff)| (v, coeff * inverse))
+        .collect();
+    Some((lc, lead))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::{ConversionReport, circom_to_bulletproofs};
+    use ark_bn254::Fr;
+    use ark_circom::CircomCircuit;
+
+    fn lc(terms: &[(usize, i64)]) -> Lc<Fr> {
+        terms
+            .iter()
+            .map(|&(v, coeff)| (v, Fr::from(coeff)))
+            .collect()
+    }
+
+    /// `c <== a * b` and `a + b === s` over wires [1, c, a, b, s], each repeated
+    /// in disguise, plus a constraint reading `0 = 0`
+    fn repetitive() -> R1CS<Fr> {
+        R1CS {
+            num_inputs: 2,
+            num_aux: 3,
+            num_variables: 5,
+            constraints: vec![
+                (lc(&[(2, 1)]), lc(&[(3, 1)]), lc(&[(1, 1)])),
+                (lc(&[]), lc(&[]), lc(&[(2, 1), (3, 1), (4, -1)])),
+                // 2a · 3b = 6c
+                (lc(&[(2, 2)]), lc(&[(3, 3)]), lc(&[(1, 6)])),
+                // b · a = c
+                (lc(&[(3, 1)]), lc(&[(2, 1)]), lc(&[(1, 1)])),
+                // -s + b + a with a split in two terms, times -1
+                (
+                    lc(&[]),
+                    lc(&[(3, 1)]),
+                    lc(&[(4, 1), (3, -1), (2, -3), (2, 2)]),
+                ),
+                (lc(&[]), lc(&[]), lc(&[(2, 1), (2, -1)])),
+            ],
+            wire_mapping: None,
+        }
+    }
+
+    #[test]
+    fn scaled_and_swapped_constraints_are_duplicates() {
+        let (r1cs, report) = deduplicate(&repetitive());
+        assert_eq!(
+            report,
+            Deduplication {
+                constraints_before: 6,
+                constraints_after: 2,
+                duplicates: 3,
+                trivial: 1,
+            }
+        );
+        assert_eq!(report.removed(), 4);
+        assert_eq!(
+            r1cs.constraints,
+            vec![
+                (lc(&[(2, 1)]), lc(&[(3, 1)]), lc(&[(1, 1)])),
+                (lc(&[]), lc(&[]), lc(&[(2, 1), (3, 1), (4, -1)])),
+            ]
+        );
+    }
+
+    #[test]
+    fn deduplicated_circuit_is_smaller_and_still_holds() {
+        let values = [1u64, 33, 3, 11, 14].map(Fr::from).to_vec();
+        let (r1cs, _) = deduplicate(&repetitive());
+        assert!(r1cs::unsatisfied_constraints(&repetitive(), &values).is_empty());
+        assert!(r1cs::unsatisfied_constraints(&r1cs, &values).is_empty());
+        let before = ConversionReport::from_r1cs(&repetitive());
+        let after = ConversionReport::from_r1cs(&r1cs);
+        assert!(after.gates < before.gates);
+        assert!(after.rows < before.rows);
+
+        let circom = CircomCircuit {
+            r1cs,
+            witness: Some(values),
+        };
+        let (circuit, witness, _) = circom_to_bulletproofs(&circom).unwrap();
+        assert!(circuit.is_satisfied_by(&witness));
+    }
+
+    /// `s <== a + b`, `out <== s * s` and an unused `t <== a - b` over wires
+    /// [1, out, a, b, s, t]
+    fn intermediates() -> CircomCircuit<Fr> {
+        CircomCircuit {
+            r1cs: R1CS {
+                num_inputs: 2,
+                num_aux: 4,
+                num_variables: 6,
+                constraints: vec![
+                    (lc(&[]), lc(&[]), lc(&[(2, 1), (3, 1), (4, -1)])),
+                    (lc(&[(4, 1)]), lc(&[(4, 1)]), lc(&[(1, 1)])),
+                    (lc(&[]), lc(&[]), lc(&[(2, 1), (3, -1), (5, -1)])),
+                ],
+                wire_mapping: None,
+            },
+            witness: Some([1u64, 49, 4, 3, 7, 1].map(Fr::from).to_vec()),
+        }
+    }
+
+    #[test]
+    fn single_use_intermediates_are_substituted() {
+        let circom = intermediates();
+        let (reduced, elimination) = eliminate_intermediates_in(&circom).unwrap();
+        assert_eq!(elimination.eliminated(), 2);
+        assert_eq!(
+            (
+                elimination.constraints_before,
+                elimination.constraints_after
+            ),
+            (3, 1)
+        );
+        // `a` and `b` appear in two linear constraints and stay
+        assert_eq!(
+            reduced.r1cs.constraints,
+            vec![(lc(&[(2, 1), (3, 1)]), lc(&[(2, 1), (3, 1)]), lc(&[(1, 1)]))]
+        );
+        assert_eq!((reduced.r1cs.num_variables, reduced.r1cs.num_aux), (4, 2));
+        assert_eq!(
+            reduced.witness,
+            Some([1u64, 49, 4, 3].map(Fr::from).to_vec())
+        );
+        let names = HashMap::from([(1, "main.out"), (3, "main.b"), (4, "main.s")]);
+        assert_eq!(
+            elimination.rename(&names),
+            HashMap::from([(1, "main.out"), (3, "main.b")])
+        );
+
+        let (circuit, witness, report) = circom_to_bulletproofs(&reduced).unwrap();
+        assert!(circuit.is_satisfied_by(&witness));
+        assert!(report.gates < ConversionReport::from_r1cs(&circom.r1cs).gates);
+    }
+
+    /// `c <== a * b`, `d <== (3b) * (2a)` and `e <== a * a` over wires [1, c, d, a, b, e]
+    fn products(d: u64) -> CircomCircuit<Fr> {
+        CircomCircuit {
+            r1cs: R1CS {
+                num_inputs: 3,
+                num_aux: 3,
+                num_variables: 6,
+                constraints: vec![
+                    (lc(&[(3, 1)]), lc(&[(4, 1)]), lc(&[(1, 1)])),
+                    (lc(&[(4, 3)]), lc(&[(3, 2)]), lc(&[(2, 1)])),
+                    (lc(&[(3, 1)]), lc(&[(3, 1)]), lc(&[(5, 1)])),
+                ],
+                wire_mapping: None,
+            },
+            witness: Some([1u64, 33, d, 3, 11, 9].map(Fr::from).to_vec()),
+        }
+    }
+
+    #[test]
+    fn repeated_products_share_a_gate() {
+        let circom = products(198);
+        let (r1cs, sharing) = share_products(&circom.r1cs);
+        assert_eq!(
+            sharing,
+            Sharing {
+                products_before: 3,
+                shared: 1,
+            }
+        );
+        assert_eq!(sharing.products_after(), 2);
+        // d = 6c
+        assert_eq!(
+            r1cs.constraints[1],
+            (lc(&[]), lc(&[]), lc(&[(1, -6), (2, 1)]))
+        );
+        assert_eq!(r1cs.constraints[2], circom.r1cs.constraints[2]);
+
+        let shared = CircomCircuit {
+            r1cs,
+            witness: circom.witness.clone(),
+        };
+        let (circuit, witness, report) = circom_to_bulletproofs(&shared).unwrap();
+        assert!(circuit.is_satisfied_by(&witness));
+        assert_eq!(report.multiplicative_constraints, 2);
+        assert!(report.gates < ConversionReport::from_r1cs(&circom.r1cs).gates);
+    }
+
+    #[test]
+    fn sharing_keeps_unsatisfied_witnesses_unsatisfied() {
+        let circom = products(199);
+        let (r1cs, _) = share_products(&circom.r1cs);
+        let witness = circom.witness.as_deref().unwrap();
+        assert_eq!(r1cs::unsatisfied_constraints(&circom.r1cs, witness), [1]);
+        assert_eq!(r1cs::unsatisfied_constraints(&r1cs, witness), [1]);
+    }
+
+    #[test]
+    fn variables_only_in_a_vanishing_factor_define_nothing() {
+        // 0 · x = y over wires [1, y, x]: linear, but x has no term in C
+        let r1cs = R1CS {
+            num_inputs: 2,
+            num_aux: 1,
+            num_variables: 3,
+            constraints: vec![(lc(&[]), lc(&[(2, 1)]), lc(&[(1, 1)]))],
+            wire_mapping: None,
+        };
+        let (reduced, elimination) = eliminate_intermediates(&r1cs);
+        assert_eq!(elimination.eliminated(), 0);
+        assert_eq!(reduced.constraints, r1cs.constraints);
+    }
+
+    #[test]
+    fn public_variables_are_never_eliminated() {
+        let mut circom = intermediates();
+        circom.r1cs.num_inputs = 6;
+        circom.r1cs.num_aux = 0;
+        let (reduced, elimination) = eliminate_intermediates_in(&circom).unwrap();
+        assert_eq!(elimination.eliminated(), 0);
+        assert_eq!(reduced.r1cs.constraints.len(), 3);
+    }
+}
@@ -0,0 +1,104 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// Destination for named artifacts such as proofs and statements
+///
+/// Lets the crate be embedded where artifacts never touch the local filesystem.
+pub trait Sink {
+    /// Stores `bytes` under `name`, replacing any previous artifact of that name
+    fn put(&mut self, name: &str, bytes: &[u8]) -> Result<(), SinkError>;
+
+    /// Human-readable location of an artifact, for logs and reports
+    fn location(&self, name: &str) -> String;
+}
+
+/// Writes artifacts as files of a directory, creating it on first use
+///
+/// Files are replaced atomically under the directory's lock (see `atomic::write`).
+#[derive(Debug, Clone)]
+pub struct FileSink {
+    pub dir: PathBuf,
+}
+
+impl FileSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl Sink for FileSink {
+    fn put(&mut self, name: &str, bytes: &[u8]) -> Result<(), SinkError> {
+        std::fs::create_dir_all(&self.dir)?;
+        crate::atomic::write(self.dir.join(name), bytes)?;
+        Ok(())
+    }
+
+    fn location(&self, name: &str) -> String {
+        self.dir.join(name).display().to_string()
+    }
+}
+
+/// Keeps artifacts in memory
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+    pub artifacts: BTreeMap<String, Vec<u8>>,
+}
+
+impl Sink for MemorySink {
+    fn put(&mut self, name: &str, bytes: &[u8]) -> Result<(), SinkError> {
+        self.artifacts.insert(name.to_string(), bytes.to_vec());
+        Ok(())
+    }
+
+    fn location(&self, name: &str) -> String {
+        format!("memory:{name}")
+    }
+}
+
+/// Uploads each artifact with an HTTP `PUT` to `<base_url>/<name>`
+#[cfg(feature = "http")]
+#[derive(Debug, Clone)]
+pub struct HttpSink {
+    pub base_url: String,
+}
+
+#[cfg(feature = "http")]
+impl HttpSink {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl Sink for HttpSink {
+    fn put(&mut self, name: &str, bytes: &[u8]) -> Result<(), SinkError> {
+        ureq::put(&self.location(name))
+            .set("Content-Type", "application/octet-stream")
+            .send_bytes(bytes)
+            .map_err(|e| SinkError::Http(e.to_string()))?;
+        Ok(())
+    }
+
+    fn location(&self, name: &str) -> String {
+        format!("{}/{name}", self.base_url)
+    }
+}
+
+/// Picks a sink for a CLI target: `http(s)://` URLs upload, anything else is a directory
+#[cfg(feature = "http")]
+pub fn open(target: &str) -> Box<dyn Sink> {
+    if target.starts_with("http://") || target.starts_with("https://") {
+        Box::new(HttpSink::new(target))
+    } else {
+        Box::new(FileSink::new(target))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SinkError {
+    #[error("Failed to write artifact: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to upload artifact: {0}")]
+    Http(String),
+}
@@ -0,0 +1,235 @@
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use bulletproofs::circuit::types::{Circuit, Witness};
+
+/// Nonzero entries `(column, coefficient)` of one weight-matrix row
+pub type SparseRow<F> = Vec<(usize, F)>;
+
+/// A circuit `W_L·a_L + W_R·a_R + W_O·a_O = W_V·v + c`, `a_L ⊙ a_R = a_O` with
+/// sparse weight matrices
+///
+/// Converted R1CS rows only touch a handful of gates and commitments, so this
+/// takes memory in proportion to the coefficients rather than rows × gates.
+/// The prover and verifier only take the dense `Circuit`, so `into_dense` still
+/// builds the full matrices for them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseCircuit<F> {
+    /// Multiplication gates, the width of `w_l`, `w_r` and `w_o`
+    pub gates: usize,
+    /// Statement commitments, the width of `w_v`
+    pub commitments: usize,
+    pub w_l: Vec<SparseRow<F>>,
+    pub w_r: Vec<SparseRow<F>>,
+    pub w_o: Vec<SparseRow<F>>,
+    pub w_v: Vec<SparseRow<F>>,
+    pub c: Vec<F>,
+}
+
+impl<F: Field> SparseCircuit<F> {
+    /// An empty circuit of the given width, to which rows are added
+    pub fn new(gates: usize, commitments: usize) -> Self {
+        Self::with_capacity(gates, commitments, 0)
+    }
+
+    /// Like `new`, with room for `rows` rows, so a conversion that knows its row
+    /// count up front never reallocates the row vectors
+    pub fn with_capacity(gates: usize, commitments: usize, rows: usize) -> Self {
+        Self {
+            gates,
+            commitments,
+            w_l: Vec::with_capacity(rows),
+            w_r: Vec::with_capacity(rows),
+            w_o: Vec::with_capacity(rows),
+            w_v: Vec::with_capacity(rows),
+            c: Vec::with_capacity(rows),
+        }
+    }
+
+    /// Adds the linear constraint `w_l·a_L + w_r·a_R + w_o·a_O = w_v·v + c`
+    pub fn push_row(
+        &mut self,
+        w_l: SparseRow<F>,
+        w_r: SparseRow<F>,
+        w_o: SparseRow<F>,
+        w_v: SparseRow<F>,
+        c: F,
+    ) {
+        self.w_l.push(w_l);
+        self.w_r.push(w_r);
+        self.w_o.push(w_o);
+        self.w_v.push(w_v);
+        self.c.push(c);
+    }
+
+    /// Number of linear constraints, as `Circuit::size`
+    pub fn size(&self) -> usize {
+        self.c.len()
+    }
+
+    /// Number of multiplication gates, as `Circuit::dim`
+    pub fn dim(&self) -> usize {
+        self.gates
+    }
+
+    /// Nonzero entries across all four weight matrices
+    pub fn nonzero_weights(&self) -> usize {
+        [&self.w_l, &self.w_r, &self.w_o, &self.w_v]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|(_, coeff)| !coeff.is_zero())
+            .count()
+    }
+
+    /// Whether the witness satisfies every gate and linear constraint, as
+    /// `Circuit::is_satisfied_by` on the dense circuit would find
+    pub fn is_satisfied_by(&self, witness: &Witness<F>) -> bool {
+        self.fits(witness)
+            && (0..self.gates).all(|gate| self.gate_holds(gate, witness))
+            && (0..self.size()).all(|row| self.row_holds(row, witness))
+    }
+
+    /// Whether the witness has a wire per gate and a value per commitment, as
+    /// `gate_holds` and `row_holds` assume
+    pub fn fits(&self, witness: &Witness<F>) -> bool {
+        [&witness.a_l, &witness.a_r, &witness.a_o]
+            .iter()
+            .all(|wire| wire.len() == self.gates)
+            && witness.v.len() == self.commitments
+    }
+
+    /// Whether the witness satisfies `a_L ⊙ a_R = a_O` at one gate
+    pub fn gate_holds(&self, gate: usize, witness: &Witness<F>) -> bool {
+        witness.a_l[gate] * witness.a_r[gate] == witness.a_o[gate]
+    }
+
+    /// Whether the witness satisfies one linear constraint
+    pub fn row_holds(&self, row: usize, witness: &Witness<F>) -> bool {
+        dot(&self.w_l[row], &witness.a_l)
+            + dot(&self.w_r[row], &witness.a_r)
+            + dot(&self.w_o[row], &witness.a_o)
+            == dot(&self.w_v[row], &witness.v) + self.c[row]
+    }
+
+    /// The dense `Circuit` the prover and verifier work on
+    ///
+    /// Entries repeating a column accumulate rather than overwrite each other, as
+    /// circom may list a variable twice in one linear combination.
+    pub fn to_dense(&self) -> Circuit<F> {
+        self.clone().into_dense()
+    }
+
+    /// Like `to_dense`, consuming the circuit
+    ///
+    /// Each sparse row is freed as soon as its dense row is built, which trims the
+    /// peak a little; the dense matrices themselves still take rows × gates.
+    pub fn into_dense(self) -> Circuit<F> {
+        let densify = |rows: Vec<SparseRow<F>>, width: usize| -> Vec<Vec<F>> {
+            let mut matrix = Vec::with_capacity(rows.len());
+            for row in rows {
+                let mut dense = vec![F::zero(); width];
+                for (column, coeff) in row {
+                    dense[column] += coeff;
+                }
+                matrix.push(dense);
+            }
+            matrix
+        };
+        let w_l = densify(self.w_l, self.gates);
+        let w_r = densify(self.w_r, self.gates);
+        let w_o = densify(self.w_o, self.gates);
+        let w_v = densify(self.w_v, self.commitments);
+        Circuit::new(w_l, w_r, w_o, w_v, self.c)
+    }
+
+    /// Compressed encoding of the circuit: both widths, the four weight matrices
+    /// row by row, then `c`
+    pub fn encode(&self) -> Result<Vec<u8>, SerializationError> {
+        let mut bytes = Vec::new();
+        self.gates.serialize_compressed(&mut bytes)?;
+        self.commitments.serialize_compressed(&mut bytes)?;
+        for matrix in [&self.w_l, &self.w_r, &self.w_o, &self.w_v] {
+            matrix.serialize_compressed(&mut bytes)?;
+        }
+        self.c.serialize_compressed(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Decodes an `encode`d circuit, rejecting matrices of unequal heights and
+    /// columns beyond their width, which the checks and `into_dense` would index
+    pub fn decode(mut bytes: &[u8]) -> Result<Self, SerializationError> {
+        let reader = &mut bytes;
+        let gates = usize::deserialize_compressed(&mut *reader)?;
+        let commitments = usize::deserialize_compressed(&mut *reader)?;
+        let mut matrix = || Vec::<SparseRow<F>>::deserialize_compressed(&mut *reader);
+        let (w_l, w_r, w_o, w_v) = (matrix()?, matrix()?, matrix()?, matrix()?);
+        let c = Vec::<F>::deserialize_compressed(&mut *reader)?;
+        let fits = |rows: &[SparseRow<F>], width: usize| {
+            rows.len() == c.len() && rows.iter().flatten().all(|&(column, _)| column < width)
+        };
+        if !reader.is_empty()
+            || ![&w_l, &w_r, &w_o].iter().all(|rows| fits(rows, gates))
+            || !fits(&w_v, commitments)
+        {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(Self {
+            gates,
+            commitments,
+            w_l,
+            w_r,
+            w_o,
+            w_v,
+            c,
+        })
+    }
+}
+
+fn dot<F: Field>(row: &SparseRow<F>, values: &[F]) -> F {
+    row.iter()
+        .map(|&(column, coeff)| coeff * values[column])
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+
+    fn circuit() -> SparseCircuit<Fr> {
+        let mut circuit = SparseCircuit::new(2, 1);
+        circuit.push_row(
+            vec![(0, Fr::from(1u64))],
+            vec![],
+            vec![(1, -Fr::from(3u64))],
+            vec![(0, Fr::from(2u64))],
+            Fr::from(5u64),
+        );
+        circuit.push_row(
+            vec![],
+            vec![(1, Fr::from(1u64))],
+            vec![],
+            vec![],
+            Fr::from(0u64),
+        );
+        circuit
+    }
+
+    #[test]
+    fn encoding_round_trips() {
+        let circuit = circuit();
+        let bytes = circuit.encode().unwrap();
+        assert_eq!(SparseCircuit::<Fr>::decode(&bytes).unwrap(), circuit);
+        assert!(SparseCircuit::<Fr>::decode(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn decoding_rejects_columns_beyond_the_width() {
+        let mut circuit = circuit();
+        circuit.gates = 1;
+        assert!(SparseCircuit::<Fr>::decode(&circuit.encode().unwrap()).is_err());
+        let mut circuit = self::circuit();
+        circuit.c.pop();
+        assert!(SparseCircuit::<Fr>::decode(&circuit.encode().unwrap()).is_err());
+    }
+}
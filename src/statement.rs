@@ -0,0 +1,97 @@
+use ark_bn254::{Fr, G1Projective};
+use ark_ff::Zero;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use bulletproofs::circuit::types::{CRS as CircuitCRS, Statement as CircuitStatement, Witness};
+
+/// Builds the statement a verifier checks a proof against from the prover's commitments
+///
+/// Unlike `Statement::new`, this needs neither a witness nor a CRS: the commitments
+/// are taken as published by the prover.
+pub fn from_commitments(commitments: Vec<G1Projective>) -> CircuitStatement<G1Projective> {
+    CircuitStatement { v: commitments }
+}
+
+/// Decodes compressed commitments, rejecting points that are off the curve
+pub fn decode_commitments<B: AsRef<[u8]>>(
+    encoded: &[B],
+) -> Result<CircuitStatement<G1Projective>, SerializationError> {
+    let commitments = encoded
+        .iter()
+        .map(|bytes| G1Projective::deserialize_compressed(bytes.as_ref()))
+        .collect::<Result<_, _>>()?;
+    Ok(from_commitments(commitments))
+}
+
+/// Compressed encoding of each commitment in the statement
+pub fn encode_commitments(
+    statement: &CircuitStatement<G1Projective>,
+) -> Result<Vec<Vec<u8>>, SerializationError> {
+    statement
+        .v
+        .iter()
+        .map(|point| {
+            let mut bytes = Vec::new();
+            point.serialize_compressed(&mut bytes)?;
+            Ok(bytes)
+        })
+        .collect()
+}
+
+/// The commitments `x·G` of public signal values, as an unblinded statement holds them
+pub fn public_commitments(crs: &CircuitCRS<G1Projective>, values: &[Fr]) -> Vec<G1Projective> {
+    // Committing through a statement guarantees the prover's generators are used;
+    // only `v` and `gamma` enter the commitments
+    CircuitStatement::new(
+        crs,
+        &Witness {
+            a_l: Vec::new(),
+            a_r: Vec::new(),
+            a_o: Vec::new(),
+            v: values.to_vec(),
+            gamma: vec![Fr::zero(); values.len()],
+        },
+    )
+    .v
+}
+
+/// Checks that the statement opens the claimed public signal values, which take
+/// its first positions with zero blinding
+///
+/// Without this a proof says nothing about the public signals published with it.
+/// `expected` is the circuit's public signal count, so a claim cannot drop some.
+pub fn check_public_signals(
+    crs: &CircuitCRS<G1Projective>,
+    statement: &CircuitStatement<G1Projective>,
+    values: &[Fr],
+    expected: usize,
+) -> Result<(), PublicSignalsError> {
+    if values.len() != expected || statement.v.len() < expected {
+        return Err(PublicSignalsError::Count {
+            claimed: values.len(),
+            commitments: statement.v.len(),
+            expected,
+        });
+    }
+    let mismatch = public_commitments(crs, values)
+        .iter()
+        .zip(&statement.v)
+        .position(|(commitment, committed)| commitment != committed);
+    match mismatch {
+        Some(position) => Err(PublicSignalsError::Mismatch(position)),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PublicSignalsError {
+    #[error(
+        "{claimed} public signals claimed for a statement of {commitments} commitments, but the circuit has {expected}"
+    )]
+    Count {
+        claimed: usize,
+        commitments: usize,
+        expected: usize,
+    },
+    #[error("Public signal {0} does not match the statement")]
+    Mismatch(usize),
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+/// One signal of a circom `.sym` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// Position of the signal in the witness
+    pub label: usize,
+    /// R1CS variable the signal maps to; `None` when the optimizer removed it
+    pub variable: Option<usize>,
+    pub component: usize,
+    /// Fully qualified name such as `main.c` or `main.in[3]`
+    pub name: String,
+}
+
+/// The signal names of a compiled circuit, read from its `.sym` file
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    pub symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, SymError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parses `label,variable,component,name` lines; a variable of -1 marks a removed signal
+    pub fn parse(contents: &str) -> Result<Self, SymError> {
+        let symbols = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                let invalid = || SymError::InvalidLine(i + 1, line.to_string());
+                let mut fields = line.trim().splitn(4, ',');
+                let mut next = || fields.next().ok_or_else(invalid);
+                let label = next()?.parse::<usize>().map_err(|_| invalid())?;
+                let variable = next()?.parse::<i64>().map_err(|_| invalid())?;
+                let component = next()?.parse::<usize>().map_err(|_| invalid())?;
+                let name = next()?.to_string();
+                Ok(Symbol {
+                    label,
+                    variable: usize::try_from(variable).ok(),
+                    component,
+                    name,
+                })
+            })
+            .collect::<Result<_, SymError>>()?;
+        Ok(Self { symbols })
+    }
+
+    /// Signal names keyed by witness position
+    pub fn names_by_label(&self) -> HashMap<usize, &str> {
+        self.symbols
+            .iter()
+            .map(|symbol| (symbol.label, symbol.name.as_str()))
+            .collect()
+    }
+
+    /// Signal names keyed by R1CS variable, skipping signals the optimizer removed
+    pub fn names_by_variable(&self) -> HashMap<usize, &str> {
+        self.symbols
+            .iter()
+            .filter_map(|symbol| Some((symbol.variable?, symbol.name.as_str())))
+            .collect()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SymError {
+    #[error("Failed to read symbol file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Malformed symbol file line {0}: '{1}'")]
+    InvalidLine(usize, String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_symbols() {
+        let table = SymbolTable::parse("1,1,0,main.c\n2,-1,0,main.a\n3,2,0,main.b[0]\n").unwrap();
+        assert_eq!(
+            table.symbols[1],
+            Symbol {
+                label: 2,
+                variable: None,
+                component: 0,
+                name: "main.a".into()
+            }
+        );
+        assert_eq!(table.names_by_variable().get(&2), Some(&"main.b[0]"));
+        assert_eq!(table.names_by_label().get(&2), Some(&"main.a"));
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert!(matches!(
+            SymbolTable::parse("1,1,0,main.c\nnot a symbol\n"),
+            Err(SymError::InvalidLine(2, _))
+        ));
+    }
+}
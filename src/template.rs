@@ -0,0 +1,278 @@
+use std::path::Path;
+
+use ark_bn254::Fr;
+use ark_circom::circom::R1CSFile;
+use serde_json::{Map, Value, json};
+
+use crate::pipeline::CircuitPaths;
+use crate::r1cs::ONE_WIRE;
+use crate::sym::{SymError, SymbolTable};
+
+/// How many witness positions after the constant `1` hold outputs and inputs
+///
+/// circom orders the witness as `1`, outputs, public inputs, private inputs, then
+/// intermediate signals, so these counts locate the inputs among the symbols.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputLayout {
+    pub outputs: usize,
+    pub public_inputs: usize,
+    pub private_inputs: usize,
+}
+
+impl InputLayout {
+    /// Reads the signal counts from the header of an `.r1cs` file
+    pub fn read(r1cs_path: impl AsRef<Path>) -> Result<Self, TemplateError> {
+        let reader = std::io::BufReader::new(std::fs::File::open(r1cs_path)?);
+        let file = R1CSFile::<Fr>::new(reader).map_err(|e| TemplateError::R1cs(e.to_string()))?;
+        Ok(Self {
+            outputs: file.header.n_pub_out as usize,
+            public_inputs: file.header.n_pub_in as usize,
+            private_inputs: file.header.n_prv_in as usize,
+        })
+    }
+
+    /// Witness positions of the input signals
+    fn labels(&self) -> std::ops::Range<usize> {
+        let first = ONE_WIRE + 1 + self.outputs;
+        first..first + self.public_inputs + self.private_inputs
+    }
+}
+
+/// An input signal of the main component, with the dimensions of array signals
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputSignal {
+    pub name: String,
+    /// Empty for scalar signals
+    pub shape: Vec<usize>,
+    pub public: bool,
+}
+
+impl InputSignal {
+    /// Number of values the signal takes, 1 for scalars
+    pub fn value_count(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// `a scalar` or `an array of shape [2, 3]`
+    pub fn describe(&self) -> String {
+        if self.shape.is_empty() {
+            "a scalar".to_string()
+        } else {
+            format!("an array of shape {:?}", self.shape)
+        }
+    }
+}
+
+/// Collects the input signals of a circuit, in witness order
+///
+/// Array elements such as `main.in[1][2]` are folded into one signal whose shape
+/// covers the largest index seen in each dimension.
+pub fn input_signals(symbols: &SymbolTable, layout: &InputLayout) -> Vec<InputSignal> {
+    let labels = layout.labels();
+    let first_private = labels.start + layout.public_inputs;
+    let mut signals: Vec<InputSignal> = Vec::new();
+    for symbol in &symbols.symbols {
+        if !labels.contains(&symbol.label) {
+            continue;
+        }
+        let full_name = symbol.name.strip_prefix("main.").unwrap_or(&symbol.name);
+        let (name, indices) = split_indices(full_name);
+        let position = match signals.iter().position(|signal| signal.name == name) {
+            Some(position) => position,
+            None => {
+                signals.push(InputSignal {
+                    name: name.to_string(),
+                    shape: vec![0; indices.len()],
+                    public: symbol.label < first_private,
+                });
+                signals.len() - 1
+            }
+        };
+        for (dim, index) in signals[position].shape.iter_mut().zip(indices) {
+            *dim = (*dim).max(index + 1);
+        }
+    }
+    signals
+}
+
+/// Reads the input signals of a compiled circuit from its `.r1cs` and `.sym` files
+pub fn read_input_signals(paths: &CircuitPaths) -> Result<Vec<InputSignal>, TemplateError> {
+    let layout = InputLayout::read(&paths.r1cs)?;
+    let symbols = SymbolTable::read(&paths.sym)?;
+    Ok(input_signals(&symbols, &layout))
+}
+
+/// Splits `in[1][2]` into `in` and `[1, 2]`
+///
+/// Only the indices after the last field are split off, so an element of a bus
+/// array such as `ps[0].x` stays one signal, named as the witness calculator expects.
+fn split_indices(name: &str) -> (&str, Vec<usize>) {
+    let last_field = name.rfind('.').map_or(0, |dot| dot + 1);
+    let Some(open) = name[last_field..].find('[').map(|open| last_field + open) else {
+        return (name, Vec::new());
+    };
+    let indices = name[open..]
+        .split(['[', ']'])
+        .filter_map(|index| index.parse().ok())
+        .collect();
+    (&name[..open], indices)
+}
+
+/// Builds an inputs file with a zero placeholder for every input signal
+pub fn inputs_template(signals: &[InputSignal]) -> Value {
+    let inputs: Map<String, Value> = signals
+        .iter()
+        .map(|signal| (signal.name.clone(), placeholder(&signal.shape)))
+        .collect();
+    Value::Object(inputs)
+}
+
+fn placeholder(shape: &[usize]) -> Value {
+    match shape.split_first() {
+        None => Value::from(0),
+        Some((&len, rest)) => Value::Array(vec![placeholder(rest); len]),
+    }
+}
+
+/// Pattern of a field element written as a string: decimal, possibly negative, or
+/// `0x`-prefixed hex
+pub const FIELD_ELEMENT_PATTERN: &str = "^(-?[0-9]+|0[xX][0-9a-fA-F]+)$";
+
+/// Builds a JSON Schema (draft 2020-12) of the inputs object of a circuit
+///
+/// Every input signal is a required property. Array signals take exactly as many
+/// elements per axis as declared, or one flat array of all their values. Values
+/// are JSON integers, integer strings or booleans, as `inputs::parse_inputs`
+/// accepts; range checks against the modulus are left to the prover. Bus signals
+/// appear in their flattened `name.field` form.
+pub fn inputs_schema(circuit: &str, signals: &[InputSignal]) -> Value {
+    let properties: Map<String, Value> = signals
+        .iter()
+        .map(|signal| {
+            let mut schema = array_schema(&signal.shape);
+            if signal.shape.len() > 1 {
+                schema = json!({
+                    "anyOf": [schema, array_schema(&[signal.value_count()])]
+                });
+            }
+            let visibility = if signal.public { "Public" } else { "Private" };
+            schema["description"] =
+                Value::from(format!("{visibility} input, {}", signal.describe()));
+            (signal.name.clone(), schema)
+        })
+        .collect();
+    let required: Vec<&str> = signals.iter().map(|signal| signal.name.as_str()).collect();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": format!("Inputs of {circuit}"),
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+        "$defs": {
+            "field": {
+                "anyOf": [
+                    {"type": "string", "pattern": FIELD_ELEMENT_PATTERN},
+                    {"type": "integer"},
+                    {"type": "boolean"}
+                ]
+            }
+        }
+    })
+}
+
+fn array_schema(shape: &[usize]) -> Value {
+    match shape.split_first() {
+        None => json!({"$ref": "#/$defs/field"}),
+        Some((&len, rest)) => json!({
+            "type": "array",
+            "items": array_schema(rest),
+            "minItems": len,
+            "maxItems": len
+        }),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TemplateError {
+    #[error("Failed to read r1cs: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse r1cs: {0}")]
+    R1cs(String),
+    #[error(transparent)]
+    Sym(#[from] SymError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_array_signals() {
+        let symbols = SymbolTable::parse(
+            "1,1,0,main.out\n2,2,0,main.key\n3,3,0,main.in[0][0]\n4,4,0,main.in[0][1]\n\
+             5,5,0,main.in[1][0]\n6,6,0,main.in[1][1]\n7,7,0,main.tmp\n",
+        )
+        .unwrap();
+        let layout = InputLayout {
+            outputs: 1,
+            public_inputs: 1,
+            private_inputs: 4,
+        };
+
+        let signals = input_signals(&symbols, &layout);
+        assert_eq!(
+            signals,
+            vec![
+                InputSignal {
+                    name: "key".into(),
+                    shape: vec![],
+                    public: true
+                },
+                InputSignal {
+                    name: "in".into(),
+                    shape: vec![2, 2],
+                    public: false
+                },
+            ]
+        );
+        assert_eq!(
+            inputs_template(&signals),
+            serde_json::json!({"key": 0, "in": [[0, 0], [0, 0]]})
+        );
+    }
+
+    #[test]
+    fn describes_inputs_as_a_json_schema() {
+        let signals = [
+            InputSignal {
+                name: "key".into(),
+                shape: vec![],
+                public: true,
+            },
+            InputSignal {
+                name: "in".into(),
+                shape: vec![2, 3],
+                public: false,
+            },
+        ];
+        let schema = inputs_schema("matrix", &signals);
+        assert_eq!(schema["required"], serde_json::json!(["key", "in"]));
+        assert_eq!(schema["properties"]["key"]["$ref"], "#/$defs/field");
+        assert_eq!(
+            schema["properties"]["key"]["description"],
+            "Public input, a scalar"
+        );
+        let nested = &schema["properties"]["in"]["anyOf"][0];
+        assert_eq!(nested["maxItems"], 2);
+        assert_eq!(nested["items"]["minItems"], 3);
+        assert_eq!(schema["properties"]["in"]["anyOf"][1]["maxItems"], 6);
+    }
+
+    #[test]
+    fn bus_fields_keep_their_own_indices() {
+        assert_eq!(split_indices("in[1][2]"), ("in", vec![1, 2]));
+        assert_eq!(split_indices("ps[1].x"), ("ps[1].x", vec![]));
+        assert_eq!(split_indices("p.y[3]"), ("p.y", vec![3]));
+    }
+}
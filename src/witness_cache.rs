@@ -0,0 +1,162 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+
+use crate::{atomic, wtns};
+
+/// Witnesses computed earlier, stored as `.wtns` files named by what they were
+/// computed from
+///
+/// A witness is a function of the calculator and its inputs only, so the key is
+/// the hash of the wasm bytes and of the canonical inputs. Proving the same inputs
+/// again, as retries and tests do, then skips the witness calculator.
+///
+/// Witnesses hold every private signal, so on Unix the directory must belong to
+/// the current user and be closed to everyone else: it is created with mode 0700,
+/// entries are written with mode 0600, and a directory failing the check is
+/// neither read nor written. Nobody else can read the witnesses or plant entries.
+#[derive(Debug, Clone)]
+pub struct WitnessCache {
+    pub dir: PathBuf,
+}
+
+impl WitnessCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `<temp>/circom-bp-witnesses-<uid>` on Unix, so every user has their own
+    /// directory, and `<temp>/circom-bp-witnesses` elsewhere
+    pub fn default_dir() -> PathBuf {
+        #[cfg(unix)]
+        // SAFETY: geteuid has no preconditions and cannot fail
+        let name = format!("circom-bp-witnesses-{}", unsafe { libc::geteuid() });
+        #[cfg(not(unix))]
+        let name = "circom-bp-witnesses".to_string();
+        std::env::temp_dir().join(name)
+    }
+
+    /// Hex cache key for running the calculator at `wasm` over `inputs`
+    ///
+    /// Inputs are hashed in order as `name ‖ 0 ‖ value` with values in their
+    /// canonical little-endian form, so `"0x1f"` and `31` share an entry.
+    pub fn key(wasm: impl AsRef<Path>, inputs: &[(String, Fr)]) -> std::io::Result<String> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(blake3::hash(&std::fs::read(wasm)?).as_bytes());
+        for (name, value) in inputs {
+            hasher.update(name.as_bytes());
+            hasher.update(&[0]);
+            hasher.update(&value.into_bigint().to_bytes_le());
+        }
+        Ok(hasher.finalize().to_hex().to_string())
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.wtns"))
+    }
+
+    /// The cached witness for `key`, if any; unreadable entries, and every entry
+    /// of a directory others could write to, count as misses
+    pub fn get(&self, key: &str) -> Option<Vec<Fr>> {
+        check_private(&self.dir).ok()?;
+        wtns::read_wtns(self.path(key)).ok()
+    }
+
+    /// Stores a witness readable by the current user only, creating the directory
+    /// if needed; a directory others could access is refused
+    pub fn put(&self, key: &str, witness: &[Fr]) -> io::Result<()> {
+        let mut builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        builder.recursive(true).create(&self.dir)?;
+        check_private(&self.dir)?;
+        atomic::write_with_mode(self.path(key), wtns::encode_wtns(witness), 0o600)
+    }
+}
+
+/// Fails unless `dir` is a directory, not a symlink, owned by the current user and
+/// without any permissions for group or others
+#[cfg(unix)]
+fn check_private(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let meta = std::fs::symlink_metadata(dir)?;
+    // SAFETY: geteuid has no preconditions and cannot fail
+    let uid = unsafe { libc::geteuid() };
+    if !meta.is_dir() || meta.uid() != uid || meta.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} is not a directory private to the current user",
+                dir.display()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_private(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_follow_wasm_and_canonical_inputs() {
+        let dir =
+            std::env::temp_dir().join(format!("circom-bp-wcache-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let wasm = dir.join("circuit.wasm");
+        std::fs::write(&wasm, b"\0asm v1").unwrap();
+        let inputs = |b: u64| {
+            vec![
+                ("a".to_string(), Fr::from(3u64)),
+                ("b".to_string(), Fr::from(b)),
+            ]
+        };
+
+        let key = WitnessCache::key(&wasm, &inputs(11)).unwrap();
+        assert_eq!(key, WitnessCache::key(&wasm, &inputs(11)).unwrap());
+        assert_ne!(key, WitnessCache::key(&wasm, &inputs(12)).unwrap());
+        std::fs::write(&wasm, b"\0asm v2").unwrap();
+        assert_ne!(key, WitnessCache::key(&wasm, &inputs(11)).unwrap());
+
+        let cache = WitnessCache::new(dir.join("cache"));
+        assert_eq!(cache.get(&key), None);
+        let witness = [1u64, 33, 3, 11].map(Fr::from);
+        cache.put(&key, &witness).unwrap();
+        assert_eq!(cache.get(&key), Some(witness.to_vec()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn entries_are_private_to_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("circom-bp-wcache-perms-{}", std::process::id()));
+        let cache = WitnessCache::new(dir.join("cache"));
+        let witness = [1u64, 9, 3].map(Fr::from);
+        cache.put("key", &witness).unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&cache.dir), 0o700);
+        assert_eq!(mode(&cache.dir.join("key.wtns")), 0o600);
+        assert_eq!(cache.get("key"), Some(witness.to_vec()));
+
+        // Entries in a directory others can write to may have been planted
+        std::fs::set_permissions(&cache.dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert_eq!(cache.get("key"), None);
+        assert!(cache.put("key", &witness).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,224 @@
+use std::io::Read;
+use std::path::Path;
+
+use ark_ff::{BigInteger, PrimeField};
+
+use crate::atomic;
+
+const MAGIC: &[u8; 4] = b"wtns";
+const HEADER_SECTION: u32 = 1;
+const WITNESS_SECTION: u32 = 2;
+
+/// Reads a snarkjs `.wtns` witness file
+///
+/// The file is an iden3 binary container: a `wtns` magic, a version, then sections
+/// of `(type: u32, size: u64)`. Section 1 holds the field element width, the prime
+/// and the witness length; section 2 the little-endian witness values.
+pub fn read_wtns<F: PrimeField>(path: impl AsRef<Path>) -> Result<Vec<F>, WtnsError> {
+    parse_wtns(&std::fs::read(path)?)
+}
+
+pub fn parse_wtns<F: PrimeField>(bytes: &[u8]) -> Result<Vec<F>, WtnsError> {
+    let mut reader = bytes;
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(WtnsError::BadMagic);
+    }
+    let _version = read_u32(&mut reader)?;
+    let num_sections = read_u32(&mut reader)?;
+
+    let mut header = None;
+    let mut witness_section = None;
+    for _ in 0..num_sections {
+        let section_type = read_u32(&mut reader)?;
+        let size = usize::try_from(read_u64(&mut reader)?).map_err(|_| WtnsError::Truncated)?;
+        if reader.len() < size {
+            return Err(WtnsError::Truncated);
+        }
+        let (section, rest) = reader.split_at(size);
+        reader = rest;
+        match section_type {
+            HEADER_SECTION => header = Some(parse_header::<F>(section)?),
+            WITNESS_SECTION => witness_section = Some(section),
+            _ => {}
+        }
+    }
+
+    let (n8, num_witness) = header.ok_or(WtnsError::MissingSection(HEADER_SECTION))?;
+    let section = witness_section.ok_or(WtnsError::MissingSection(WITNESS_SECTION))?;
+    if n8.checked_mul(num_witness) != Some(section.len()) {
+        return Err(WtnsError::Truncated);
+    }
+    Ok(section
+        .chunks_exact(n8)
+        .map(F::from_le_bytes_mod_order)
+        .collect())
+}
+
+/// Writes a witness as a `.wtns` file, e.g. to cross-check it with snarkjs
+pub fn write_wtns<F: PrimeField>(path: impl AsRef<Path>, witness: &[F]) -> std::io::Result<()> {
+    atomic::write(path, encode_wtns(witness))
+}
+
+/// Encodes a witness as a version 2 `.wtns` file, readable by snarkjs
+pub fn encode_wtns<F: PrimeField>(witness: &[F]) -> Vec<u8> {
+    let prime = F::MODULUS.to_bytes_le();
+    let n8 = prime.len();
+    let mut header = Vec::with_capacity(8 + n8);
+    header.extend((n8 as u32).to_le_bytes());
+    header.extend(&prime);
+    header.extend((witness.len() as u32).to_le_bytes());
+    let mut values = Vec::with_capacity(n8 * witness.len());
+    for value in witness {
+        let mut bytes = value.into_bigint().to_bytes_le();
+        bytes.resize(n8, 0);
+        values.extend(bytes);
+    }
+
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(2u32.to_le_bytes());
+    bytes.extend(2u32.to_le_bytes());
+    for (section_type, section) in [(HEADER_SECTION, header), (WITNESS_SECTION, values)] {
+        bytes.extend(section_type.to_le_bytes());
+        bytes.extend((section.len() as u64).to_le_bytes());
+        bytes.extend(section);
+    }
+    bytes
+}
+
+/// Returns the element width and witness length after checking the prime matches `F`
+///
+/// The width comes from the file, so it is checked against the section before
+/// anything is allocated for it.
+fn parse_header<F: PrimeField>(mut section: &[u8]) -> Result<(usize, usize), WtnsError> {
+    let width = read_u32(&mut section)?;
+    let n8 = width as usize;
+    // The prime and the witness length follow
+    if n8 == 0 || section.len().saturating_sub(4) < n8 {
+        return Err(WtnsError::ElementWidth(width));
+    }
+    let mut prime = vec![0u8; n8];
+    section.read_exact(&mut prime)?;
+    let num_witness = read_u32(&mut section)? as usize;
+
+    let mut expected = F::MODULUS.to_bytes_le();
+    expected.resize(n8.max(expected.len()), 0);
+    prime.resize(expected.len(), 0);
+    if prime != expected {
+        let be_hex = |le: &[u8]| {
+            let be: Vec<u8> = le.iter().rev().copied().collect();
+            format!("0x{}", hex::encode(be).trim_start_matches('0'))
+        };
+        return Err(WtnsError::FieldMismatch {
+            prime: be_hex(&prime),
+            expected: be_hex(&expected),
+        });
+    }
+    Ok((n8, num_witness))
+}
+
+fn read_u32(reader: &mut &[u8]) -> std::io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(reader: &mut &[u8]) -> std::io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WtnsError {
+    #[error("Failed to read witness file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a .wtns file")]
+    BadMagic,
+    #[error("Witness file is truncated")]
+    Truncated,
+    #[error("Witness file declares {0}-byte field elements, which its header cannot hold")]
+    ElementWidth(u32),
+    #[error("Witness file has no section {0}")]
+    MissingSection(u32),
+    #[error("Witness file was computed over prime {prime}, not the scalar field {expected}")]
+    FieldMismatch { prime: String, expected: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+
+    fn wtns_bytes(prime: &[u8], values: &[u64]) -> Vec<u8> {
+        let n8 = prime.len();
+        let mut header = Vec::new();
+        header.extend((n8 as u32).to_le_bytes());
+        header.extend(prime);
+        header.extend((values.len() as u32).to_le_bytes());
+        let mut witness = Vec::new();
+        for value in values {
+            let mut bytes = value.to_le_bytes().to_vec();
+            bytes.resize(n8, 0);
+            witness.extend(bytes);
+        }
+
+        let mut bytes = b"wtns".to_vec();
+        bytes.extend(2u32.to_le_bytes());
+        bytes.extend(2u32.to_le_bytes());
+        for (section_type, section) in [(1u32, header), (2u32, witness)] {
+            bytes.extend(section_type.to_le_bytes());
+            bytes.extend((section.len() as u64).to_le_bytes());
+            bytes.extend(section);
+        }
+        bytes
+    }
+
+    #[test]
+    fn parses_witness_values() {
+        let bytes = wtns_bytes(&Fr::MODULUS.to_bytes_le(), &[1, 33, 3, 11]);
+        let witness = parse_wtns::<Fr>(&bytes).unwrap();
+        assert_eq!(witness, [1u64, 33, 3, 11].map(Fr::from).to_vec());
+    }
+
+    #[test]
+    fn encoded_witness_round_trips() {
+        let witness = [1u64, 33, 3, 11].map(Fr::from).to_vec();
+        let bytes = encode_wtns(&witness);
+        assert_eq!(
+            bytes,
+            wtns_bytes(&Fr::MODULUS.to_bytes_le(), &[1, 33, 3, 11])
+        );
+        assert_eq!(parse_wtns::<Fr>(&bytes).unwrap(), witness);
+    }
+
+    #[test]
+    fn rejects_other_fields() {
+        let mut prime = Fr::MODULUS.to_bytes_le();
+        prime[0] ^= 1;
+        assert!(matches!(
+            parse_wtns::<Fr>(&wtns_bytes(&prime, &[1])),
+            Err(WtnsError::FieldMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_element_widths_beyond_the_header() {
+        let mut bytes = wtns_bytes(&Fr::MODULUS.to_bytes_le(), &[1]);
+        // The width right after the header section's type and size
+        for width in [u32::MAX, 0] {
+            bytes[24..28].copy_from_slice(&width.to_le_bytes());
+            assert!(matches!(
+                parse_wtns::<Fr>(&bytes),
+                Err(WtnsError::ElementWidth(w)) if w == width
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let bytes = wtns_bytes(&Fr::MODULUS.to_bytes_le(), &[1, 2]);
+        assert!(parse_wtns::<Fr>(&bytes[..bytes.len() - 1]).is_err());
+    }
+}